@@ -2,29 +2,130 @@ import type { Principal } from '@dfinity/principal';
 import type { ActorMethod } from '@dfinity/agent';
 import type { IDL } from '@dfinity/candid';
 
+export type ActivityType = { 'ExplicitLogin' : null } |
+  { 'AnyActivity' : null };
 export interface AddOrUpdateUserArgs {
   'user_type' : [] | [UserType],
   'name' : [] | [string],
   'email' : [] | [string],
 }
+export interface AnswerBeneficiaryChallengeArgs {
+  'answer' : string,
+  'policy_id' : string,
+}
+export interface ApplyConditionTemplateArgs {
+  'template_id' : string,
+  'linked' : boolean,
+  'policy_id' : string,
+}
+export interface BeneficiaryChallengeStatus {
+  'failed_attempts' : number,
+  'question' : string,
+  'locked_until' : [] | [bigint],
+  'lockouts' : number,
+  'token_expires_at' : [] | [bigint],
+}
+export interface BeneficiaryClaim {
+  'claimant' : string,
+  'filed_at' : bigint,
+  'response_deadline' : bigint,
+}
+export interface BeneficiaryClaimCondition {
+  'id' : string,
+  'pending_claim' : [] | [BeneficiaryClaim],
+  'condition_status' : boolean,
+  'last_claims' : Array<[string, bigint]>,
+  'response_period_days' : bigint,
+}
+export interface ChallengeToken { 'token' : string, 'expires_at' : bigint }
+export interface CheckInCondition {
+  'id' : string,
+  'next_deadline' : bigint,
+  'condition_status' : boolean,
+  'last_check_in' : bigint,
+  'grace_period_days' : bigint,
+  'schedule' : CheckInSchedule,
+}
+export type CheckInSchedule = { 'DayOfMonth' : number } |
+  { 'EveryNumberOfDays' : bigint };
+export interface CommitXOutOfYVoteArgs {
+  'condition_id' : string,
+  'policy_id' : string,
+  'commitment' : string,
+}
 export type Condition = { 'LastLogin' : LastLoginTimeCondition } |
   { 'FixedDateTime' : FixedDateTimeCondition } |
-  { 'XOutOfY' : XOutOfYCondition };
+  { 'PolicyDependency' : PolicyDependencyCondition } |
+  { 'Oracle' : OracleCondition } |
+  { 'DeadMansSwitch' : DeadMansSwitchCondition } |
+  { 'XOutOfY' : XOutOfYCondition } |
+  { 'BeneficiaryClaim' : BeneficiaryClaimCondition } |
+  { 'CheckIn' : CheckInCondition };
+export type ConditionExpression = { 'Or' : Array<ConditionExpression> } |
+  { 'And' : Array<ConditionExpression> } |
+  { 'Not' : ConditionExpression } |
+  { 'Condition' : string };
+export interface ConditionForecast {
+  'status' : boolean,
+  'met_at' : [] | [bigint],
+  'condition_id' : string,
+}
+export interface ConditionReset {
+  'date' : bigint,
+  'condition_id' : [] | [string],
+  'reason' : ConditionResetReason,
+}
+export type ConditionResetReason = { 'OwnerReset' : null } |
+  { 'ConditionEdited' : null } |
+  { 'ValidatorRejection' : null };
+export interface ConditionTemplate {
+  'id' : string,
+  'date_created' : bigint,
+  'owner' : [] | [string],
+  'name' : string,
+  'conditions_logical_operator' : [] | [LogicalOperator],
+  'description' : [] | [string],
+  'conditions' : Array<UpdateCondition>,
+  'date_modified' : bigint,
+  'conditions_expression' : [] | [UpdateConditionExpression],
+}
+export interface ConditionTemplateUpdate {
+  'template' : ConditionTemplate,
+  'unlinked_policies' : Array<string>,
+}
+export interface ConfirmDeadMansSwitchArgs {
+  'status' : boolean,
+  'owner' : string,
+  'condition_id' : string,
+}
 export interface ConfirmXOutOfYConditionArgs {
   'status' : boolean,
+  'evidence' : [] | [Array<Evidence>],
   'condition_id' : string,
   'policy_id' : string,
+  'evidence_visibility' : [] | [EvidenceVisibility],
+  'reason' : [] | [string],
 }
 export interface Contact {
   'id' : string,
   'user_type' : [] | [UserType],
   'name' : [] | [string],
+  'role' : [] | [ContactRole],
   'email' : [] | [string],
 }
+export type ContactRole = { 'DeadlineExtender' : null };
+export interface CreateConditionTemplateArgs {
+  'name' : string,
+  'conditions_logical_operator' : [] | [LogicalOperator],
+  'description' : [] | [string],
+  'conditions' : Array<UpdateCondition>,
+  'conditions_expression' : [] | [UpdateConditionExpression],
+}
 export interface CreateContactArgs {
   'id' : string,
   'user_type' : [] | [UserType],
   'name' : [] | [string],
+  'role' : [] | [ContactRole],
   'email' : [] | [string],
 }
 export interface CreatePolicyArgs { 'name' : [] | [string] }
@@ -37,37 +138,102 @@ export interface CreateSecretArgs {
   'notes' : [] | [Uint8Array | number[]],
   'category' : [] | [SecretCategory],
 }
+export interface DeadMansSwitch {
+  'conditions_logical_operator' : [] | [LogicalOperator],
+  'triggered_at' : [] | [bigint],
+  'conditions' : Array<Condition>,
+  'date_modified' : bigint,
+}
+export interface DeadMansSwitchCondition {
+  'id' : string,
+  'condition_status' : boolean,
+}
+export interface DeadMansSwitchForValidator {
+  'owner' : string,
+  'conditions' : Array<Condition>,
+}
+export interface DeadlineExtension {
+  'date' : bigint,
+  'days' : bigint,
+  'extended_by' : string,
+}
+export interface Duration { 'value' : bigint, 'unit' : DurationUnit }
+export type DurationUnit = { 'Days' : null } |
+  { 'Weeks' : null } |
+  { 'Years' : null } |
+  { 'Hours' : null } |
+  { 'Months' : null };
+export interface Evidence {
+  'encrypted_content' : Uint8Array | number[],
+  'name' : [] | [string],
+}
+export type EvidenceVisibility = { 'Validators' : null } |
+  { 'Beneficiaries' : null };
+export interface ExtendDeadlineArgs { 'owner' : string, 'days' : bigint }
+export interface FileClaimArgs { 'condition_id' : string, 'policy_id' : string }
 export interface FixedDateTimeCondition {
   'id' : string,
+  'time_zone' : [] | [TimeZone],
   'condition_status' : boolean,
+  'end_datetime' : [] | [bigint],
   'datetime' : bigint,
 }
+export interface ForecastPolicyArgs {
+  'now' : [] | [bigint],
+  'last_login' : [] | [bigint],
+  'votes' : Array<SimulatedVote>,
+  'policy_id' : string,
+}
 export interface LastLoginTimeCondition {
   'id' : string,
+  'activity_type' : [] | [ActivityType],
   'condition_status' : boolean,
-  'number_of_days_since_last_login' : bigint,
+  'inactivity' : [] | [Duration],
+  'number_of_days_since_last_login' : [] | [bigint],
 }
 export type LogicalOperator = { 'Or' : null } |
   { 'And' : null };
-export interface Policy {
+export interface OracleCondition {
   'id' : string,
-  'date_created' : bigint,
-  'owner' : string,
-  'name' : [] | [string],
-  'conditions_logical_operator' : [] | [LogicalOperator],
-  'secrets' : Array<string>,
-  'conditions_status' : boolean,
-  'beneficiaries' : Array<string>,
-  'key_box' : Array<[string, Uint8Array | number[]]>,
-  'conditions' : Array<Condition>,
-  'date_modified' : bigint,
+  'method' : string,
+  'last_call_at' : [] | [bigint],
+  'condition_status' : boolean,
+  'canister_id' : string,
+  'max_days_inactive' : [] | [bigint],
+  'last_reply' : [] | [OracleReply],
+  'call_pending' : boolean,
+  'last_reply_at' : [] | [bigint],
+  'last_failure' : [] | [OracleFailure],
+}
+export interface OracleFailure { 'date' : bigint, 'message' : string }
+export type OracleReply = { 'LastActivity' : bigint } |
+  { 'Status' : boolean };
+export interface PolicyDependencyCondition {
+  'id' : string,
+  'condition_status' : boolean,
+  'delay_days' : [] | [bigint],
+  'dependency_met_at' : [] | [bigint],
+  'policy_id' : string,
 }
 export interface PolicyForValidator {
   'id' : string,
   'owner' : string,
+  'owner_vacations' : Array<Vacation>,
   'conditions' : Array<Condition>,
+  'voting_deadlines' : Array<VotingDeadline>,
+}
+export interface PolicyForecast {
+  'now' : bigint,
+  'release_date' : [] | [bigint],
+  'conditions_logical_operator' : [] | [LogicalOperator],
+  'conditions_status' : boolean,
+  'conditions_met_at' : [] | [bigint],
+  'conditions' : Array<ConditionForecast>,
+  'policy_id' : string,
+  'conditions_expression' : [] | [ConditionExpression],
 }
 export interface PolicyKeyDerviationArgs {
+  'challenge_token' : [] | [string],
   'encryption_public_key' : Uint8Array | number[],
   'policy_id' : string,
 }
@@ -75,32 +241,77 @@ export interface PolicyListEntry {
   'id' : string,
   'owner' : string,
   'name' : [] | [string],
+  'released' : boolean,
   'conditions_status' : boolean,
 }
 export interface PolicyWithSecretListEntries {
   'id' : string,
+  'not_before' : [] | [bigint],
   'date_created' : bigint,
+  'release_date' : [] | [bigint],
   'owner' : string,
+  'condition_resets' : Array<ConditionReset>,
   'name' : [] | [string],
   'conditions_logical_operator' : [] | [LogicalOperator],
   'secrets' : Array<SecretListEntry>,
+  'release_stages' : Array<ReleaseStage>,
+  'released' : boolean,
+  'cooling_off_period_days' : [] | [bigint],
   'conditions_status' : boolean,
+  'beneficiary_challenges' : Array<[string, BeneficiaryChallengeStatus]>,
+  'condition_template' : [] | [string],
   'beneficiaries' : Array<string>,
+  'vetoers' : Array<string>,
   'key_box' : Array<[string, Uint8Array | number[]]>,
   'conditions' : Array<Condition>,
   'date_modified' : bigint,
+  'expires_at' : [] | [bigint],
+  'beneficiary_secrets' : Array<[string, Array<string>]>,
+  'conditions_expression' : [] | [ConditionExpression],
+}
+export interface QuorumHealth {
+  'quorum_type' : [] | [QuorumType],
+  'total_weight' : bigint,
+  'reachable_weight' : bigint,
+  'quorum_unreachable_since' : [] | [bigint],
+  'quorum_reachable' : boolean,
+  'condition_id' : string,
+  'quorum' : bigint,
+  'backup_validators' : bigint,
+}
+export type QuorumType = { 'Absolute' : null } |
+  { 'Percentage' : null };
+export interface ReleaseStage {
+  'not_before' : [] | [bigint],
+  'name' : [] | [string],
+  'secrets' : Array<string>,
+  'delay_days' : [] | [bigint],
 }
 export type Result = { 'Ok' : null } |
   { 'Err' : SmartVaultErr };
 export type Result_1 = { 'Ok' : Contact } |
   { 'Err' : SmartVaultErr };
-export type Result_10 = { 'Ok' : Array<PolicyListEntry> } |
+export type Result_10 = { 'Ok' : Array<PolicyForValidator> } |
+  { 'Err' : SmartVaultErr };
+export type Result_11 = { 'Ok' : Array<SecretListEntry> } |
   { 'Err' : SmartVaultErr };
-export type Result_11 = { 'Ok' : Array<PolicyForValidator> } |
+export type Result_12 = { 'Ok' : PolicyForecast } |
   { 'Err' : SmartVaultErr };
-export type Result_12 = { 'Ok' : Array<SecretListEntry> } |
+export type Result_13 = { 'Ok' : Array<QuorumHealth> } |
   { 'Err' : SmartVaultErr };
-export type Result_2 = { 'Ok' : Policy } |
+export type Result_14 = { 'Ok' : ConditionTemplate } |
+  { 'Err' : SmartVaultErr };
+export type Result_15 = { 'Ok' : Array<ConditionTemplate> } |
+  { 'Err' : SmartVaultErr };
+export type Result_16 = { 'Ok' : DeadlineExtension } |
+  { 'Err' : SmartVaultErr };
+export type Result_17 = { 'Ok' : Array<DeadMansSwitchForValidator> } |
+  { 'Err' : SmartVaultErr };
+export type Result_18 = { 'Ok' : ChallengeToken } |
+  { 'Err' : SmartVaultErr };
+export type Result_19 = { 'Ok' : ConditionTemplateUpdate } |
+  { 'Err' : SmartVaultErr };
+export type Result_2 = { 'Ok' : PolicyWithSecretListEntries } |
   { 'Err' : SmartVaultErr };
 export type Result_3 = { 'Ok' : Secret } |
   { 'Err' : SmartVaultErr };
@@ -112,10 +323,19 @@ export type Result_6 = { 'Ok' : Array<Contact> } |
   { 'Err' : SmartVaultErr };
 export type Result_7 = { 'Ok' : Uint8Array | number[] } |
   { 'Err' : SmartVaultErr };
-export type Result_8 = { 'Ok' : PolicyWithSecretListEntries } |
+export type Result_8 = { 'Ok' : PolicyForValidator } |
   { 'Err' : SmartVaultErr };
-export type Result_9 = { 'Ok' : PolicyForValidator } |
+export type Result_9 = { 'Ok' : Array<PolicyListEntry> } |
   { 'Err' : SmartVaultErr };
+export interface RevealXOutOfYVoteArgs {
+  'status' : boolean,
+  'salt' : string,
+  'evidence' : [] | [Array<Evidence>],
+  'condition_id' : string,
+  'policy_id' : string,
+  'evidence_visibility' : [] | [EvidenceVisibility],
+  'reason' : [] | [string],
+}
 export interface Secret {
   'id' : string,
   'url' : [] | [string],
@@ -136,50 +356,152 @@ export interface SecretListEntry {
   'name' : [] | [string],
   'category' : [] | [SecretCategory],
 }
+export interface SetBeneficiaryChallengeArgs {
+  'question' : string,
+  'beneficiary' : string,
+  'answer' : string,
+  'policy_id' : string,
+}
+export interface SimulatedVote {
+  'status' : boolean,
+  'validator' : string,
+  'condition_id' : string,
+}
 export type SmartVaultErr = { 'ContactDoesNotExist' : string } |
   { 'UserAlreadyExists' : string } |
   { 'OnlyOwnerCanDeleteSecret' : string } |
   { 'PolicyConditionDoesNotExist' : string } |
   { 'SecretHasNoId' : null } |
+  { 'ClaimAlreadyPending' : string } |
+  { 'InvalidDeadMansSwitch' : string } |
+  { 'PolicyExpired' : string } |
   { 'UserDeletionFailed' : string } |
   { 'KeyBoxEntryDoesNotExistForSecret' : string } |
   { 'ContactAlreadyExists' : string } |
   { 'CallerNotBeneficiary' : string } |
   { 'InvalidQuorum' : [string, string] } |
+  { 'CallerNotVetoer' : string } |
+  { 'ChallengeRateLimited' : string } |
+  { 'InvalidCheckInSchedule' : string } |
+  { 'InvalidReleaseStage' : string } |
+  { 'SecretNotReleased' : string } |
+  { 'PolicyReleaseNotPending' : string } |
+  { 'ConditionTemplateDoesNotExist' : string } |
   { 'SecretDoesNotExist' : string } |
+  { 'OracleNotAllowed' : string } |
   { 'NoPolicyForBeneficiary' : string } |
   { 'CallerNotPolicyOwner' : string } |
+  { 'CallerNotDeadlineExtender' : string } |
+  { 'InvalidConditionTemplate' : string } |
+  { 'InvalidConditionExpression' : string } |
+  { 'InvalidVotingPeriod' : string } |
   { 'SecretEntryDoesNotExistForKeyBoxEntry' : string } |
   { 'InvalidDateTime' : string } |
+  { 'InvalidVacation' : string } |
+  { 'BeneficiaryChallengeRequired' : string } |
   { 'Unauthorized' : null } |
+  { 'InvalidOracleCondition' : string } |
+  { 'WrongChallengeAnswer' : string } |
   { 'UserUpdateFailed' : string } |
   { 'LogicalOperatorWithLessThanTwoConditions' : null } |
   { 'NoPolicyForValidator' : string } |
+  { 'InvalidBeneficiaryChallenge' : string } |
+  { 'DeadMansSwitchInUse' : string } |
+  { 'InvalidBeneficiarySecrets' : string } |
   { 'PolicyAlreadyExists' : string } |
+  { 'InvalidValidatorWeight' : string } |
+  { 'InvalidBackupValidator' : string } |
   { 'PolicyDoesNotExist' : string } |
   { 'UserDoesNotExist' : string } |
+  { 'InvalidVoteCommitment' : string } |
+  { 'SecretNotAssigned' : string } |
   { 'SecretAlreadyExists' : string } |
+  { 'InvalidVoteEvidence' : string } |
+  { 'PolicyDependencyCycle' : string } |
   { 'InvalidPolicyCondition' : null } |
-  { 'KeyGenerationNotAllowed' : null };
+  { 'InvalidResponsePeriod' : string } |
+  { 'KeyGenerationNotAllowed' : null } |
+  { 'BeneficiaryChallengeLocked' : string } |
+  { 'InvalidDeadlineExtension' : string } |
+  { 'ClaimRateLimited' : string };
+export interface TimeZone {
+  'name' : [] | [string],
+  'utc_offset_minutes' : number,
+}
+export interface UpdateBeneficiaryClaimCondition {
+  'id' : [] | [string],
+  'response_period_days' : bigint,
+}
+export interface UpdateCheckInCondition {
+  'id' : [] | [string],
+  'grace_period_days' : bigint,
+  'schedule' : CheckInSchedule,
+}
 export type UpdateCondition = { 'LastLogin' : UpdateLastLoginTimeCondition } |
   { 'FixedDateTime' : UpdateFixedDateTimeCondition } |
-  { 'XOutOfY' : UpdateXOutOfYCondition };
+  { 'PolicyDependency' : UpdatePolicyDependencyCondition } |
+  { 'Oracle' : UpdateOracleCondition } |
+  { 'DeadMansSwitch' : UpdateDeadMansSwitchCondition } |
+  { 'XOutOfY' : UpdateXOutOfYCondition } |
+  { 'BeneficiaryClaim' : UpdateBeneficiaryClaimCondition } |
+  { 'CheckIn' : UpdateCheckInCondition };
+export type UpdateConditionExpression = {
+    'Or' : Array<UpdateConditionExpression>
+  } |
+  { 'And' : Array<UpdateConditionExpression> } |
+  { 'Not' : UpdateConditionExpression } |
+  { 'Condition' : bigint };
+export interface UpdateConditionTemplateArgs {
+  'id' : string,
+  'name' : string,
+  'conditions_logical_operator' : [] | [LogicalOperator],
+  'description' : [] | [string],
+  'conditions' : Array<UpdateCondition>,
+  'conditions_expression' : [] | [UpdateConditionExpression],
+}
+export interface UpdateDeadMansSwitchArgs {
+  'conditions_logical_operator' : [] | [LogicalOperator],
+  'conditions' : Array<UpdateCondition>,
+}
+export interface UpdateDeadMansSwitchCondition { 'id' : [] | [string] }
 export interface UpdateFixedDateTimeCondition {
   'id' : [] | [string],
+  'time_zone' : [] | [TimeZone],
+  'end_datetime' : [] | [bigint],
   'datetime' : bigint,
 }
 export interface UpdateLastLoginTimeCondition {
   'id' : [] | [string],
-  'number_of_days_since_last_login' : bigint,
+  'activity_type' : [] | [ActivityType],
+  'inactivity' : [] | [Duration],
+  'number_of_days_since_last_login' : [] | [bigint],
+}
+export interface UpdateOracleCondition {
+  'id' : [] | [string],
+  'method' : string,
+  'canister_id' : string,
+  'max_days_inactive' : [] | [bigint],
 }
 export interface UpdatePolicyArgs {
   'id' : string,
+  'not_before' : [] | [bigint],
   'name' : [] | [string],
   'conditions_logical_operator' : [] | [LogicalOperator],
   'secrets' : Array<string>,
+  'release_stages' : [] | [Array<ReleaseStage>],
+  'cooling_off_period_days' : [] | [bigint],
   'beneficiaries' : Array<string>,
+  'vetoers' : [] | [Array<string>],
   'key_box' : Array<[string, Uint8Array | number[]]>,
   'conditions' : Array<UpdateCondition>,
+  'expires_at' : [] | [bigint],
+  'beneficiary_secrets' : [] | [Array<[string, Array<string>]>],
+  'conditions_expression' : [] | [UpdateConditionExpression],
+}
+export interface UpdatePolicyDependencyCondition {
+  'id' : [] | [string],
+  'delay_days' : [] | [bigint],
+  'policy_id' : string,
 }
 export interface UpdateSecretArgs {
   'id' : string,
@@ -192,46 +514,122 @@ export interface UpdateSecretArgs {
 }
 export interface UpdateXOutOfYCondition {
   'id' : [] | [string],
+  'voting_window_days' : [] | [bigint],
   'question' : string,
+  'quorum_type' : [] | [QuorumType],
+  'clear_votes_on_activity' : [] | [ActivityType],
+  'secret_ballot_days' : [] | [bigint],
   'quorum' : bigint,
+  'vote_validity_days' : [] | [bigint],
   'validators' : Array<Validator>,
+  'backup_validators' : Array<Validator>,
+  'reject_quorum' : [] | [bigint],
 }
 export interface User {
   'id' : string,
   'user_type' : [] | [UserType],
   'date_created' : bigint,
   'contacts' : Array<Contact>,
+  'dead_mans_switch' : [] | [DeadMansSwitch],
   'name' : [] | [string],
   'secrets' : Array<string>,
   'date_last_login' : [] | [bigint],
   'email' : [] | [string],
+  'date_last_activity' : [] | [bigint],
+  'deadline_extensions' : [] | [Array<DeadlineExtension>],
   'key_box' : Array<[string, Uint8Array | number[]]>,
   'date_modified' : bigint,
+  'vacations' : [] | [Array<Vacation>],
   'policies' : Array<string>,
 }
 export type UserType = { 'Company' : null } |
   { 'Person' : null };
+export interface Vacation { 'end' : bigint, 'start' : bigint }
 export interface Validator {
+  'weight' : [] | [bigint],
   'status' : [] | [boolean],
+  'voted_at' : [] | [bigint],
+  'role' : [] | [ValidatorRole],
+  'vote_expires_at' : [] | [bigint],
   'principal_id' : string,
 }
+export type ValidatorRole = { 'Approver' : null } |
+  { 'Guardian' : null };
+export interface VoteClearance {
+  'cleared_at' : bigint,
+  'reason' : VoteClearanceReason,
+}
+export type VoteClearanceReason = { 'OwnerActivity' : null };
+export interface VoteCommitment {
+  'committed_at' : bigint,
+  'revealed_at' : [] | [bigint],
+  'commitment' : string,
+}
+export interface VoteEvidence {
+  'evidence' : Array<Evidence>,
+  'visibility' : EvidenceVisibility,
+  'submitted_at' : bigint,
+  'reason' : [] | [string],
+}
+export type VoteOutcome = { 'Approved' : null } |
+  { 'Rejected' : null } |
+  { 'Pending' : null };
+export interface VotingDeadline {
+  'voting_window_remaining' : [] | [bigint],
+  'commit_phase_remaining' : [] | [bigint],
+  'condition_id' : string,
+  'vote_expiry_remaining' : [] | [bigint],
+  'reveal_open' : [] | [boolean],
+}
 export interface XOutOfYCondition {
   'id' : string,
+  'voting_window_days' : [] | [bigint],
   'question' : string,
   'condition_status' : boolean,
+  'quorum_type' : [] | [QuorumType],
+  'clear_votes_on_activity' : [] | [ActivityType],
+  'commit_deadline' : [] | [bigint],
+  'quorum_unreachable_since' : [] | [bigint],
+  'voting_window_start' : [] | [bigint],
+  'vote_evidence' : [] | [Array<[string, VoteEvidence]>],
+  'secret_ballot_days' : [] | [bigint],
   'quorum' : bigint,
+  'commitments' : [] | [Array<[string, VoteCommitment]>],
+  'vote_clearances' : [] | [Array<[string, VoteClearance]>],
+  'outcome' : [] | [VoteOutcome],
+  'vote_validity_days' : [] | [bigint],
   'validators' : Array<Validator>,
+  'backup_validators' : [] | [Array<Validator>],
+  'reject_quorum' : [] | [bigint],
 }
 export interface _SERVICE {
+  'add_oracle' : ActorMethod<[string], Result>,
+  'answer_beneficiary_challenge' : ActorMethod<
+    [AnswerBeneficiaryChallengeArgs],
+    Result_18
+  >,
+  'apply_condition_template' : ActorMethod<
+    [ApplyConditionTemplateArgs],
+    Result_2
+  >,
+  'check_in' : ActorMethod<[], Result>,
+  'commit_x_out_of_y_vote' : ActorMethod<[CommitXOutOfYVoteArgs], Result>,
+  'confirm_dead_mans_switch' : ActorMethod<[ConfirmDeadMansSwitchArgs], Result>,
   'confirm_x_out_of_y_condition' : ActorMethod<
     [ConfirmXOutOfYConditionArgs],
     Result
   >,
+  'create_condition_template' : ActorMethod<
+    [CreateConditionTemplateArgs],
+    Result_14
+  >,
   'create_contact' : ActorMethod<[CreateContactArgs], Result_1>,
   'create_policy' : ActorMethod<[CreatePolicyArgs], Result_2>,
   'create_secret' : ActorMethod<[CreateSecretArgs], Result_3>,
   'create_user' : ActorMethod<[AddOrUpdateUserArgs], Result_4>,
+  'delete_condition_template' : ActorMethod<[string], Result>,
   'delete_contact' : ActorMethod<[string], Result>,
+  'delete_dead_mans_switch' : ActorMethod<[], Result_4>,
   'delete_policy' : ActorMethod<[string], Result>,
   'delete_secret' : ActorMethod<[string], Result>,
   'delete_user' : ActorMethod<[], Result>,
@@ -239,6 +637,10 @@ export interface _SERVICE {
     [Uint8Array | number[]],
     string
   >,
+  'end_vacation' : ActorMethod<[], Result_4>,
+  'extend_inactivity_deadline' : ActorMethod<[ExtendDeadlineArgs], Result_16>,
+  'file_claim' : ActorMethod<[FileClaimArgs], Result>,
+  'forecast_policy' : ActorMethod<[ForecastPolicyArgs], Result_12>,
   'generate_vetkd_encrypted_symmetric_key_for_policy' : ActorMethod<
     [PolicyKeyDerviationArgs],
     Result_5
@@ -247,30 +649,53 @@ export interface _SERVICE {
     [Uint8Array | number[]],
     string
   >,
+  'get_condition_template_list' : ActorMethod<[], Result_15>,
   'get_contact_list' : ActorMethod<[], Result_6>,
   'get_current_user' : ActorMethod<[], Result_4>,
+  'get_dead_mans_switch_list_as_validator' : ActorMethod<[], Result_17>,
   'get_encrypted_symmetric_key' : ActorMethod<[string], Result_7>,
   'get_encrypted_symmetric_key_as_beneficiary' : ActorMethod<
     [string, string],
     Result_7
   >,
-  'get_policy_as_beneficiary' : ActorMethod<[string], Result_8>,
-  'get_policy_as_owner' : ActorMethod<[string], Result_8>,
-  'get_policy_as_validator' : ActorMethod<[string], Result_9>,
-  'get_policy_list_as_beneficiary' : ActorMethod<[], Result_10>,
-  'get_policy_list_as_owner' : ActorMethod<[], Result_10>,
-  'get_policy_list_as_validator' : ActorMethod<[], Result_11>,
+  'get_oracle_list' : ActorMethod<[], Array<string>>,
+  'get_policy_as_beneficiary' : ActorMethod<[string], Result_2>,
+  'get_policy_as_owner' : ActorMethod<[string], Result_2>,
+  'get_policy_as_validator' : ActorMethod<[string], Result_8>,
+  'get_policy_list_as_beneficiary' : ActorMethod<[], Result_9>,
+  'get_policy_list_as_owner' : ActorMethod<[], Result_9>,
+  'get_policy_list_as_validator' : ActorMethod<[], Result_10>,
+  'get_quorum_health' : ActorMethod<[string], Result_13>,
   'get_secret' : ActorMethod<[string], Result_3>,
-  'get_secret_as_beneficiary' : ActorMethod<[string, string], Result_3>,
-  'get_secret_list' : ActorMethod<[], Result_12>,
+  'get_secret_as_beneficiary' : ActorMethod<
+    [string, string, [] | [string]],
+    Result_3
+  >,
+  'get_secret_list' : ActorMethod<[], Result_11>,
   'ibe_encryption_key' : ActorMethod<[], string>,
+  'remove_beneficiary_challenge' : ActorMethod<[string, string], Result_2>,
+  'remove_oracle' : ActorMethod<[string], Result>,
+  'reset_policy_condition' : ActorMethod<[string, string], Result_2>,
+  'reset_policy_conditions' : ActorMethod<[string], Result_2>,
+  'reveal_x_out_of_y_vote' : ActorMethod<[RevealXOutOfYVoteArgs], Result>,
+  'set_beneficiary_challenge' : ActorMethod<
+    [SetBeneficiaryChallengeArgs],
+    Result_2
+  >,
+  'start_vacation' : ActorMethod<[bigint], Result_4>,
   'start_with_interval_secs' : ActorMethod<[bigint], undefined>,
   'symmetric_key_verification_key' : ActorMethod<[], string>,
+  'update_condition_template' : ActorMethod<
+    [UpdateConditionTemplateArgs],
+    Result_19
+  >,
   'update_contact' : ActorMethod<[Contact], Result_1>,
+  'update_dead_mans_switch' : ActorMethod<[UpdateDeadMansSwitchArgs], Result_4>,
   'update_policy' : ActorMethod<[UpdatePolicyArgs], Result_2>,
   'update_secret' : ActorMethod<[UpdateSecretArgs], Result_3>,
   'update_user' : ActorMethod<[AddOrUpdateUserArgs], Result_4>,
   'update_user_login_date' : ActorMethod<[], Result_4>,
+  'veto_policy_release' : ActorMethod<[string], Result_2>,
 }
 export declare const idlFactory: IDL.InterfaceFactory;
 export declare const init: (args: { IDL: typeof IDL }) => IDL.Type[];
@@ -1,98 +1,441 @@
 export const idlFactory = ({ IDL }) => {
-  const ConfirmXOutOfYConditionArgs = IDL.Record({
-    'status' : IDL.Bool,
-    'condition_id' : IDL.Text,
-    'policy_id' : IDL.Text,
-  });
+  const ConditionExpression = IDL.Rec();
+  const UpdateConditionExpression = IDL.Rec();
   const SmartVaultErr = IDL.Variant({
     'ContactDoesNotExist' : IDL.Text,
     'UserAlreadyExists' : IDL.Text,
     'OnlyOwnerCanDeleteSecret' : IDL.Text,
     'PolicyConditionDoesNotExist' : IDL.Text,
     'SecretHasNoId' : IDL.Null,
+    'ClaimAlreadyPending' : IDL.Text,
+    'InvalidDeadMansSwitch' : IDL.Text,
+    'PolicyExpired' : IDL.Text,
     'UserDeletionFailed' : IDL.Text,
     'KeyBoxEntryDoesNotExistForSecret' : IDL.Text,
     'ContactAlreadyExists' : IDL.Text,
     'CallerNotBeneficiary' : IDL.Text,
     'InvalidQuorum' : IDL.Tuple(IDL.Text, IDL.Text),
+    'CallerNotVetoer' : IDL.Text,
+    'ChallengeRateLimited' : IDL.Text,
+    'InvalidCheckInSchedule' : IDL.Text,
+    'InvalidReleaseStage' : IDL.Text,
+    'SecretNotReleased' : IDL.Text,
+    'PolicyReleaseNotPending' : IDL.Text,
+    'ConditionTemplateDoesNotExist' : IDL.Text,
     'SecretDoesNotExist' : IDL.Text,
+    'OracleNotAllowed' : IDL.Text,
     'NoPolicyForBeneficiary' : IDL.Text,
     'CallerNotPolicyOwner' : IDL.Text,
+    'CallerNotDeadlineExtender' : IDL.Text,
+    'InvalidConditionTemplate' : IDL.Text,
+    'InvalidConditionExpression' : IDL.Text,
+    'InvalidVotingPeriod' : IDL.Text,
     'SecretEntryDoesNotExistForKeyBoxEntry' : IDL.Text,
     'InvalidDateTime' : IDL.Text,
+    'InvalidVacation' : IDL.Text,
+    'BeneficiaryChallengeRequired' : IDL.Text,
     'Unauthorized' : IDL.Null,
+    'InvalidOracleCondition' : IDL.Text,
+    'WrongChallengeAnswer' : IDL.Text,
     'UserUpdateFailed' : IDL.Text,
     'LogicalOperatorWithLessThanTwoConditions' : IDL.Null,
     'NoPolicyForValidator' : IDL.Text,
+    'InvalidBeneficiaryChallenge' : IDL.Text,
+    'DeadMansSwitchInUse' : IDL.Text,
+    'InvalidBeneficiarySecrets' : IDL.Text,
     'PolicyAlreadyExists' : IDL.Text,
+    'InvalidValidatorWeight' : IDL.Text,
+    'InvalidBackupValidator' : IDL.Text,
     'PolicyDoesNotExist' : IDL.Text,
     'UserDoesNotExist' : IDL.Text,
+    'InvalidVoteCommitment' : IDL.Text,
+    'SecretNotAssigned' : IDL.Text,
     'SecretAlreadyExists' : IDL.Text,
+    'InvalidVoteEvidence' : IDL.Text,
+    'PolicyDependencyCycle' : IDL.Text,
     'InvalidPolicyCondition' : IDL.Null,
+    'InvalidResponsePeriod' : IDL.Text,
     'KeyGenerationNotAllowed' : IDL.Null,
+    'BeneficiaryChallengeLocked' : IDL.Text,
+    'InvalidDeadlineExtension' : IDL.Text,
+    'ClaimRateLimited' : IDL.Text,
   });
   const Result = IDL.Variant({ 'Ok' : IDL.Null, 'Err' : SmartVaultErr });
-  const UserType = IDL.Variant({ 'Company' : IDL.Null, 'Person' : IDL.Null });
-  const CreateContactArgs = IDL.Record({
+  const AnswerBeneficiaryChallengeArgs = IDL.Record({
+    'answer' : IDL.Text,
+    'policy_id' : IDL.Text,
+  });
+  const ChallengeToken = IDL.Record({
+    'token' : IDL.Text,
+    'expires_at' : IDL.Nat64,
+  });
+  const Result_18 = IDL.Variant({
+    'Ok' : ChallengeToken,
+    'Err' : SmartVaultErr,
+  });
+  const ApplyConditionTemplateArgs = IDL.Record({
+    'template_id' : IDL.Text,
+    'linked' : IDL.Bool,
+    'policy_id' : IDL.Text,
+  });
+  const ConditionResetReason = IDL.Variant({
+    'OwnerReset' : IDL.Null,
+    'ConditionEdited' : IDL.Null,
+    'ValidatorRejection' : IDL.Null,
+  });
+  const ConditionReset = IDL.Record({
+    'date' : IDL.Nat64,
+    'condition_id' : IDL.Opt(IDL.Text),
+    'reason' : ConditionResetReason,
+  });
+  const LogicalOperator = IDL.Variant({ 'Or' : IDL.Null, 'And' : IDL.Null });
+  const SecretCategory = IDL.Variant({
+    'Password' : IDL.Null,
+    'Note' : IDL.Null,
+    'Document' : IDL.Null,
+  });
+  const SecretListEntry = IDL.Record({
     'id' : IDL.Text,
-    'user_type' : IDL.Opt(UserType),
     'name' : IDL.Opt(IDL.Text),
-    'email' : IDL.Opt(IDL.Text),
+    'category' : IDL.Opt(SecretCategory),
   });
-  const Contact = IDL.Record({
-    'id' : IDL.Text,
-    'user_type' : IDL.Opt(UserType),
+  const ReleaseStage = IDL.Record({
+    'not_before' : IDL.Opt(IDL.Nat64),
     'name' : IDL.Opt(IDL.Text),
-    'email' : IDL.Opt(IDL.Text),
+    'secrets' : IDL.Vec(IDL.Text),
+    'delay_days' : IDL.Opt(IDL.Nat64),
   });
-  const Result_1 = IDL.Variant({ 'Ok' : Contact, 'Err' : SmartVaultErr });
-  const CreatePolicyArgs = IDL.Record({ 'name' : IDL.Opt(IDL.Text) });
-  const LogicalOperator = IDL.Variant({ 'Or' : IDL.Null, 'And' : IDL.Null });
+  const BeneficiaryChallengeStatus = IDL.Record({
+    'failed_attempts' : IDL.Nat32,
+    'question' : IDL.Text,
+    'locked_until' : IDL.Opt(IDL.Nat64),
+    'lockouts' : IDL.Nat32,
+    'token_expires_at' : IDL.Opt(IDL.Nat64),
+  });
+  const ActivityType = IDL.Variant({
+    'ExplicitLogin' : IDL.Null,
+    'AnyActivity' : IDL.Null,
+  });
+  const DurationUnit = IDL.Variant({
+    'Days' : IDL.Null,
+    'Weeks' : IDL.Null,
+    'Years' : IDL.Null,
+    'Hours' : IDL.Null,
+    'Months' : IDL.Null,
+  });
+  const Duration = IDL.Record({ 'value' : IDL.Nat64, 'unit' : DurationUnit });
   const LastLoginTimeCondition = IDL.Record({
     'id' : IDL.Text,
+    'activity_type' : IDL.Opt(ActivityType),
     'condition_status' : IDL.Bool,
-    'number_of_days_since_last_login' : IDL.Nat64,
+    'inactivity' : IDL.Opt(Duration),
+    'number_of_days_since_last_login' : IDL.Opt(IDL.Nat64),
+  });
+  const TimeZone = IDL.Record({
+    'name' : IDL.Opt(IDL.Text),
+    'utc_offset_minutes' : IDL.Int32,
   });
   const FixedDateTimeCondition = IDL.Record({
     'id' : IDL.Text,
+    'time_zone' : IDL.Opt(TimeZone),
     'condition_status' : IDL.Bool,
+    'end_datetime' : IDL.Opt(IDL.Nat64),
     'datetime' : IDL.Nat64,
   });
+  const PolicyDependencyCondition = IDL.Record({
+    'id' : IDL.Text,
+    'condition_status' : IDL.Bool,
+    'delay_days' : IDL.Opt(IDL.Nat64),
+    'dependency_met_at' : IDL.Opt(IDL.Nat64),
+    'policy_id' : IDL.Text,
+  });
+  const OracleReply = IDL.Variant({
+    'LastActivity' : IDL.Nat64,
+    'Status' : IDL.Bool,
+  });
+  const OracleFailure = IDL.Record({
+    'date' : IDL.Nat64,
+    'message' : IDL.Text,
+  });
+  const OracleCondition = IDL.Record({
+    'id' : IDL.Text,
+    'method' : IDL.Text,
+    'last_call_at' : IDL.Opt(IDL.Nat64),
+    'condition_status' : IDL.Bool,
+    'canister_id' : IDL.Text,
+    'max_days_inactive' : IDL.Opt(IDL.Nat64),
+    'last_reply' : IDL.Opt(OracleReply),
+    'call_pending' : IDL.Bool,
+    'last_reply_at' : IDL.Opt(IDL.Nat64),
+    'last_failure' : IDL.Opt(OracleFailure),
+  });
+  const DeadMansSwitchCondition = IDL.Record({
+    'id' : IDL.Text,
+    'condition_status' : IDL.Bool,
+  });
+  const QuorumType = IDL.Variant({
+    'Absolute' : IDL.Null,
+    'Percentage' : IDL.Null,
+  });
+  const Evidence = IDL.Record({
+    'encrypted_content' : IDL.Vec(IDL.Nat8),
+    'name' : IDL.Opt(IDL.Text),
+  });
+  const EvidenceVisibility = IDL.Variant({
+    'Validators' : IDL.Null,
+    'Beneficiaries' : IDL.Null,
+  });
+  const VoteEvidence = IDL.Record({
+    'evidence' : IDL.Vec(Evidence),
+    'visibility' : EvidenceVisibility,
+    'submitted_at' : IDL.Nat64,
+    'reason' : IDL.Opt(IDL.Text),
+  });
+  const VoteCommitment = IDL.Record({
+    'committed_at' : IDL.Nat64,
+    'revealed_at' : IDL.Opt(IDL.Nat64),
+    'commitment' : IDL.Text,
+  });
+  const VoteClearanceReason = IDL.Variant({ 'OwnerActivity' : IDL.Null });
+  const VoteClearance = IDL.Record({
+    'cleared_at' : IDL.Nat64,
+    'reason' : VoteClearanceReason,
+  });
+  const VoteOutcome = IDL.Variant({
+    'Approved' : IDL.Null,
+    'Rejected' : IDL.Null,
+    'Pending' : IDL.Null,
+  });
+  const ValidatorRole = IDL.Variant({
+    'Approver' : IDL.Null,
+    'Guardian' : IDL.Null,
+  });
   const Validator = IDL.Record({
+    'weight' : IDL.Opt(IDL.Nat64),
     'status' : IDL.Opt(IDL.Bool),
+    'voted_at' : IDL.Opt(IDL.Nat64),
+    'role' : IDL.Opt(ValidatorRole),
+    'vote_expires_at' : IDL.Opt(IDL.Nat64),
     'principal_id' : IDL.Text,
   });
   const XOutOfYCondition = IDL.Record({
     'id' : IDL.Text,
+    'voting_window_days' : IDL.Opt(IDL.Nat64),
     'question' : IDL.Text,
     'condition_status' : IDL.Bool,
+    'quorum_type' : IDL.Opt(QuorumType),
+    'clear_votes_on_activity' : IDL.Opt(ActivityType),
+    'commit_deadline' : IDL.Opt(IDL.Nat64),
+    'quorum_unreachable_since' : IDL.Opt(IDL.Nat64),
+    'voting_window_start' : IDL.Opt(IDL.Nat64),
+    'vote_evidence' : IDL.Opt(IDL.Vec(IDL.Tuple(IDL.Text, VoteEvidence))),
+    'secret_ballot_days' : IDL.Opt(IDL.Nat64),
     'quorum' : IDL.Nat64,
+    'commitments' : IDL.Opt(IDL.Vec(IDL.Tuple(IDL.Text, VoteCommitment))),
+    'vote_clearances' : IDL.Opt(IDL.Vec(IDL.Tuple(IDL.Text, VoteClearance))),
+    'outcome' : IDL.Opt(VoteOutcome),
+    'vote_validity_days' : IDL.Opt(IDL.Nat64),
     'validators' : IDL.Vec(Validator),
+    'backup_validators' : IDL.Opt(IDL.Vec(Validator)),
+    'reject_quorum' : IDL.Opt(IDL.Nat64),
+  });
+  const BeneficiaryClaim = IDL.Record({
+    'claimant' : IDL.Text,
+    'filed_at' : IDL.Nat64,
+    'response_deadline' : IDL.Nat64,
+  });
+  const BeneficiaryClaimCondition = IDL.Record({
+    'id' : IDL.Text,
+    'pending_claim' : IDL.Opt(BeneficiaryClaim),
+    'condition_status' : IDL.Bool,
+    'last_claims' : IDL.Vec(IDL.Tuple(IDL.Text, IDL.Nat64)),
+    'response_period_days' : IDL.Nat64,
+  });
+  const CheckInSchedule = IDL.Variant({
+    'DayOfMonth' : IDL.Nat8,
+    'EveryNumberOfDays' : IDL.Nat64,
+  });
+  const CheckInCondition = IDL.Record({
+    'id' : IDL.Text,
+    'next_deadline' : IDL.Nat64,
+    'condition_status' : IDL.Bool,
+    'last_check_in' : IDL.Nat64,
+    'grace_period_days' : IDL.Nat64,
+    'schedule' : CheckInSchedule,
   });
   const Condition = IDL.Variant({
     'LastLogin' : LastLoginTimeCondition,
     'FixedDateTime' : FixedDateTimeCondition,
+    'PolicyDependency' : PolicyDependencyCondition,
+    'Oracle' : OracleCondition,
+    'DeadMansSwitch' : DeadMansSwitchCondition,
     'XOutOfY' : XOutOfYCondition,
-  });
-  const Policy = IDL.Record({
+    'BeneficiaryClaim' : BeneficiaryClaimCondition,
+    'CheckIn' : CheckInCondition,
+  });
+  ConditionExpression.fill(
+    IDL.Variant({
+      'Or' : IDL.Vec(ConditionExpression),
+      'And' : IDL.Vec(ConditionExpression),
+      'Not' : ConditionExpression,
+      'Condition' : IDL.Text,
+    })
+  );
+  const PolicyWithSecretListEntries = IDL.Record({
     'id' : IDL.Text,
+    'not_before' : IDL.Opt(IDL.Nat64),
     'date_created' : IDL.Nat64,
+    'release_date' : IDL.Opt(IDL.Nat64),
     'owner' : IDL.Text,
+    'condition_resets' : IDL.Vec(ConditionReset),
     'name' : IDL.Opt(IDL.Text),
     'conditions_logical_operator' : IDL.Opt(LogicalOperator),
-    'secrets' : IDL.Vec(IDL.Text),
+    'secrets' : IDL.Vec(SecretListEntry),
+    'release_stages' : IDL.Vec(ReleaseStage),
+    'released' : IDL.Bool,
+    'cooling_off_period_days' : IDL.Opt(IDL.Nat64),
     'conditions_status' : IDL.Bool,
+    'beneficiary_challenges' : IDL.Vec(
+      IDL.Tuple(IDL.Text, BeneficiaryChallengeStatus)
+    ),
+    'condition_template' : IDL.Opt(IDL.Text),
     'beneficiaries' : IDL.Vec(IDL.Text),
+    'vetoers' : IDL.Vec(IDL.Text),
     'key_box' : IDL.Vec(IDL.Tuple(IDL.Text, IDL.Vec(IDL.Nat8))),
     'conditions' : IDL.Vec(Condition),
     'date_modified' : IDL.Nat64,
+    'expires_at' : IDL.Opt(IDL.Nat64),
+    'beneficiary_secrets' : IDL.Vec(IDL.Tuple(IDL.Text, IDL.Vec(IDL.Text))),
+    'conditions_expression' : IDL.Opt(ConditionExpression),
   });
-  const Result_2 = IDL.Variant({ 'Ok' : Policy, 'Err' : SmartVaultErr });
-  const SecretCategory = IDL.Variant({
-    'Password' : IDL.Null,
-    'Note' : IDL.Null,
-    'Document' : IDL.Null,
+  const Result_2 = IDL.Variant({
+    'Ok' : PolicyWithSecretListEntries,
+    'Err' : SmartVaultErr,
+  });
+  const CommitXOutOfYVoteArgs = IDL.Record({
+    'condition_id' : IDL.Text,
+    'policy_id' : IDL.Text,
+    'commitment' : IDL.Text,
+  });
+  const ConfirmDeadMansSwitchArgs = IDL.Record({
+    'status' : IDL.Bool,
+    'owner' : IDL.Text,
+    'condition_id' : IDL.Text,
+  });
+  const ConfirmXOutOfYConditionArgs = IDL.Record({
+    'status' : IDL.Bool,
+    'evidence' : IDL.Opt(IDL.Vec(Evidence)),
+    'condition_id' : IDL.Text,
+    'policy_id' : IDL.Text,
+    'evidence_visibility' : IDL.Opt(EvidenceVisibility),
+    'reason' : IDL.Opt(IDL.Text),
+  });
+  const UpdateLastLoginTimeCondition = IDL.Record({
+    'id' : IDL.Opt(IDL.Text),
+    'activity_type' : IDL.Opt(ActivityType),
+    'inactivity' : IDL.Opt(Duration),
+    'number_of_days_since_last_login' : IDL.Opt(IDL.Nat64),
+  });
+  const UpdateFixedDateTimeCondition = IDL.Record({
+    'id' : IDL.Opt(IDL.Text),
+    'time_zone' : IDL.Opt(TimeZone),
+    'end_datetime' : IDL.Opt(IDL.Nat64),
+    'datetime' : IDL.Nat64,
+  });
+  const UpdatePolicyDependencyCondition = IDL.Record({
+    'id' : IDL.Opt(IDL.Text),
+    'delay_days' : IDL.Opt(IDL.Nat64),
+    'policy_id' : IDL.Text,
+  });
+  const UpdateOracleCondition = IDL.Record({
+    'id' : IDL.Opt(IDL.Text),
+    'method' : IDL.Text,
+    'canister_id' : IDL.Text,
+    'max_days_inactive' : IDL.Opt(IDL.Nat64),
   });
+  const UpdateDeadMansSwitchCondition = IDL.Record({
+    'id' : IDL.Opt(IDL.Text),
+  });
+  const UpdateXOutOfYCondition = IDL.Record({
+    'id' : IDL.Opt(IDL.Text),
+    'voting_window_days' : IDL.Opt(IDL.Nat64),
+    'question' : IDL.Text,
+    'quorum_type' : IDL.Opt(QuorumType),
+    'clear_votes_on_activity' : IDL.Opt(ActivityType),
+    'secret_ballot_days' : IDL.Opt(IDL.Nat64),
+    'quorum' : IDL.Nat64,
+    'vote_validity_days' : IDL.Opt(IDL.Nat64),
+    'validators' : IDL.Vec(Validator),
+    'backup_validators' : IDL.Vec(Validator),
+    'reject_quorum' : IDL.Opt(IDL.Nat64),
+  });
+  const UpdateBeneficiaryClaimCondition = IDL.Record({
+    'id' : IDL.Opt(IDL.Text),
+    'response_period_days' : IDL.Nat64,
+  });
+  const UpdateCheckInCondition = IDL.Record({
+    'id' : IDL.Opt(IDL.Text),
+    'grace_period_days' : IDL.Nat64,
+    'schedule' : CheckInSchedule,
+  });
+  const UpdateCondition = IDL.Variant({
+    'LastLogin' : UpdateLastLoginTimeCondition,
+    'FixedDateTime' : UpdateFixedDateTimeCondition,
+    'PolicyDependency' : UpdatePolicyDependencyCondition,
+    'Oracle' : UpdateOracleCondition,
+    'DeadMansSwitch' : UpdateDeadMansSwitchCondition,
+    'XOutOfY' : UpdateXOutOfYCondition,
+    'BeneficiaryClaim' : UpdateBeneficiaryClaimCondition,
+    'CheckIn' : UpdateCheckInCondition,
+  });
+  UpdateConditionExpression.fill(
+    IDL.Variant({
+      'Or' : IDL.Vec(UpdateConditionExpression),
+      'And' : IDL.Vec(UpdateConditionExpression),
+      'Not' : UpdateConditionExpression,
+      'Condition' : IDL.Nat64,
+    })
+  );
+  const CreateConditionTemplateArgs = IDL.Record({
+    'name' : IDL.Text,
+    'conditions_logical_operator' : IDL.Opt(LogicalOperator),
+    'description' : IDL.Opt(IDL.Text),
+    'conditions' : IDL.Vec(UpdateCondition),
+    'conditions_expression' : IDL.Opt(UpdateConditionExpression),
+  });
+  const ConditionTemplate = IDL.Record({
+    'id' : IDL.Text,
+    'date_created' : IDL.Nat64,
+    'owner' : IDL.Opt(IDL.Text),
+    'name' : IDL.Text,
+    'conditions_logical_operator' : IDL.Opt(LogicalOperator),
+    'description' : IDL.Opt(IDL.Text),
+    'conditions' : IDL.Vec(UpdateCondition),
+    'date_modified' : IDL.Nat64,
+    'conditions_expression' : IDL.Opt(UpdateConditionExpression),
+  });
+  const Result_14 = IDL.Variant({
+    'Ok' : ConditionTemplate,
+    'Err' : SmartVaultErr,
+  });
+  const UserType = IDL.Variant({ 'Company' : IDL.Null, 'Person' : IDL.Null });
+  const ContactRole = IDL.Variant({ 'DeadlineExtender' : IDL.Null });
+  const CreateContactArgs = IDL.Record({
+    'id' : IDL.Text,
+    'user_type' : IDL.Opt(UserType),
+    'name' : IDL.Opt(IDL.Text),
+    'role' : IDL.Opt(ContactRole),
+    'email' : IDL.Opt(IDL.Text),
+  });
+  const Contact = IDL.Record({
+    'id' : IDL.Text,
+    'user_type' : IDL.Opt(UserType),
+    'name' : IDL.Opt(IDL.Text),
+    'role' : IDL.Opt(ContactRole),
+    'email' : IDL.Opt(IDL.Text),
+  });
+  const Result_1 = IDL.Variant({ 'Ok' : Contact, 'Err' : SmartVaultErr });
+  const CreatePolicyArgs = IDL.Record({ 'name' : IDL.Opt(IDL.Text) });
   const CreateSecretArgs = IDL.Record({
     'url' : IDL.Opt(IDL.Text),
     'username' : IDL.Opt(IDL.Vec(IDL.Nat8)),
@@ -120,61 +463,119 @@ export const idlFactory = ({ IDL }) => {
     'name' : IDL.Opt(IDL.Text),
     'email' : IDL.Opt(IDL.Text),
   });
+  const DeadMansSwitch = IDL.Record({
+    'conditions_logical_operator' : IDL.Opt(LogicalOperator),
+    'triggered_at' : IDL.Opt(IDL.Nat64),
+    'conditions' : IDL.Vec(Condition),
+    'date_modified' : IDL.Nat64,
+  });
+  const DeadlineExtension = IDL.Record({
+    'date' : IDL.Nat64,
+    'days' : IDL.Nat64,
+    'extended_by' : IDL.Text,
+  });
+  const Vacation = IDL.Record({ 'end' : IDL.Nat64, 'start' : IDL.Nat64 });
   const User = IDL.Record({
     'id' : IDL.Text,
     'user_type' : IDL.Opt(UserType),
     'date_created' : IDL.Nat64,
     'contacts' : IDL.Vec(Contact),
+    'dead_mans_switch' : IDL.Opt(DeadMansSwitch),
     'name' : IDL.Opt(IDL.Text),
     'secrets' : IDL.Vec(IDL.Text),
     'date_last_login' : IDL.Opt(IDL.Nat64),
     'email' : IDL.Opt(IDL.Text),
+    'date_last_activity' : IDL.Opt(IDL.Nat64),
+    'deadline_extensions' : IDL.Opt(IDL.Vec(DeadlineExtension)),
     'key_box' : IDL.Vec(IDL.Tuple(IDL.Text, IDL.Vec(IDL.Nat8))),
     'date_modified' : IDL.Nat64,
+    'vacations' : IDL.Opt(IDL.Vec(Vacation)),
     'policies' : IDL.Vec(IDL.Text),
   });
   const Result_4 = IDL.Variant({ 'Ok' : User, 'Err' : SmartVaultErr });
+  const ExtendDeadlineArgs = IDL.Record({
+    'owner' : IDL.Text,
+    'days' : IDL.Nat64,
+  });
+  const Result_16 = IDL.Variant({
+    'Ok' : DeadlineExtension,
+    'Err' : SmartVaultErr,
+  });
+  const FileClaimArgs = IDL.Record({
+    'condition_id' : IDL.Text,
+    'policy_id' : IDL.Text,
+  });
+  const SimulatedVote = IDL.Record({
+    'status' : IDL.Bool,
+    'validator' : IDL.Text,
+    'condition_id' : IDL.Text,
+  });
+  const ForecastPolicyArgs = IDL.Record({
+    'now' : IDL.Opt(IDL.Nat64),
+    'last_login' : IDL.Opt(IDL.Nat64),
+    'votes' : IDL.Vec(SimulatedVote),
+    'policy_id' : IDL.Text,
+  });
+  const ConditionForecast = IDL.Record({
+    'status' : IDL.Bool,
+    'met_at' : IDL.Opt(IDL.Nat64),
+    'condition_id' : IDL.Text,
+  });
+  const PolicyForecast = IDL.Record({
+    'now' : IDL.Nat64,
+    'release_date' : IDL.Opt(IDL.Nat64),
+    'conditions_logical_operator' : IDL.Opt(LogicalOperator),
+    'conditions_status' : IDL.Bool,
+    'conditions_met_at' : IDL.Opt(IDL.Nat64),
+    'conditions' : IDL.Vec(ConditionForecast),
+    'policy_id' : IDL.Text,
+    'conditions_expression' : IDL.Opt(ConditionExpression),
+  });
+  const Result_12 = IDL.Variant({
+    'Ok' : PolicyForecast,
+    'Err' : SmartVaultErr,
+  });
   const PolicyKeyDerviationArgs = IDL.Record({
+    'challenge_token' : IDL.Opt(IDL.Text),
     'encryption_public_key' : IDL.Vec(IDL.Nat8),
     'policy_id' : IDL.Text,
   });
   const Result_5 = IDL.Variant({ 'Ok' : IDL.Text, 'Err' : SmartVaultErr });
-  const Result_6 = IDL.Variant({
-    'Ok' : IDL.Vec(Contact),
+  const Result_15 = IDL.Variant({
+    'Ok' : IDL.Vec(ConditionTemplate),
     'Err' : SmartVaultErr,
   });
-  const Result_7 = IDL.Variant({
-    'Ok' : IDL.Vec(IDL.Nat8),
+  const Result_6 = IDL.Variant({
+    'Ok' : IDL.Vec(Contact),
     'Err' : SmartVaultErr,
   });
-  const SecretListEntry = IDL.Record({
-    'id' : IDL.Text,
-    'name' : IDL.Opt(IDL.Text),
-    'category' : IDL.Opt(SecretCategory),
-  });
-  const PolicyWithSecretListEntries = IDL.Record({
-    'id' : IDL.Text,
-    'date_created' : IDL.Nat64,
+  const DeadMansSwitchForValidator = IDL.Record({
     'owner' : IDL.Text,
-    'name' : IDL.Opt(IDL.Text),
-    'conditions_logical_operator' : IDL.Opt(LogicalOperator),
-    'secrets' : IDL.Vec(SecretListEntry),
-    'conditions_status' : IDL.Bool,
-    'beneficiaries' : IDL.Vec(IDL.Text),
-    'key_box' : IDL.Vec(IDL.Tuple(IDL.Text, IDL.Vec(IDL.Nat8))),
     'conditions' : IDL.Vec(Condition),
-    'date_modified' : IDL.Nat64,
   });
-  const Result_8 = IDL.Variant({
-    'Ok' : PolicyWithSecretListEntries,
+  const Result_17 = IDL.Variant({
+    'Ok' : IDL.Vec(DeadMansSwitchForValidator),
+    'Err' : SmartVaultErr,
+  });
+  const Result_7 = IDL.Variant({
+    'Ok' : IDL.Vec(IDL.Nat8),
     'Err' : SmartVaultErr,
   });
+  const VotingDeadline = IDL.Record({
+    'voting_window_remaining' : IDL.Opt(IDL.Nat64),
+    'commit_phase_remaining' : IDL.Opt(IDL.Nat64),
+    'condition_id' : IDL.Text,
+    'vote_expiry_remaining' : IDL.Opt(IDL.Nat64),
+    'reveal_open' : IDL.Opt(IDL.Bool),
+  });
   const PolicyForValidator = IDL.Record({
     'id' : IDL.Text,
     'owner' : IDL.Text,
+    'owner_vacations' : IDL.Vec(Vacation),
     'conditions' : IDL.Vec(Condition),
+    'voting_deadlines' : IDL.Vec(VotingDeadline),
   });
-  const Result_9 = IDL.Variant({
+  const Result_8 = IDL.Variant({
     'Ok' : PolicyForValidator,
     'Err' : SmartVaultErr,
   });
@@ -182,47 +583,87 @@ export const idlFactory = ({ IDL }) => {
     'id' : IDL.Text,
     'owner' : IDL.Text,
     'name' : IDL.Opt(IDL.Text),
+    'released' : IDL.Bool,
     'conditions_status' : IDL.Bool,
   });
-  const Result_10 = IDL.Variant({
+  const Result_9 = IDL.Variant({
     'Ok' : IDL.Vec(PolicyListEntry),
     'Err' : SmartVaultErr,
   });
-  const Result_11 = IDL.Variant({
+  const Result_10 = IDL.Variant({
     'Ok' : IDL.Vec(PolicyForValidator),
     'Err' : SmartVaultErr,
   });
-  const Result_12 = IDL.Variant({
-    'Ok' : IDL.Vec(SecretListEntry),
+  const QuorumHealth = IDL.Record({
+    'quorum_type' : IDL.Opt(QuorumType),
+    'total_weight' : IDL.Nat64,
+    'reachable_weight' : IDL.Nat64,
+    'quorum_unreachable_since' : IDL.Opt(IDL.Nat64),
+    'quorum_reachable' : IDL.Bool,
+    'condition_id' : IDL.Text,
+    'quorum' : IDL.Nat64,
+    'backup_validators' : IDL.Nat64,
+  });
+  const Result_13 = IDL.Variant({
+    'Ok' : IDL.Vec(QuorumHealth),
     'Err' : SmartVaultErr,
   });
-  const UpdateLastLoginTimeCondition = IDL.Record({
-    'id' : IDL.Opt(IDL.Text),
-    'number_of_days_since_last_login' : IDL.Nat64,
+  const Result_11 = IDL.Variant({
+    'Ok' : IDL.Vec(SecretListEntry),
+    'Err' : SmartVaultErr,
   });
-  const UpdateFixedDateTimeCondition = IDL.Record({
-    'id' : IDL.Opt(IDL.Text),
-    'datetime' : IDL.Nat64,
+  const RevealXOutOfYVoteArgs = IDL.Record({
+    'status' : IDL.Bool,
+    'salt' : IDL.Text,
+    'evidence' : IDL.Opt(IDL.Vec(Evidence)),
+    'condition_id' : IDL.Text,
+    'policy_id' : IDL.Text,
+    'evidence_visibility' : IDL.Opt(EvidenceVisibility),
+    'reason' : IDL.Opt(IDL.Text),
   });
-  const UpdateXOutOfYCondition = IDL.Record({
-    'id' : IDL.Opt(IDL.Text),
+  const SetBeneficiaryChallengeArgs = IDL.Record({
     'question' : IDL.Text,
-    'quorum' : IDL.Nat64,
-    'validators' : IDL.Vec(Validator),
+    'beneficiary' : IDL.Text,
+    'answer' : IDL.Text,
+    'policy_id' : IDL.Text,
   });
-  const UpdateCondition = IDL.Variant({
-    'LastLogin' : UpdateLastLoginTimeCondition,
-    'FixedDateTime' : UpdateFixedDateTimeCondition,
-    'XOutOfY' : UpdateXOutOfYCondition,
+  const UpdateConditionTemplateArgs = IDL.Record({
+    'id' : IDL.Text,
+    'name' : IDL.Text,
+    'conditions_logical_operator' : IDL.Opt(LogicalOperator),
+    'description' : IDL.Opt(IDL.Text),
+    'conditions' : IDL.Vec(UpdateCondition),
+    'conditions_expression' : IDL.Opt(UpdateConditionExpression),
+  });
+  const ConditionTemplateUpdate = IDL.Record({
+    'template' : ConditionTemplate,
+    'unlinked_policies' : IDL.Vec(IDL.Text),
+  });
+  const Result_19 = IDL.Variant({
+    'Ok' : ConditionTemplateUpdate,
+    'Err' : SmartVaultErr,
+  });
+  const UpdateDeadMansSwitchArgs = IDL.Record({
+    'conditions_logical_operator' : IDL.Opt(LogicalOperator),
+    'conditions' : IDL.Vec(UpdateCondition),
   });
   const UpdatePolicyArgs = IDL.Record({
     'id' : IDL.Text,
+    'not_before' : IDL.Opt(IDL.Nat64),
     'name' : IDL.Opt(IDL.Text),
     'conditions_logical_operator' : IDL.Opt(LogicalOperator),
     'secrets' : IDL.Vec(IDL.Text),
+    'release_stages' : IDL.Opt(IDL.Vec(ReleaseStage)),
+    'cooling_off_period_days' : IDL.Opt(IDL.Nat64),
     'beneficiaries' : IDL.Vec(IDL.Text),
+    'vetoers' : IDL.Opt(IDL.Vec(IDL.Text)),
     'key_box' : IDL.Vec(IDL.Tuple(IDL.Text, IDL.Vec(IDL.Nat8))),
     'conditions' : IDL.Vec(UpdateCondition),
+    'expires_at' : IDL.Opt(IDL.Nat64),
+    'beneficiary_secrets' : IDL.Opt(
+      IDL.Vec(IDL.Tuple(IDL.Text, IDL.Vec(IDL.Text)))
+    ),
+    'conditions_expression' : IDL.Opt(UpdateConditionExpression),
   });
   const UpdateSecretArgs = IDL.Record({
     'id' : IDL.Text,
@@ -234,16 +675,41 @@ export const idlFactory = ({ IDL }) => {
     'category' : IDL.Opt(SecretCategory),
   });
   return IDL.Service({
+    'add_oracle' : IDL.Func([IDL.Text], [Result], []),
+    'answer_beneficiary_challenge' : IDL.Func(
+        [AnswerBeneficiaryChallengeArgs],
+        [Result_18],
+        [],
+      ),
+    'apply_condition_template' : IDL.Func(
+        [ApplyConditionTemplateArgs],
+        [Result_2],
+        [],
+      ),
+    'check_in' : IDL.Func([], [Result], []),
+    'commit_x_out_of_y_vote' : IDL.Func([CommitXOutOfYVoteArgs], [Result], []),
+    'confirm_dead_mans_switch' : IDL.Func(
+        [ConfirmDeadMansSwitchArgs],
+        [Result],
+        [],
+      ),
     'confirm_x_out_of_y_condition' : IDL.Func(
         [ConfirmXOutOfYConditionArgs],
         [Result],
         [],
       ),
+    'create_condition_template' : IDL.Func(
+        [CreateConditionTemplateArgs],
+        [Result_14],
+        [],
+      ),
     'create_contact' : IDL.Func([CreateContactArgs], [Result_1], []),
     'create_policy' : IDL.Func([CreatePolicyArgs], [Result_2], []),
     'create_secret' : IDL.Func([CreateSecretArgs], [Result_3], []),
     'create_user' : IDL.Func([AddOrUpdateUserArgs], [Result_4], []),
+    'delete_condition_template' : IDL.Func([IDL.Text], [Result], []),
     'delete_contact' : IDL.Func([IDL.Text], [Result], []),
+    'delete_dead_mans_switch' : IDL.Func([], [Result_4], []),
     'delete_policy' : IDL.Func([IDL.Text], [Result], []),
     'delete_secret' : IDL.Func([IDL.Text], [Result], []),
     'delete_user' : IDL.Func([], [Result], []),
@@ -252,6 +718,14 @@ export const idlFactory = ({ IDL }) => {
         [IDL.Text],
         [],
       ),
+    'end_vacation' : IDL.Func([], [Result_4], []),
+    'extend_inactivity_deadline' : IDL.Func(
+        [ExtendDeadlineArgs],
+        [Result_16],
+        [],
+      ),
+    'file_claim' : IDL.Func([FileClaimArgs], [Result], []),
+    'forecast_policy' : IDL.Func([ForecastPolicyArgs], [Result_12], ['query']),
     'generate_vetkd_encrypted_symmetric_key_for_policy' : IDL.Func(
         [PolicyKeyDerviationArgs],
         [Result_5],
@@ -262,35 +736,69 @@ export const idlFactory = ({ IDL }) => {
         [IDL.Text],
         [],
       ),
+    'get_condition_template_list' : IDL.Func([], [Result_15], ['query']),
     'get_contact_list' : IDL.Func([], [Result_6], ['query']),
     'get_current_user' : IDL.Func([], [Result_4], ['query']),
+    'get_dead_mans_switch_list_as_validator' : IDL.Func(
+        [],
+        [Result_17],
+        ['query'],
+      ),
     'get_encrypted_symmetric_key' : IDL.Func([IDL.Text], [Result_7], ['query']),
     'get_encrypted_symmetric_key_as_beneficiary' : IDL.Func(
         [IDL.Text, IDL.Text],
         [Result_7],
         ['query'],
       ),
-    'get_policy_as_beneficiary' : IDL.Func([IDL.Text], [Result_8], ['query']),
-    'get_policy_as_owner' : IDL.Func([IDL.Text], [Result_8], ['query']),
-    'get_policy_as_validator' : IDL.Func([IDL.Text], [Result_9], ['query']),
-    'get_policy_list_as_beneficiary' : IDL.Func([], [Result_10], ['query']),
-    'get_policy_list_as_owner' : IDL.Func([], [Result_10], ['query']),
-    'get_policy_list_as_validator' : IDL.Func([], [Result_11], ['query']),
+    'get_oracle_list' : IDL.Func([], [IDL.Vec(IDL.Text)], ['query']),
+    'get_policy_as_beneficiary' : IDL.Func([IDL.Text], [Result_2], ['query']),
+    'get_policy_as_owner' : IDL.Func([IDL.Text], [Result_2], ['query']),
+    'get_policy_as_validator' : IDL.Func([IDL.Text], [Result_8], ['query']),
+    'get_policy_list_as_beneficiary' : IDL.Func([], [Result_9], ['query']),
+    'get_policy_list_as_owner' : IDL.Func([], [Result_9], ['query']),
+    'get_policy_list_as_validator' : IDL.Func([], [Result_10], ['query']),
+    'get_quorum_health' : IDL.Func([IDL.Text], [Result_13], ['query']),
     'get_secret' : IDL.Func([IDL.Text], [Result_3], ['query']),
     'get_secret_as_beneficiary' : IDL.Func(
-        [IDL.Text, IDL.Text],
+        [IDL.Text, IDL.Text, IDL.Opt(IDL.Text)],
         [Result_3],
         ['query'],
       ),
-    'get_secret_list' : IDL.Func([], [Result_12], ['query']),
+    'get_secret_list' : IDL.Func([], [Result_11], ['query']),
     'ibe_encryption_key' : IDL.Func([], [IDL.Text], []),
+    'remove_beneficiary_challenge' : IDL.Func(
+        [IDL.Text, IDL.Text],
+        [Result_2],
+        [],
+      ),
+    'remove_oracle' : IDL.Func([IDL.Text], [Result], []),
+    'reset_policy_condition' : IDL.Func([IDL.Text, IDL.Text], [Result_2], []),
+    'reset_policy_conditions' : IDL.Func([IDL.Text], [Result_2], []),
+    'reveal_x_out_of_y_vote' : IDL.Func([RevealXOutOfYVoteArgs], [Result], []),
+    'set_beneficiary_challenge' : IDL.Func(
+        [SetBeneficiaryChallengeArgs],
+        [Result_2],
+        [],
+      ),
+    'start_vacation' : IDL.Func([IDL.Nat64], [Result_4], []),
     'start_with_interval_secs' : IDL.Func([IDL.Nat64], [], []),
     'symmetric_key_verification_key' : IDL.Func([], [IDL.Text], []),
+    'update_condition_template' : IDL.Func(
+        [UpdateConditionTemplateArgs],
+        [Result_19],
+        [],
+      ),
     'update_contact' : IDL.Func([Contact], [Result_1], []),
+    'update_dead_mans_switch' : IDL.Func(
+        [UpdateDeadMansSwitchArgs],
+        [Result_4],
+        [],
+      ),
     'update_policy' : IDL.Func([UpdatePolicyArgs], [Result_2], []),
     'update_secret' : IDL.Func([UpdateSecretArgs], [Result_3], []),
     'update_user' : IDL.Func([AddOrUpdateUserArgs], [Result_4], []),
     'update_user_login_date' : IDL.Func([], [Result_4], []),
+    'veto_policy_release' : IDL.Func([IDL.Text], [Result_2], []),
   });
 };
 export const init = ({ IDL }) => { return []; };
@@ -27,8 +27,41 @@ pub enum SmartVaultErr {
     KeyBoxEntryDoesNotExistForSecret(String),
     SecretEntryDoesNotExistForKeyBoxEntry(String),
     LogicalOperatorWithLessThanTwoConditions,
+    InvalidConditionExpression(String),
     InvalidDateTime(String),
     InvalidQuorum(String, String),
+    InvalidValidatorWeight(String),
+    InvalidVotingPeriod(String),
+    InvalidCheckInSchedule(String),
+    PolicyReleaseNotPending(String),
+    CallerNotVetoer(String),
+    PolicyDependencyCycle(String),
+    PolicyExpired(String),
+    InvalidResponsePeriod(String),
+    ClaimAlreadyPending(String),
+    ClaimRateLimited(String),
+    InvalidOracleCondition(String),
+    OracleNotAllowed(String),
+    InvalidVoteEvidence(String),
+    InvalidVoteCommitment(String),
+    InvalidReleaseStage(String),
+    SecretNotReleased(String),
+    SecretNotAssigned(String),
+    InvalidBeneficiarySecrets(String),
+    InvalidBackupValidator(String),
+    ConditionTemplateDoesNotExist(String),
+    InvalidConditionTemplate(String),
+    InvalidVacation(String),
+    CallerNotDeadlineExtender(String),
+    InvalidDeadlineExtension(String),
+    InvalidDeadMansSwitch(String),
+    DeadMansSwitchInUse(String),
+    InvalidBeneficiaryChallenge(String),
+    BeneficiaryChallengeRequired(String),
+    WrongChallengeAnswer(String),
+    ChallengeRateLimited(String),
+    BeneficiaryChallengeLocked(String),
+    InvalidTimeZone(String),
     // Various errors
     CallerNotBeneficiary(String),
     KeyGenerationNotAllowed,
@@ -110,6 +143,9 @@ impl Display for SmartVaultErr {
             SmartVaultErr::LogicalOperatorWithLessThanTwoConditions => {
                 write!(f, "Logical operator provided with less than two conditions")
             }
+            SmartVaultErr::InvalidConditionExpression(reason) => {
+                write!(f, "Invalid condition expression: {}", reason)
+            }
             SmartVaultErr::InvalidPolicyCondition => {
                 write!(f, "Policy cannot be read by beneficiary because of wrong condition state")
             }
@@ -117,7 +153,103 @@ impl Display for SmartVaultErr {
                 write!(f, "Datetime {} is invalid or in the past", datetime)
             }
             SmartVaultErr::InvalidQuorum(quorum, validators) => {
-                write!(f, "Quorum {} must be less than number of validators: {}", quorum, validators)
+                write!(f, "Quorum {} must not exceed the total weight of the validators: {}", quorum, validators)
+            }
+            SmartVaultErr::InvalidVotingPeriod(reason) => {
+                write!(f, "Invalid voting period: {}", reason)
+            }
+            SmartVaultErr::InvalidValidatorWeight(validator) => {
+                write!(f, "Weight of validator {} must be greater than 0", validator)
+            }
+            SmartVaultErr::InvalidCheckInSchedule(schedule) => {
+                write!(f, "Check-in schedule {} is invalid", schedule)
+            }
+            SmartVaultErr::PolicyReleaseNotPending(id) => {
+                write!(f, "Release of policy is not pending: {}", id)
+            }
+            SmartVaultErr::InvalidResponsePeriod(days) => {
+                write!(f, "Response period must be at least one day: {}", days)
+            }
+            SmartVaultErr::ClaimAlreadyPending(claimant) => {
+                write!(f, "There is already a pending claim of: {}", claimant)
+            }
+            SmartVaultErr::ClaimRateLimited(claimant) => {
+                write!(f, "Too many claims of beneficiary: {}", claimant)
+            }
+            SmartVaultErr::InvalidOracleCondition(reason) => {
+                write!(f, "Invalid oracle condition: {}", reason)
+            }
+            SmartVaultErr::OracleNotAllowed(canister_id) => {
+                write!(f, "Oracle is not allowed: {}", canister_id)
+            }
+            SmartVaultErr::InvalidVoteCommitment(reason) => {
+                write!(f, "Invalid vote commitment: {}", reason)
+            }
+            SmartVaultErr::InvalidVoteEvidence(reason) => {
+                write!(f, "Invalid vote evidence: {}", reason)
+            }
+            SmartVaultErr::InvalidReleaseStage(secret) => {
+                write!(f, "Invalid release stage for secret: {}", secret)
+            }
+            SmartVaultErr::SecretNotReleased(secret) => {
+                write!(f, "Secret has not been released yet: {}", secret)
+            }
+            SmartVaultErr::SecretNotAssigned(secret) => {
+                write!(f, "Secret is not assigned to the beneficiary: {}", secret)
+            }
+            SmartVaultErr::InvalidBeneficiarySecrets(id) => {
+                write!(f, "Invalid secret assignment: {}", id)
+            }
+            SmartVaultErr::InvalidBackupValidator(validator) => {
+                write!(f, "Validator is listed more than once: {}", validator)
+            }
+            SmartVaultErr::ConditionTemplateDoesNotExist(id) => {
+                write!(f, "Condition template does not exist: {}", id)
+            }
+            SmartVaultErr::InvalidConditionTemplate(reason) => {
+                write!(f, "Invalid condition template: {}", reason)
+            }
+            SmartVaultErr::InvalidVacation(reason) => {
+                write!(f, "Invalid vacation: {}", reason)
+            }
+            SmartVaultErr::CallerNotDeadlineExtender(user) => {
+                write!(f, "Caller is not allowed to extend the deadlines of user: {}", user)
+            }
+            SmartVaultErr::InvalidDeadlineExtension(reason) => {
+                write!(f, "Invalid deadline extension: {}", reason)
+            }
+            SmartVaultErr::InvalidDeadMansSwitch(reason) => {
+                write!(f, "Invalid dead man's switch: {}", reason)
+            }
+            SmartVaultErr::DeadMansSwitchInUse(policy_id) => {
+                write!(f, "Dead man's switch is referenced by policy: {}", policy_id)
+            }
+            SmartVaultErr::InvalidBeneficiaryChallenge(reason) => {
+                write!(f, "Invalid beneficiary challenge: {}", reason)
+            }
+            SmartVaultErr::BeneficiaryChallengeRequired(question) => {
+                write!(f, "Answer the challenge of the policy first: {}", question)
+            }
+            SmartVaultErr::WrongChallengeAnswer(attempts_left) => {
+                write!(f, "Wrong answer, attempts left: {}", attempts_left)
+            }
+            SmartVaultErr::ChallengeRateLimited(retry_at) => {
+                write!(f, "Too many attempts, retry at: {}", retry_at)
+            }
+            SmartVaultErr::BeneficiaryChallengeLocked(locked_until) => {
+                write!(f, "Challenge is locked until: {}", locked_until)
+            }
+            SmartVaultErr::InvalidTimeZone(time_zone) => {
+                write!(f, "Time zone {} is invalid", time_zone)
+            }
+            SmartVaultErr::PolicyExpired(id) => {
+                write!(f, "Policy has expired: {}", id)
+            }
+            SmartVaultErr::PolicyDependencyCycle(id) => {
+                write!(f, "Dependencies of policy {} form a cycle", id)
+            }
+            SmartVaultErr::CallerNotVetoer(id) => {
+                write!(f, "Caller is not allowed to veto the release of policy: {}", id)
             }
             SmartVaultErr::KeyGenerationNotAllowed => {
                 write!(f, "Key cannot be generated because some conditions are not met")
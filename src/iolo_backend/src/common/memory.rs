@@ -13,6 +13,8 @@ const STABLE_BTREE_SECRETS: MemoryId = MemoryId::new(2);
 const STABLE_BTREE_POLICIES: MemoryId = MemoryId::new(3);
 const STABLE_BTREE_POLICIES_B2P: MemoryId = MemoryId::new(4);
 const STABLE_BTREE_POLICIES_V2P: MemoryId = MemoryId::new(5);
+const STABLE_BTREE_CONDITION_TEMPLATES: MemoryId = MemoryId::new(6);
+const STABLE_BTREE_ORACLES: MemoryId = MemoryId::new(7);
 
 pub type Memory = VirtualMemory<DefaultMemoryImpl>;
 
@@ -45,3 +47,11 @@ pub fn get_stable_btree_memory_for_policies_b2p() -> Memory {
 pub fn get_stable_btree_memory_for_policies_v2p() -> Memory {
     MEMORY_MANAGER.with(|m| m.borrow().get(STABLE_BTREE_POLICIES_V2P))
 }
+
+pub fn get_stable_btree_memory_for_condition_templates() -> Memory {
+    MEMORY_MANAGER.with(|m| m.borrow().get(STABLE_BTREE_CONDITION_TEMPLATES))
+}
+
+pub fn get_stable_btree_memory_for_oracles() -> Memory {
+    MEMORY_MANAGER.with(|m| m.borrow().get(STABLE_BTREE_ORACLES))
+}
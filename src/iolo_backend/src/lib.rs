@@ -7,9 +7,20 @@ use utils::login_date_condition::init_time_based_conditions_checks;
 
 // for the candid file creation
 use crate::common::error::SmartVaultErr;
+use crate::policies::condition_templates::ApplyConditionTemplateArgs;
+use crate::policies::condition_templates::ConditionTemplate;
+use crate::policies::condition_templates::ConditionTemplateID;
+use crate::policies::condition_templates::ConditionTemplateUpdate;
+use crate::policies::condition_templates::CreateConditionTemplateArgs;
+use crate::policies::condition_templates::UpdateConditionTemplateArgs;
+use crate::policies::conditions::CommitXOutOfYVoteArgs;
 use crate::policies::conditions::ConfirmXOutOfYConditionArgs;
+use crate::policies::conditions::FileClaimArgs;
+use crate::policies::conditions::QuorumHealth;
+use crate::policies::conditions::RevealXOutOfYVoteArgs;
+use crate::policies::forecast::ForecastPolicyArgs;
+use crate::policies::forecast::PolicyForecast;
 use crate::policies::policy::CreatePolicyArgs;
-use crate::policies::policy::Policy;
 use crate::policies::policy::PolicyForValidator;
 use crate::policies::policy::PolicyID;
 use crate::policies::policy::PolicyListEntry;
@@ -22,6 +33,14 @@ use crate::smart_vaults::key_manager::PolicyKeyDerviationArgs;
 use crate::users::contact::CreateContactArgs;
 use crate::users::contact::Contact;
 use crate::users::user::AddOrUpdateUserArgs;
+use crate::users::user::DeadlineExtension;
+use crate::users::user::ExtendDeadlineArgs;
+use crate::policies::beneficiary_challenges::AnswerBeneficiaryChallengeArgs;
+use crate::policies::beneficiary_challenges::ChallengeToken;
+use crate::policies::beneficiary_challenges::SetBeneficiaryChallengeArgs;
+use crate::users::dead_mans_switch::ConfirmDeadMansSwitchArgs;
+use crate::users::dead_mans_switch::DeadMansSwitchForValidator;
+use crate::users::dead_mans_switch::UpdateDeadMansSwitchArgs;
 use crate::users::user::PrincipalID;
 use crate::users::user::User;
 
@@ -0,0 +1,262 @@
+//! Knowledge-based challenges a beneficiary has to answer before the secrets of a released policy
+//! are revealed, e.g. a family passphrase. Answers are only stored as salted PBKDF2-HMAC-SHA256 hash.
+//! A correct answer grants a time-limited access token, failed attempts are rate-limited and locked out
+//! for a period that doubles with every further lockout.
+
+use candid::{CandidType, Deserialize};
+use pbkdf2::pbkdf2_hmac;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::common::error::SmartVaultErr;
+use crate::policies::policy::PolicyID;
+use crate::users::user::PrincipalID;
+use crate::utils::time;
+
+/// Number of PBKDF2 iterations applied to the salted answer
+pub const CHALLENGE_HASH_ITERATIONS: u32 = 10_000;
+
+/// Maximum number of characters of a challenge question
+pub const MAX_CHALLENGE_QUESTION_LENGTH: usize = 500;
+
+/// Validity of the access token granted for a correct answer (1 hour in nanoseconds)
+pub const CHALLENGE_TOKEN_VALIDITY: u64 = 60 * 60 * 1_000_000_000;
+
+/// Minimum time between a failed attempt and the next attempt (1 minute in nanoseconds)
+pub const CHALLENGE_RETRY_DELAY: u64 = 60 * 1_000_000_000;
+
+/// Number of consecutive failed attempts after which the challenge is locked
+pub const MAX_FAILED_CHALLENGE_ATTEMPTS: u32 = 5;
+
+/// Number of days a challenge stays locked after the first lockout, doubled with every further lockout
+pub const CHALLENGE_LOCKOUT_DAYS: u64 = 1;
+
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone)]
+pub struct BeneficiaryChallenge {
+    pub question: String,
+    salt: String,
+    answer_hash: String,
+    /// Consecutive failed attempts since the last correct answer or lockout
+    pub failed_attempts: u32,
+    pub last_failed_attempt: Option<u64>,
+    pub locked_until: Option<u64>,
+    /// Number of lockouts since the last correct answer
+    pub lockouts: Option<u32>,
+    /// Hash of the access token granted with the last correct answer
+    token_hash: Option<String>,
+    pub token_expires_at: Option<u64>,
+}
+
+/// Challenge as shown to the owner and the beneficiary, without the hashes
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone, PartialEq)]
+pub struct BeneficiaryChallengeStatus {
+    pub question: String,
+    pub failed_attempts: u32,
+    pub locked_until: Option<u64>,
+    pub lockouts: u32,
+    pub token_expires_at: Option<u64>,
+}
+
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone)]
+pub struct SetBeneficiaryChallengeArgs {
+    pub policy_id: PolicyID,
+    pub beneficiary: PrincipalID,
+    pub question: String,
+    pub answer: String,
+}
+
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone)]
+pub struct AnswerBeneficiaryChallengeArgs {
+    pub policy_id: PolicyID,
+    pub answer: String,
+}
+
+/// Access token to be passed along with the requests for the secrets of the policy
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone)]
+pub struct ChallengeToken {
+    pub token: String,
+    pub expires_at: u64,
+}
+
+/// Hex encoded PBKDF2-HMAC-SHA256 key of the answer, derived with the salt
+fn hash_answer(answer: &str, salt: &str) -> String {
+    let mut hash = [0u8; 32];
+    pbkdf2_hmac::<Sha256>(
+        answer.as_bytes(),
+        salt.as_bytes(),
+        CHALLENGE_HASH_ITERATIONS,
+        &mut hash,
+    );
+    hex::encode(hash)
+}
+
+impl BeneficiaryChallenge {
+    pub fn new(question: String, answer: &str, salt: String) -> Result<Self, SmartVaultErr> {
+        if question.trim().is_empty() || question.chars().count() > MAX_CHALLENGE_QUESTION_LENGTH {
+            return Err(SmartVaultErr::InvalidBeneficiaryChallenge(format!(
+                "question must have 1 to {} characters",
+                MAX_CHALLENGE_QUESTION_LENGTH
+            )));
+        }
+        if answer.is_empty() {
+            return Err(SmartVaultErr::InvalidBeneficiaryChallenge(
+                "answer must not be empty".to_string(),
+            ));
+        }
+        Ok(Self {
+            question,
+            answer_hash: hash_answer(answer, &salt),
+            salt,
+            failed_attempts: 0,
+            last_failed_attempt: None,
+            locked_until: None,
+            lockouts: None,
+            token_hash: None,
+            token_expires_at: None,
+        })
+    }
+
+    /// Checks the answer at `now`. A correct answer grants the given token,
+    /// a wrong one counts as failed attempt and locks the challenge once too many attempts failed.
+    /// Every further lockout doubles the lock duration until the beneficiary answers correctly.
+    pub fn answer(
+        &mut self,
+        answer: &str,
+        token: String,
+        now: u64,
+    ) -> Result<ChallengeToken, SmartVaultErr> {
+        match self.locked_until {
+            Some(locked_until) if now < locked_until => {
+                return Err(SmartVaultErr::BeneficiaryChallengeLocked(
+                    locked_until.to_string(),
+                ));
+            }
+            Some(_) => {
+                // the lockout is over, the beneficiary gets a fresh set of attempts
+                self.locked_until = None;
+                self.failed_attempts = 0;
+            }
+            None => {}
+        }
+        if let Some(last_failed_attempt) = self.last_failed_attempt {
+            let retry_at = last_failed_attempt.saturating_add(CHALLENGE_RETRY_DELAY);
+            if now < retry_at {
+                return Err(SmartVaultErr::ChallengeRateLimited(retry_at.to_string()));
+            }
+        }
+
+        if hash_answer(answer, &self.salt) != self.answer_hash {
+            self.failed_attempts += 1;
+            self.last_failed_attempt = Some(now);
+            if self.failed_attempts >= MAX_FAILED_CHALLENGE_ATTEMPTS {
+                let lockouts = self.lockouts.unwrap_or_default().saturating_add(1);
+                let lockout = 2u64
+                    .saturating_pow(lockouts - 1)
+                    .saturating_mul(CHALLENGE_LOCKOUT_DAYS * time::NANOS_PER_DAY);
+                self.lockouts = Some(lockouts);
+                self.locked_until = Some(now.saturating_add(lockout));
+            }
+            return Err(SmartVaultErr::WrongChallengeAnswer(
+                MAX_FAILED_CHALLENGE_ATTEMPTS
+                    .saturating_sub(self.failed_attempts)
+                    .to_string(),
+            ));
+        }
+
+        self.failed_attempts = 0;
+        self.last_failed_attempt = None;
+        self.lockouts = None;
+        let expires_at = now.saturating_add(CHALLENGE_TOKEN_VALIDITY);
+        self.token_hash = Some(hex::encode(Sha256::digest(&token)));
+        self.token_expires_at = Some(expires_at);
+        Ok(ChallengeToken { token, expires_at })
+    }
+
+    /// Checks that the token has been granted by this challenge and has not yet expired
+    pub fn check_token(&self, token: Option<&str>, now: u64) -> Result<(), SmartVaultErr> {
+        let valid = match (token, &self.token_hash, self.token_expires_at) {
+            (Some(token), Some(token_hash), Some(expires_at)) => {
+                now < expires_at && &hex::encode(Sha256::digest(token)) == token_hash
+            }
+            _ => false,
+        };
+        if !valid {
+            return Err(SmartVaultErr::BeneficiaryChallengeRequired(
+                self.question.to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn status(&self) -> BeneficiaryChallengeStatus {
+        BeneficiaryChallengeStatus {
+            question: self.question.clone(),
+            failed_attempts: self.failed_attempts,
+            locked_until: self.locked_until,
+            lockouts: self.lockouts.unwrap_or_default(),
+            token_expires_at: self.token_expires_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::error::SmartVaultErr;
+    use crate::utils::time::NANOS_PER_DAY;
+
+    use super::{
+        BeneficiaryChallenge, CHALLENGE_RETRY_DELAY, CHALLENGE_TOKEN_VALIDITY,
+        MAX_FAILED_CHALLENGE_ATTEMPTS,
+    };
+
+    #[test]
+    fn utest_beneficiary_challenge() {
+        let now: u64 = 1_700_000_000 * 1_000_000_000;
+        let mut challenge = BeneficiaryChallenge::new(
+            "Name of our first dog?".to_string(),
+            "Bello",
+            "salt".to_string(),
+        )
+        .unwrap();
+        assert_ne!(challenge.answer_hash, "Bello");
+        assert!(BeneficiaryChallenge::new(" ".to_string(), "Bello", "salt".to_string()).is_err());
+
+        // wrong answers are rate-limited and locked out
+        let result = challenge.answer("Rex", "t1".to_string(), now);
+        assert!(result.is_err_and(|e| matches!(e, SmartVaultErr::WrongChallengeAnswer(_))));
+        let result = challenge.answer("Bello", "t1".to_string(), now + 1);
+        assert!(result.is_err_and(|e| matches!(e, SmartVaultErr::ChallengeRateLimited(_))));
+        let mut at = now;
+        for _ in 1..MAX_FAILED_CHALLENGE_ATTEMPTS {
+            at += CHALLENGE_RETRY_DELAY;
+            challenge.answer("Rex", "t1".to_string(), at).unwrap_err();
+        }
+        assert_eq!(challenge.locked_until, Some(at + NANOS_PER_DAY));
+        let result = challenge.answer("Bello", "t1".to_string(), at + CHALLENGE_RETRY_DELAY);
+        assert!(result.is_err_and(|e| matches!(e, SmartVaultErr::BeneficiaryChallengeLocked(_))));
+
+        // every further lockout doubles the lock duration
+        at += NANOS_PER_DAY;
+        for _ in 0..MAX_FAILED_CHALLENGE_ATTEMPTS {
+            at += CHALLENGE_RETRY_DELAY;
+            challenge.answer("Rex", "t1".to_string(), at).unwrap_err();
+        }
+        assert_eq!(challenge.locked_until, Some(at + 2 * NANOS_PER_DAY));
+        assert_eq!(challenge.status().lockouts, 2);
+        let result = challenge.answer("Bello", "t1".to_string(), at + NANOS_PER_DAY);
+        assert!(result.is_err_and(|e| matches!(e, SmartVaultErr::BeneficiaryChallengeLocked(_))));
+
+        // after the lockout the correct answer grants a time-limited token
+        at += 2 * NANOS_PER_DAY;
+        let token = challenge.answer("Bello", "t2".to_string(), at).unwrap();
+        assert_eq!(token.expires_at, at + CHALLENGE_TOKEN_VALIDITY);
+        assert_eq!(challenge.failed_attempts, 0);
+        assert_eq!(challenge.status().lockouts, 0);
+        challenge.check_token(Some("t2"), at + 1).unwrap();
+        assert!(challenge.check_token(Some("t1"), at + 1).is_err());
+        assert!(challenge.check_token(None, at + 1).is_err());
+        assert!(challenge
+            .check_token(Some("t2"), token.expires_at)
+            .is_err_and(|e| matches!(e, SmartVaultErr::BeneficiaryChallengeRequired(_))));
+    }
+}
@@ -0,0 +1,85 @@
+use ic_stable_structures::StableBTreeMap;
+use serde::{Deserialize, Serialize};
+
+use crate::common::{
+    error::SmartVaultErr,
+    memory::{get_stable_btree_memory_for_condition_templates, Memory},
+};
+use crate::users::user::PrincipalID;
+
+use super::condition_templates::{ConditionTemplate, ConditionTemplateID};
+
+#[derive(Serialize, Deserialize)]
+pub struct ConditionTemplateStore {
+    #[serde(skip, default = "init_stable_data")]
+    pub templates: StableBTreeMap<ConditionTemplateID, ConditionTemplate, Memory>,
+}
+
+fn init_stable_data() -> StableBTreeMap<ConditionTemplateID, ConditionTemplate, Memory> {
+    StableBTreeMap::init(get_stable_btree_memory_for_condition_templates())
+}
+
+impl Default for ConditionTemplateStore {
+    fn default() -> Self {
+        Self {
+            templates: init_stable_data(),
+        }
+    }
+}
+
+impl ConditionTemplateStore {
+    pub fn new() -> Self {
+        Self {
+            templates: init_stable_data(),
+        }
+    }
+
+    pub fn get(
+        &self,
+        template_id: &ConditionTemplateID,
+    ) -> Result<ConditionTemplate, SmartVaultErr> {
+        self.templates
+            .get(template_id)
+            .ok_or_else(|| SmartVaultErr::ConditionTemplateDoesNotExist(template_id.to_string()))
+    }
+
+    pub fn get_templates_of_owner(&self, owner: &PrincipalID) -> Vec<ConditionTemplate> {
+        self.templates
+            .iter()
+            .map(|(_, template)| template)
+            .filter(|template| template.owner.as_ref() == Some(owner))
+            .collect()
+    }
+
+    pub fn add_template(
+        &mut self,
+        template: ConditionTemplate,
+    ) -> Result<ConditionTemplate, SmartVaultErr> {
+        self.templates.insert(template.id.clone(), template.clone());
+        Ok(template)
+    }
+
+    pub fn update_template(
+        &mut self,
+        template: ConditionTemplate,
+    ) -> Result<ConditionTemplate, SmartVaultErr> {
+        match self.templates.insert(template.id.clone(), template.clone()) {
+            Some(_) => Ok(template),
+            None => Err(SmartVaultErr::ConditionTemplateDoesNotExist(
+                template.id.to_string(),
+            )),
+        }
+    }
+
+    pub fn delete_template(
+        &mut self,
+        template_id: &ConditionTemplateID,
+    ) -> Result<(), SmartVaultErr> {
+        match self.templates.remove(template_id) {
+            Some(_) => Ok(()),
+            None => Err(SmartVaultErr::ConditionTemplateDoesNotExist(
+                template_id.to_string(),
+            )),
+        }
+    }
+}
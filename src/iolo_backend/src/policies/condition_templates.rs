@@ -0,0 +1,294 @@
+//! Condition templates are reusable sets of conditions, e.g. "180 days of inactivity AND 2 out of 3 family members".
+//! Built-in templates are provided by the canister, user-defined templates are stored in the condition template store.
+//! Applying a template to a policy replaces its conditions. Linked policies follow later edits of the template.
+
+use std::borrow::Cow;
+use std::mem::discriminant;
+
+use candid::{CandidType, Decode, Deserialize, Encode};
+use ic_stable_structures::storable::Bound;
+use ic_stable_structures::Storable;
+use serde::Serialize;
+
+use crate::common::error::SmartVaultErr;
+use crate::users::user::PrincipalID;
+use crate::utils::time;
+
+use super::conditions::{
+    CheckInSchedule, Condition, Duration, DurationUnit, UpdateCheckInCondition, UpdateCondition,
+    UpdateConditionExpression, UpdateLastLoginTimeCondition,
+};
+use super::policy::{LogicalOperator, PolicyID};
+
+pub type ConditionTemplateID = String;
+
+/// Prefix of the ids of built-in templates
+pub const BUILTIN_TEMPLATE_PREFIX: &str = "builtin-";
+
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone)]
+pub struct ConditionTemplate {
+    pub id: ConditionTemplateID,
+    /// Owner of a user-defined template, none for built-in templates
+    pub owner: Option<PrincipalID>,
+    pub name: String,
+    pub description: Option<String>,
+    pub conditions: Vec<UpdateCondition>,
+    pub conditions_logical_operator: Option<LogicalOperator>,
+    pub conditions_expression: Option<UpdateConditionExpression>,
+    pub date_created: u64,
+    pub date_modified: u64,
+}
+
+impl Storable for ConditionTemplate {
+    fn to_bytes(&self) -> std::borrow::Cow<[u8]> {
+        Cow::Owned(Encode!(self).unwrap())
+    }
+
+    fn from_bytes(bytes: std::borrow::Cow<[u8]>) -> Self {
+        Decode!(bytes.as_ref(), Self).unwrap()
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone)]
+pub struct CreateConditionTemplateArgs {
+    pub name: String,
+    pub description: Option<String>,
+    pub conditions: Vec<UpdateCondition>,
+    pub conditions_logical_operator: Option<LogicalOperator>,
+    pub conditions_expression: Option<UpdateConditionExpression>,
+}
+
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone)]
+pub struct UpdateConditionTemplateArgs {
+    pub id: ConditionTemplateID,
+    pub name: String,
+    pub description: Option<String>,
+    pub conditions: Vec<UpdateCondition>,
+    pub conditions_logical_operator: Option<LogicalOperator>,
+    pub conditions_expression: Option<UpdateConditionExpression>,
+}
+
+/// Result of a template edit
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone)]
+pub struct ConditionTemplateUpdate {
+    pub template: ConditionTemplate,
+    /// Linked policies which failed validation with the new conditions,
+    /// they keep their conditions and are no longer linked to the template
+    pub unlinked_policies: Vec<PolicyID>,
+}
+
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone)]
+pub struct ApplyConditionTemplateArgs {
+    pub policy_id: PolicyID,
+    pub template_id: ConditionTemplateID,
+    /// Keeps the policy linked to the template, so that edits of the template are applied to the policy
+    pub linked: bool,
+}
+
+impl ConditionTemplate {
+    pub fn new(
+        id: ConditionTemplateID,
+        owner: &PrincipalID,
+        args: CreateConditionTemplateArgs,
+    ) -> Self {
+        let now: u64 = time::get_current_time();
+        let mut template = Self {
+            id,
+            owner: Some(owner.to_string()),
+            name: args.name,
+            description: args.description,
+            conditions: args.conditions,
+            conditions_logical_operator: args.conditions_logical_operator,
+            conditions_expression: args.conditions_expression,
+            date_created: now,
+            date_modified: now,
+        };
+        template.clear_condition_ids();
+        template
+    }
+
+    pub fn update(&mut self, args: UpdateConditionTemplateArgs) {
+        self.name = args.name;
+        self.description = args.description;
+        self.conditions = args.conditions;
+        self.conditions_logical_operator = args.conditions_logical_operator;
+        self.conditions_expression = args.conditions_expression;
+        self.date_modified = time::get_current_time();
+        self.clear_condition_ids();
+    }
+
+    /// Conditions of a template never reference the conditions of a policy
+    fn clear_condition_ids(&mut self) {
+        for condition in self.conditions.iter_mut() {
+            condition.set_id(None);
+        }
+    }
+
+    pub fn is_builtin(&self) -> bool {
+        self.owner.is_none()
+    }
+
+    /// Checks what can be checked without a policy, the conditions themselves
+    /// are validated like any other conditions when the template is applied
+    pub fn validate(&self) -> Result<(), SmartVaultErr> {
+        if self.name.trim().is_empty() {
+            return Err(SmartVaultErr::InvalidConditionTemplate(
+                "name must not be empty".to_string(),
+            ));
+        }
+        if self.conditions.is_empty() {
+            return Err(SmartVaultErr::InvalidConditionTemplate(
+                "a template needs at least one condition".to_string(),
+            ));
+        }
+        if let Some(expression) = &self.conditions_expression {
+            if self.conditions_logical_operator.is_some() {
+                return Err(SmartVaultErr::InvalidConditionExpression(
+                    "Logical operator and condition expression cannot be used together".to_string(),
+                ));
+            }
+            expression.validate(self.conditions.len())?;
+        }
+        for condition in self.conditions.iter() {
+            match condition {
+                UpdateCondition::XOutOfY(c) => c.validate()?,
+                UpdateCondition::LastLogin(c) => c.validate()?,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Conditions to be passed to update_policy. A condition of the policy at the same position and of the same type
+    /// is updated instead of replaced, so that its state (e.g. votes or check-ins) is kept.
+    pub fn instantiate(&self, existing: &[Condition]) -> Vec<UpdateCondition> {
+        self.conditions
+            .iter()
+            .enumerate()
+            .map(|(index, condition)| {
+                let mut condition = condition.clone();
+                if let Some(existing) = existing.get(index) {
+                    let existing = existing.into_update_condition();
+                    if discriminant(&existing) == discriminant(&condition) {
+                        condition.set_id(existing.id());
+                    }
+                }
+                condition
+            })
+            .collect()
+    }
+}
+
+/// Templates provided by the canister to all users
+pub fn builtin_templates() -> Vec<ConditionTemplate> {
+    let last_login = |inactivity: Duration| {
+        UpdateCondition::LastLogin(UpdateLastLoginTimeCondition {
+            id: None,
+            number_of_days_since_last_login: None,
+            inactivity: Some(inactivity),
+            activity_type: None,
+        })
+    };
+    let builtin = |id: &str, name: &str, description: &str, conditions: Vec<UpdateCondition>| {
+        ConditionTemplate {
+            id: format!("{}{}", BUILTIN_TEMPLATE_PREFIX, id),
+            owner: None,
+            name: name.to_string(),
+            description: Some(description.to_string()),
+            conditions,
+            conditions_logical_operator: None,
+            conditions_expression: None,
+            date_created: 0,
+            date_modified: 0,
+        }
+    };
+
+    vec![
+        builtin(
+            "inactivity-180",
+            "180 days of inactivity",
+            "Released if the owner has not logged in for 180 days",
+            vec![last_login(Duration::days(180))],
+        ),
+        builtin(
+            "inactivity-365",
+            "One year of inactivity",
+            "Released if the owner has not logged in for one year",
+            vec![last_login(Duration {
+                value: 1,
+                unit: DurationUnit::Years,
+            })],
+        ),
+        builtin(
+            "monthly-check-in",
+            "Monthly check-in",
+            "Released if the owner misses a check-in every 30 days by more than 7 days",
+            vec![UpdateCondition::CheckIn(UpdateCheckInCondition {
+                id: None,
+                schedule: CheckInSchedule::EveryNumberOfDays(30),
+                grace_period_days: 7,
+            })],
+        ),
+    ]
+}
+
+pub fn get_builtin_template(id: &ConditionTemplateID) -> Option<ConditionTemplate> {
+    builtin_templates().into_iter().find(|t| &t.id == id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{builtin_templates, ConditionTemplate, CreateConditionTemplateArgs};
+    use crate::policies::conditions::{
+        Condition, Duration, LastLoginTimeCondition, UpdateCondition, UpdateLastLoginTimeCondition,
+    };
+
+    #[test]
+    fn utest_condition_template() {
+        let last_login = |id: Option<String>| {
+            UpdateCondition::LastLogin(UpdateLastLoginTimeCondition {
+                id,
+                number_of_days_since_last_login: None,
+                inactivity: Some(Duration::days(90)),
+                activity_type: None,
+            })
+        };
+        let args = CreateConditionTemplateArgs {
+            name: "Quarterly".to_string(),
+            description: None,
+            conditions: vec![last_login(Some("foreign".to_string()))],
+            conditions_logical_operator: None,
+            conditions_expression: None,
+        };
+        let template = ConditionTemplate::new("1".to_string(), &"owner".to_string(), args);
+        assert!(template.validate().is_ok());
+        assert_eq!(template.conditions[0].id(), None);
+
+        // an existing condition of the same type keeps its id
+        let existing = Condition::LastLogin(LastLoginTimeCondition {
+            id: "existing".to_string(),
+            number_of_days_since_last_login: None,
+            inactivity: Some(Duration::days(30)),
+            activity_type: None,
+            condition_status: false,
+        });
+        assert_eq!(
+            template.instantiate(&[existing]),
+            vec![last_login(Some("existing".to_string()))]
+        );
+        assert_eq!(template.instantiate(&[]), vec![last_login(None)]);
+
+        let empty = ConditionTemplate {
+            conditions: vec![],
+            ..template
+        };
+        assert!(empty.validate().is_err());
+
+        for builtin in builtin_templates() {
+            assert!(builtin.is_builtin());
+            assert!(builtin.validate().is_ok());
+        }
+    }
+}
@@ -1,69 +1,1174 @@
-//! Conditions contain the logic required to define the dead man's switch mechanism. There are two type of conditions:
+//! Conditions contain the logic required to define the dead man's switch mechanism. There are the following types of conditions:
 //! 1. Time based conditions - Checks whether a certain time threshold is reached
 //! 2. X out of Y conditions - Checks whether X out of Y validators have voted "yes" on the condition
+//! 3. Check-in conditions - Checks whether the owner missed a scheduled check-in (plus grace period)
+//! 4. Policy dependency conditions - Checks whether another policy of the owner has met its conditions
+//! 5. Beneficiary claim conditions - Checks whether the owner left a claim of a beneficiary unanswered
+//! 6. Oracle conditions - Checks the proof-of-life signal reported by another canister
+//! 7. Dead man's switch conditions - Checks the account-level dead man's switch of the owner
 
+use crate::common::error::SmartVaultErr;
 use crate::common::uuid::UUID;
+use crate::policies::oracles::{
+    OracleFailure, OracleReply, ORACLE_CALL_TIMEOUT, ORACLE_POLL_INTERVAL,
+};
 use crate::policies::policy::PolicyID;
 use candid::{CandidType, Deserialize};
 use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashSet};
 
 use crate::users::user::{PrincipalID, User};
 use crate::utils::time;
 
-/// Defines the number of days since the last login of a user.
+/// Defines the time without login of a user after which the condition is met.
 #[derive(Debug, CandidType, Deserialize, Serialize, Clone)]
 pub struct LastLoginTimeCondition {
     pub id: ConditionID,
-    pub number_of_days_since_last_login: u64,
+    /// Deprecated, use inactivity. Moved to inactivity by the migration after an upgrade.
+    pub number_of_days_since_last_login: Option<u64>,
+    pub inactivity: Option<Duration>,
+    pub activity_type: Option<ActivityType>,
     pub condition_status: bool,
 }
 
-#[derive(Debug, CandidType, Deserialize, Serialize, Clone)]
+impl LastLoginTimeCondition {
+    /// Time without login after which the condition is met
+    pub fn inactivity(&self) -> Duration {
+        self.inactivity.clone().unwrap_or(Duration::days(
+            self.number_of_days_since_last_login.unwrap_or_default(),
+        ))
+    }
+
+    /// Point in time at which the user is inactive for too long if last seen at `last_seen` (in nanoseconds)
+    pub fn inactivity_deadline(&self, last_seen: u64) -> u64 {
+        self.inactivity().after(last_seen)
+    }
+
+    /// Last sign of life of the user which counts for this condition
+    pub fn last_seen(&self, user: &User) -> Option<u64> {
+        match self.activity_type.unwrap_or_default() {
+            ActivityType::ExplicitLogin => user.date_last_login,
+            ActivityType::AnyActivity => user.date_last_activity.max(user.date_last_login),
+        }
+    }
+}
+
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone, PartialEq)]
 pub struct UpdateLastLoginTimeCondition {
     pub id: Option<ConditionID>,
-    pub number_of_days_since_last_login: u64,
+    /// Deprecated, use inactivity. Only read if no inactivity is given.
+    pub number_of_days_since_last_login: Option<u64>,
+    pub inactivity: Option<Duration>,
+    pub activity_type: Option<ActivityType>,
+}
+
+impl UpdateLastLoginTimeCondition {
+    pub fn inactivity(&self) -> Duration {
+        self.inactivity.clone().unwrap_or(Duration::days(
+            self.number_of_days_since_last_login.unwrap_or_default(),
+        ))
+    }
+
+    /// Checks that the length of the inactivity is given
+    pub fn validate(&self) -> Result<(), SmartVaultErr> {
+        if self.inactivity.is_none() && self.number_of_days_since_last_login.is_none() {
+            return Err(SmartVaultErr::InvalidPolicyCondition);
+        }
+        Ok(())
+    }
+}
+
+/// Defines which activity of the owner counts as login, defaults to ExplicitLogin
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+pub enum ActivityType {
+    /// Only calls of update_user_login_date
+    #[default]
+    ExplicitLogin,
+    /// Any authenticated update call of the owner
+    AnyActivity,
+}
+
+/// Length of a time span. Months and years follow the calendar (UTC),
+/// e.g. one month after January 31 is the last day of February.
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone, PartialEq)]
+pub struct Duration {
+    pub value: u64,
+    pub unit: DurationUnit,
+}
+
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+pub enum DurationUnit {
+    Hours,
+    #[default]
+    Days,
+    Weeks,
+    Months,
+    Years,
+}
+
+impl Duration {
+    pub fn days(value: u64) -> Self {
+        Self {
+            value,
+            unit: DurationUnit::Days,
+        }
+    }
+
+    /// Returns the point in time this duration after `start` (both in nanoseconds)
+    pub fn after(&self, start: u64) -> u64 {
+        let fixed =
+            |nanos_per_unit: u64| start.saturating_add(self.value.saturating_mul(nanos_per_unit));
+        match self.unit {
+            DurationUnit::Hours => fixed(time::NANOS_PER_HOUR),
+            DurationUnit::Days => fixed(time::NANOS_PER_DAY),
+            DurationUnit::Weeks => fixed(7 * time::NANOS_PER_DAY),
+            DurationUnit::Months => time::add_months(start, self.value),
+            DurationUnit::Years => time::add_months(start, self.value.saturating_mul(12)),
+        }
+    }
 }
 
-/// Defines a moment in time in the future upon which a condition is valid
+/// Defines a moment in time in the future upon which a condition is valid.
+/// With an end date the condition is only valid within the window [datetime, end_datetime).
+/// With a time zone, datetime and end_datetime are wall-clock times of that zone
+/// (nanoseconds since 1970-01-01 00:00 local time), otherwise UTC.
+/// The zone is a fixed offset, both times are converted with the same offset (no daylight saving time).
 #[derive(Debug, CandidType, Deserialize, Serialize, Clone)]
 pub struct FixedDateTimeCondition {
     pub id: ConditionID,
     pub datetime: u64,
+    pub end_datetime: Option<u64>,
+    pub time_zone: Option<TimeZone>,
     pub condition_status: bool,
 }
 
-#[derive(Debug, CandidType, Deserialize, Serialize, Clone)]
+impl FixedDateTimeCondition {
+    /// Start of the condition in UTC
+    pub fn starts_at(&self) -> u64 {
+        TimeZone::to_utc(&self.time_zone, self.datetime)
+    }
+
+    /// End of the release window in UTC
+    pub fn ends_at(&self) -> Option<u64> {
+        self.end_datetime
+            .map(|end_datetime| TimeZone::to_utc(&self.time_zone, end_datetime))
+    }
+}
+
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone, PartialEq)]
 pub struct UpdateFixedDateTimeCondition {
     pub id: Option<ConditionID>,
     pub datetime: u64,
+    pub end_datetime: Option<u64>,
+    pub time_zone: Option<TimeZone>,
+}
+
+/// Time zone given as fixed offset to UTC, e.g. +60 minutes for CET or +120 minutes for CEST.
+/// Daylight saving time is not applied, the offset in effect at the date of the condition has to be given.
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone, PartialEq)]
+pub struct TimeZone {
+    /// Name for display only, e.g. "Europe/Zurich", the offset is not derived from it
+    pub name: Option<String>,
+    pub utc_offset_minutes: i32,
+}
+
+/// Offsets in use range from UTC-12:00 to UTC+14:00
+const MIN_UTC_OFFSET_MINUTES: i32 = -12 * 60;
+const MAX_UTC_OFFSET_MINUTES: i32 = 14 * 60;
+
+impl TimeZone {
+    pub fn is_valid(&self) -> bool {
+        (MIN_UTC_OFFSET_MINUTES..=MAX_UTC_OFFSET_MINUTES).contains(&self.utc_offset_minutes)
+    }
+
+    /// Converts a local time of the zone to UTC, without a zone the time is already UTC
+    pub fn to_utc(time_zone: &Option<TimeZone>, local_time: u64) -> u64 {
+        let Some(time_zone) = time_zone else {
+            return local_time;
+        };
+        let offset = time_zone.utc_offset_minutes.unsigned_abs() as u64 * time::NANOS_PER_MINUTE;
+        if time_zone.utc_offset_minutes >= 0 {
+            local_time.saturating_sub(offset)
+        } else {
+            local_time.saturating_add(offset)
+        }
+    }
+}
+
+/// Defines a recurring proof-of-life check-in the owner has to perform.
+/// The condition is valid if the owner did not check in before `next_deadline` plus the grace period.
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone)]
+pub struct CheckInCondition {
+    pub id: ConditionID,
+    pub schedule: CheckInSchedule,
+    pub grace_period_days: u64,
+    pub last_check_in: u64,
+    pub next_deadline: u64,
+    pub condition_status: bool,
+}
+
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone, PartialEq)]
+pub struct UpdateCheckInCondition {
+    pub id: Option<ConditionID>,
+    pub schedule: CheckInSchedule,
+    pub grace_period_days: u64,
+}
+
+/// Makes a policy depend on the release of another policy of the same owner.
+/// The condition is valid once the referenced policy has been released and the optional delay has passed.
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone)]
+pub struct PolicyDependencyCondition {
+    pub id: ConditionID,
+    pub policy_id: PolicyID,
+    pub delay_days: Option<u64>,
+    /// Time at which the referenced policy was found to be released
+    pub dependency_met_at: Option<u64>,
+    pub condition_status: bool,
+}
+
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone, PartialEq)]
+pub struct UpdatePolicyDependencyCondition {
+    pub id: Option<ConditionID>,
+    pub policy_id: PolicyID,
+    pub delay_days: Option<u64>,
+}
+
+/// Minimum number of days between two claims of the same beneficiary
+pub const CLAIM_COOLDOWN_DAYS: u64 = 30;
+
+/// Lets a beneficiary claim that the owner has passed away.
+/// The owner can reject a claim by signing in within the response period,
+/// otherwise the condition becomes valid.
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone)]
+pub struct BeneficiaryClaimCondition {
+    pub id: ConditionID,
+    pub response_period_days: u64,
+    pub pending_claim: Option<BeneficiaryClaim>,
+    /// Date of the last claim per beneficiary, used for rate-limiting
+    pub last_claims: BTreeMap<PrincipalID, u64>,
+    pub condition_status: bool,
+}
+
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone, PartialEq)]
+pub struct UpdateBeneficiaryClaimCondition {
+    pub id: Option<ConditionID>,
+    pub response_period_days: u64,
+}
+
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone)]
+pub struct BeneficiaryClaim {
+    pub claimant: PrincipalID,
+    pub filed_at: u64,
+    pub response_deadline: u64,
+}
+
+impl BeneficiaryClaimCondition {
+    /// Files a claim of a beneficiary at `now`
+    pub fn file_claim(&mut self, claimant: &PrincipalID, now: u64) -> Result<(), SmartVaultErr> {
+        if self.condition_status {
+            return Err(SmartVaultErr::InvalidPolicyCondition);
+        }
+        if let Some(claim) = &self.pending_claim {
+            return Err(SmartVaultErr::ClaimAlreadyPending(
+                claim.claimant.to_string(),
+            ));
+        }
+        if let Some(last_claim) = self.last_claims.get(claimant) {
+            let cooldown = CLAIM_COOLDOWN_DAYS.saturating_mul(time::NANOS_PER_DAY);
+            if now < last_claim.saturating_add(cooldown) {
+                return Err(SmartVaultErr::ClaimRateLimited(claimant.to_string()));
+            }
+        }
+
+        self.pending_claim = Some(BeneficiaryClaim {
+            claimant: claimant.to_string(),
+            filed_at: now,
+            response_deadline: now.saturating_add(
+                self.response_period_days
+                    .saturating_mul(time::NANOS_PER_DAY),
+            ),
+        });
+        self.last_claims.insert(claimant.to_string(), now);
+        Ok(())
+    }
+
+    /// Drops the pending claim if the owner has signed in since it was filed.
+    /// Returns true if a claim has been rejected.
+    pub fn reject_claim_on_login(&mut self, owner: &User) -> bool {
+        let rejected = self.pending_claim.as_ref().is_some_and(|claim| {
+            owner
+                .date_last_login
+                .is_some_and(|last_login| last_login > claim.filed_at)
+        });
+        if rejected {
+            self.pending_claim = None;
+        }
+        rejected
+    }
+}
+
+/// Delegates the proof-of-life check to an oracle canister, which is called periodically.
+/// The condition is valid once the oracle reports it as met, or reports a last activity
+/// of the owner which is older than the allowed number of days.
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone)]
+pub struct OracleCondition {
+    pub id: ConditionID,
+    pub canister_id: String,
+    pub method: String,
+    pub max_days_inactive: Option<u64>,
+    pub last_reply: Option<OracleReply>,
+    pub last_reply_at: Option<u64>,
+    pub last_failure: Option<OracleFailure>,
+    /// Start of the last oracle call
+    pub last_call_at: Option<u64>,
+    pub call_pending: bool,
+    pub condition_status: bool,
+}
+
+/// References the account-level dead man's switch of the owner, see `User::dead_mans_switch`.
+/// The switch is evaluated once per user, the condition only mirrors its status.
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone)]
+pub struct DeadMansSwitchCondition {
+    pub id: ConditionID,
+    pub condition_status: bool,
+}
+
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone, PartialEq)]
+pub struct UpdateDeadMansSwitchCondition {
+    pub id: Option<ConditionID>,
+}
+
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone, PartialEq)]
+pub struct UpdateOracleCondition {
+    pub id: Option<ConditionID>,
+    pub canister_id: String,
+    pub method: String,
+    pub max_days_inactive: Option<u64>,
+}
+
+impl OracleCondition {
+    /// Whether the oracle has to be called at `now`
+    pub fn is_due(&self, now: u64) -> bool {
+        if self.condition_status {
+            return false;
+        }
+        match self.last_call_at {
+            _ if self.call_pending => false,
+            Some(last_call_at) => now.saturating_sub(last_call_at) >= ORACLE_POLL_INTERVAL,
+            None => true,
+        }
+    }
+
+    /// Records a pending call which has not been answered in time as failed.
+    /// Its late reply is ignored, the oracle is called again after the poll interval.
+    /// Returns true if the condition has been changed.
+    pub fn expire_call(&mut self, now: u64) -> bool {
+        match self.last_call_at {
+            Some(last_call_at)
+                if self.call_pending && now.saturating_sub(last_call_at) > ORACLE_CALL_TIMEOUT =>
+            {
+                self.call_pending = false;
+                self.last_failure = Some(OracleFailure {
+                    date: now,
+                    message: "The oracle call timed out".to_string(),
+                });
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Marks a new oracle call as pending
+    pub fn start_call(&mut self, now: u64) {
+        self.last_call_at = Some(now);
+        self.call_pending = true;
+    }
+
+    /// Records the result of the oracle call started at `started_at`.
+    /// Replies to superseded calls are ignored, late replies are recorded as failures.
+    /// Returns true if the condition has been changed.
+    pub fn record_reply(
+        &mut self,
+        started_at: u64,
+        now: u64,
+        result: Result<OracleReply, String>,
+    ) -> bool {
+        if !self.call_pending || self.last_call_at != Some(started_at) {
+            return false;
+        }
+        self.call_pending = false;
+
+        let failure = |message: &str| OracleFailure {
+            date: now,
+            message: message.to_string(),
+        };
+        match result {
+            _ if now.saturating_sub(started_at) > ORACLE_CALL_TIMEOUT => {
+                self.last_failure = Some(failure("The oracle call timed out"));
+            }
+            Err(message) => self.last_failure = Some(failure(&message)),
+            Ok(OracleReply::LastActivity(_)) if self.max_days_inactive.is_none() => {
+                self.last_failure = Some(failure(
+                    "The oracle replied with a last activity but no number of days is defined",
+                ));
+            }
+            Ok(reply) => {
+                self.last_reply = Some(reply);
+                self.last_reply_at = Some(now);
+                self.last_failure = None;
+            }
+        }
+        true
+    }
+}
+
+/// Defines when the next check-in is due
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone, PartialEq)]
+pub enum CheckInSchedule {
+    /// The owner has to check in every n days
+    EveryNumberOfDays(u64),
+    /// The owner has to check in until the end of the given day of each month (UTC).
+    /// Days beyond the length of a month are clamped to its last day.
+    DayOfMonth(u8),
+}
+
+impl CheckInSchedule {
+    pub fn is_valid(&self) -> bool {
+        match self {
+            CheckInSchedule::EveryNumberOfDays(days) => *days > 0,
+            CheckInSchedule::DayOfMonth(day) => (1..=31).contains(day),
+        }
+    }
+
+    /// Returns the deadline for the next check-in after a check-in at `check_in_time` (both in nanoseconds)
+    pub fn next_deadline(&self, check_in_time: u64) -> u64 {
+        match self {
+            CheckInSchedule::EveryNumberOfDays(days) => {
+                check_in_time.saturating_add(days.saturating_mul(time::NANOS_PER_DAY))
+            }
+            CheckInSchedule::DayOfMonth(day) => {
+                let check_in_day = (check_in_time / time::NANOS_PER_DAY) as i64;
+                let (mut year, mut month, _) = time::civil_from_days(check_in_day);
+                let mut due_day = time::days_from_civil(
+                    year,
+                    month,
+                    (*day as u32).min(time::days_in_month(year, month)),
+                );
+                if due_day <= check_in_day {
+                    // due day of this month is already over, take the one of next month
+                    if month == 12 {
+                        year += 1;
+                        month = 1;
+                    } else {
+                        month += 1;
+                    }
+                    due_day = time::days_from_civil(
+                        year,
+                        month,
+                        (*day as u32).min(time::days_in_month(year, month)),
+                    );
+                }
+                // the owner has time until the end of the due day
+                (due_day as u64 + 1) * time::NANOS_PER_DAY
+            }
+        }
+    }
 }
 
 /// The X out of Y condition contains a set of validators and defines a quorum.
 /// Each validator has its own status (true or false) and the condition is valid if the quorum is reached.
 /// Each Validator has to vote for the condition and the condition is valid if the quorum is reached.
 /// The overall condition status defines the status of the condition.
-#[derive(Debug, CandidType, Deserialize, Serialize, Clone)]
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone, Default)]
 pub struct XOutOfYCondition {
     pub id: ConditionID,
     pub validators: Vec<Validator>,
     pub question: String,
-    pub quorum: u64, // in absolute numbers
+    pub quorum: u64, // in absolute numbers (sum of weights) or percent, see quorum_type
+    pub quorum_type: Option<QuorumType>,
+    /// Once the first yes vote arrives the quorum must be reached within this number of days, otherwise all votes are reset
+    pub voting_window_days: Option<u64>,
+    /// Start of the running voting window (time of the first yes vote)
+    pub voting_window_start: Option<u64>,
+    /// Number of days a single vote stays valid
+    pub vote_validity_days: Option<u64>,
+    /// Reasons and evidence the validators attached to their current votes
+    pub vote_evidence: Option<BTreeMap<PrincipalID, VoteEvidence>>,
+    /// Backup validators in order of precedence, promoted when a validator deletes their account
+    pub backup_validators: Option<Vec<Validator>>,
+    /// Time at which the validators whose account still exists fell below the quorum
+    pub quorum_unreachable_since: Option<u64>,
+    /// Enables the secret ballot: votes are committed first and revealed once the commitments
+    /// cover the quorum or this number of days after the first commitment have passed.
+    /// Commitments which are not revealed within the same number of days after the commit deadline are dropped.
+    pub secret_ballot_days: Option<u64>,
+    /// Commitments of the validators in the running secret ballot
+    pub commitments: Option<BTreeMap<PrincipalID, VoteCommitment>>,
+    /// End of the commit phase of the running secret ballot
+    pub commit_deadline: Option<u64>,
+    /// No votes (sum of weights or percent, see quorum_type) which reject the condition
+    pub reject_quorum: Option<u64>,
+    /// Outcome of the current votes, a rejection blocks the condition even if the quorum is reached.
+    /// Conditions stored before outcomes existed have none, which is pending.
+    pub outcome: Option<VoteOutcome>,
+    /// Activity of the owner which clears pending yes votes, none keeps the votes
+    pub clear_votes_on_activity: Option<ActivityType>,
+    /// Tells the validators why their votes have been cleared, removed when they vote again
+    pub vote_clearances: Option<BTreeMap<PrincipalID, VoteClearance>>,
     pub condition_status: bool,
 }
 
+/// Notice to a validator whose vote has been cleared
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone, PartialEq)]
+pub struct VoteClearance {
+    pub cleared_at: u64,
+    pub reason: VoteClearanceReason,
+}
+
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone, Copy, PartialEq)]
+pub enum VoteClearanceReason {
+    /// The owner has been active before the quorum was reached
+    OwnerActivity,
+}
+
+/// Outcome of the votes of an XOutOfY condition
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+pub enum VoteOutcome {
+    #[default]
+    Pending,
+    Approved,
+    /// A guardian voted no or the reject quorum has been reached
+    Rejected,
+}
+
+/// Commitment of a validator to a vote in a secret ballot, see `vote_commitment`
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone, PartialEq)]
+pub struct VoteCommitment {
+    pub commitment: String,
+    pub committed_at: u64,
+    pub revealed_at: Option<u64>,
+}
+
+/// Hex encoded sha256 hash of "<validator>:<yes|no>:<salt>" a validator commits to in a secret ballot
+pub fn vote_commitment(principal_id: &PrincipalID, status: bool, salt: &str) -> String {
+    let vote = if status { "yes" } else { "no" };
+    hex::encode(Sha256::digest(format!(
+        "{}:{}:{}",
+        principal_id, vote, salt
+    )))
+}
+
+/// Maximum number of characters of the reason attached to a vote
+pub const MAX_VOTE_REASON_LENGTH: usize = 1_000;
+
+/// Maximum total size of the evidence attached to a vote (2 MiB)
+pub const MAX_VOTE_EVIDENCE_SIZE: usize = 2 * 1024 * 1024;
+
+/// Reason and evidence a validator attached to a vote
 #[derive(Debug, CandidType, Deserialize, Serialize, Clone)]
+pub struct VoteEvidence {
+    pub reason: Option<String>,
+    pub evidence: Vec<Evidence>,
+    pub visibility: EvidenceVisibility,
+    pub submitted_at: u64,
+}
+
+/// Document attached to a vote, e.g. a scanned death certificate.
+/// The content is encrypted by the validator before it is submitted.
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone)]
+pub struct Evidence {
+    pub name: Option<String>,
+    pub encrypted_content: Vec<u8>,
+}
+
+/// Defines who can see the reason and evidence of a vote besides the owner
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+pub enum EvidenceVisibility {
+    /// The beneficiaries once the policy is released
+    #[default]
+    Beneficiaries,
+    /// The other validators of the condition and the beneficiaries once the policy is released
+    Validators,
+}
+
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone, PartialEq, Default)]
 pub struct UpdateXOutOfYCondition {
     pub id: Option<ConditionID>,
     pub validators: Vec<Validator>,
     pub question: String,
-    pub quorum: u64, // in absolute numbers
+    pub quorum: u64, // in absolute numbers (sum of weights) or percent, see quorum_type
+    pub quorum_type: Option<QuorumType>,
+    pub voting_window_days: Option<u64>,
+    pub vote_validity_days: Option<u64>,
+    pub backup_validators: Vec<Validator>,
+    pub secret_ballot_days: Option<u64>,
+    pub reject_quorum: Option<u64>,
+    pub clear_votes_on_activity: Option<ActivityType>,
+}
+
+/// Defines how the quorum of an XOutOfY condition is interpreted, defaults to Absolute
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+pub enum QuorumType {
+    /// Sum of the weights of the approving validators
+    #[default]
+    Absolute,
+    /// Share of the total weight of all approving validators in percent
+    Percentage,
+}
+
+/// Defines how the vote of a validator is counted, defaults to Approver
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+pub enum ValidatorRole {
+    /// Votes count towards the quorum and the reject quorum
+    #[default]
+    Approver,
+    /// A single no vote rejects the condition, yes votes are not counted
+    Guardian,
 }
 
 /// Validator is the role a user has when it is part of of a condition and has to vote for it
-#[derive(Debug, CandidType, Deserialize, Serialize, Clone)]
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone, PartialEq, Default)]
 pub struct Validator {
     pub principal_id: PrincipalID,
     pub status: Option<bool>,
+    /// Number of votes the validator has, defaults to 1
+    pub weight: Option<u64>,
+    pub voted_at: Option<u64>,
+    pub vote_expires_at: Option<u64>,
+    pub role: Option<ValidatorRole>,
+}
+
+impl Validator {
+    pub fn weight(&self) -> u64 {
+        self.weight.unwrap_or(1)
+    }
+
+    pub fn is_guardian(&self) -> bool {
+        self.role == Some(ValidatorRole::Guardian)
+    }
+
+    /// A no vote which has been cast, a status preset by the owner does not count
+    fn voted_no(&self) -> bool {
+        self.status == Some(false) && self.voted_at.is_some()
+    }
+
+    fn clear_vote(&mut self) {
+        self.status = None;
+        self.voted_at = None;
+        self.vote_expires_at = None;
+    }
+}
+
+/// Quorum health of an XOutOfY condition, only validators whose account still exists can vote
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone, PartialEq)]
+pub struct QuorumHealth {
+    pub condition_id: ConditionID,
+    pub quorum: u64,
+    pub quorum_type: Option<QuorumType>,
+    /// Sum of the weights of the validators whose account still exists
+    pub reachable_weight: u64,
+    pub total_weight: u64,
+    /// Number of backup validators whose account still exists
+    pub backup_validators: u64,
+    pub quorum_reachable: bool,
+    pub quorum_unreachable_since: Option<u64>,
+}
+
+/// Sums up the weights of the given validators
+fn total_weight<'a>(validators: impl Iterator<Item = &'a Validator>) -> u64 {
+    validators.fold(0, |sum, v| sum.saturating_add(v.weight()))
+}
+
+impl XOutOfYCondition {
+    pub fn quorum_reached(&self) -> bool {
+        let approved = total_weight(self.approvers().filter(|v| v.status == Some(true)));
+        self.covers_quorum(approved)
+    }
+
+    pub fn is_guardian(&self, principal: &PrincipalID) -> bool {
+        self.validators
+            .iter()
+            .any(|v| &v.principal_id == principal && v.is_guardian())
+    }
+
+    /// Validators whose votes count towards the quorum, i.e. all validators but the guardians
+    fn approvers(&self) -> impl Iterator<Item = &Validator> {
+        self.validators.iter().filter(|v| !v.is_guardian())
+    }
+
+    /// Checks whether the given sum of weights is enough to reach the quorum
+    fn covers_quorum(&self, weight: u64) -> bool {
+        self.covers(weight, self.quorum)
+    }
+
+    fn covers(&self, weight: u64, quorum: u64) -> bool {
+        match self.quorum_type.unwrap_or_default() {
+            QuorumType::Absolute => weight >= quorum,
+            QuorumType::Percentage => {
+                // without approvers a percentage can never be reached
+                let total = total_weight(self.approvers());
+                total > 0 && weight as u128 * 100 >= quorum as u128 * total as u128
+            }
+        }
+    }
+
+    /// A single no vote of a guardian or the no votes of the approvers reaching the reject quorum reject the condition
+    pub fn rejected(&self) -> bool {
+        if self
+            .validators
+            .iter()
+            .any(|v| v.is_guardian() && v.voted_no())
+        {
+            return true;
+        }
+        let rejected = total_weight(self.approvers().filter(|v| v.voted_no()));
+        self.reject_quorum
+            .is_some_and(|reject_quorum| self.covers(rejected, reject_quorum))
+    }
+
+    /// Outcome of the current votes, a rejection takes precedence over the quorum
+    pub fn current_outcome(&self) -> VoteOutcome {
+        if self.rejected() {
+            VoteOutcome::Rejected
+        } else if self.quorum_reached() {
+            VoteOutcome::Approved
+        } else {
+            VoteOutcome::Pending
+        }
+    }
+
+    fn refresh_outcome(&mut self) {
+        self.outcome = Some(self.current_outcome());
+    }
+
+    /// Votes are counted right away, or in a secret ballot once the reveal phase has started
+    pub fn tally_open(&self, now: u64) -> bool {
+        self.secret_ballot_days.is_none() || self.reveal_open(now)
+    }
+
+    /// The reveal phase of a secret ballot starts when the commitments cover the quorum
+    /// or when the commit deadline has passed
+    pub fn reveal_open(&self, now: u64) -> bool {
+        if self.commit_deadline.is_some_and(|deadline| now > deadline) {
+            return true;
+        }
+        let Some(commitments) = self.commitments.as_ref().filter(|c| !c.is_empty()) else {
+            return false;
+        };
+        let committed = total_weight(
+            self.approvers()
+                .filter(|v| commitments.contains_key(&v.principal_id)),
+        );
+        self.covers_quorum(committed)
+    }
+
+    /// End of the reveal phase of the running secret ballot
+    pub fn reveal_deadline(&self) -> Option<u64> {
+        let days = self.secret_ballot_days?;
+        let commit_deadline = self.commit_deadline?;
+        Some(commit_deadline.saturating_add(days.saturating_mul(time::NANOS_PER_DAY)))
+    }
+
+    /// Drops the commitments which have not been revealed before the reveal deadline.
+    /// Returns true if any commitment has been dropped.
+    pub fn drop_unrevealed_commitments(&mut self, now: u64) -> bool {
+        let Some(deadline) = self.reveal_deadline() else {
+            return false;
+        };
+        let Some(commitments) = self.commitments.as_mut().filter(|_| now > deadline) else {
+            return false;
+        };
+        let committed = commitments.len();
+        commitments.retain(|_, c| c.revealed_at.is_some());
+        commitments.len() != committed
+    }
+
+    /// Records the commitment of a validator in a secret ballot. A commitment can be replaced until the
+    /// reveal phase starts. Once all commitments have been revealed or the reveal deadline has passed
+    /// the next commitment starts a new ballot.
+    pub fn commit_vote(
+        &mut self,
+        principal_id: &PrincipalID,
+        commitment: String,
+        now: u64,
+    ) -> Result<(), SmartVaultErr> {
+        let Some(days) = self.secret_ballot_days else {
+            return Err(SmartVaultErr::InvalidVoteCommitment(
+                "Condition has no secret ballot".to_string(),
+            ));
+        };
+        if !self
+            .validators
+            .iter()
+            .any(|v| &v.principal_id == principal_id)
+        {
+            return Err(SmartVaultErr::InvalidVoteCommitment(format!(
+                "{} is no validator of the condition",
+                principal_id
+            )));
+        }
+        self.drop_unrevealed_commitments(now);
+        if self.reveal_open(now) {
+            if self
+                .commitments
+                .iter()
+                .flat_map(|c| c.values())
+                .any(|c| c.revealed_at.is_none())
+            {
+                return Err(SmartVaultErr::InvalidVoteCommitment(
+                    "Commit phase has ended".to_string(),
+                ));
+            }
+            self.commitments = None;
+            self.commit_deadline = None;
+        }
+
+        if self.commit_deadline.is_none() {
+            self.commit_deadline =
+                Some(now.saturating_add(days.saturating_mul(time::NANOS_PER_DAY)));
+        }
+        self.commitments.get_or_insert_with(BTreeMap::new).insert(
+            principal_id.clone(),
+            VoteCommitment {
+                commitment,
+                committed_at: now,
+                revealed_at: None,
+            },
+        );
+        Ok(())
+    }
+
+    /// Reveals the committed vote of a validator in the reveal phase of a secret ballot and records it
+    pub fn reveal_vote(
+        &mut self,
+        principal_id: &PrincipalID,
+        status: bool,
+        salt: &str,
+        now: u64,
+    ) -> Result<(), SmartVaultErr> {
+        if !self.reveal_open(now) {
+            return Err(SmartVaultErr::InvalidVoteCommitment(
+                "Reveal phase has not started yet".to_string(),
+            ));
+        }
+        let expected = vote_commitment(principal_id, status, salt);
+        match self
+            .commitments
+            .as_mut()
+            .and_then(|c| c.get_mut(principal_id))
+        {
+            Some(c) if c.revealed_at.is_none() && c.commitment == expected => {
+                c.revealed_at = Some(now);
+            }
+            _ => {
+                return Err(SmartVaultErr::InvalidVoteCommitment(
+                    "Vote does not match an open commitment".to_string(),
+                ))
+            }
+        }
+        self.register_vote(principal_id, status, now);
+        Ok(())
+    }
+
+    /// Records the vote of a validator at `now`, starting the voting window with the first yes vote.
+    /// Returns false if the principal is no validator of the condition.
+    pub fn register_vote(&mut self, principal_id: &PrincipalID, status: bool, now: u64) -> bool {
+        let vote_validity_days = self.vote_validity_days;
+        let Some(validator) = self
+            .validators
+            .iter_mut()
+            .find(|v| &v.principal_id == principal_id)
+        else {
+            return false;
+        };
+
+        validator.status = Some(status);
+        validator.voted_at = Some(now);
+        validator.vote_expires_at =
+            vote_validity_days.map(|d| now.saturating_add(d.saturating_mul(time::NANOS_PER_DAY)));
+
+        if status && self.voting_window_days.is_some() && self.voting_window_start.is_none() {
+            self.voting_window_start = Some(now);
+        }
+        if let Some(vote_clearances) = self.vote_clearances.as_mut() {
+            vote_clearances.remove(principal_id);
+        }
+        self.refresh_outcome();
+        true
+    }
+
+    /// Clears the yes votes if the owner has shown the given activity before the quorum has been reached,
+    /// no votes agree with the owner being alive and are kept. Returns true if any vote has been cleared.
+    pub fn clear_votes_for_owner_activity(&mut self, activity: ActivityType, now: u64) -> bool {
+        let Some(clearing_activity) = self.clear_votes_on_activity else {
+            return false;
+        };
+        // an explicit login is activity as well
+        if clearing_activity == ActivityType::ExplicitLogin
+            && activity != ActivityType::ExplicitLogin
+        {
+            return false;
+        }
+        if self.condition_status || self.quorum_reached() {
+            return false;
+        }
+
+        let mut cleared = false;
+        for validator in self.validators.iter_mut() {
+            if validator.status == Some(true) {
+                validator.clear_vote();
+                self.vote_clearances
+                    .get_or_insert_with(BTreeMap::new)
+                    .insert(
+                        validator.principal_id.clone(),
+                        VoteClearance {
+                            cleared_at: now,
+                            reason: VoteClearanceReason::OwnerActivity,
+                        },
+                    );
+                cleared = true;
+            }
+        }
+        if cleared {
+            self.voting_window_start = None;
+            self.drop_stale_vote_data();
+        }
+        cleared
+    }
+
+    /// Replaces the reason and evidence of the validator's current vote
+    pub fn set_vote_evidence(
+        &mut self,
+        principal_id: &PrincipalID,
+        evidence: Option<VoteEvidence>,
+    ) {
+        match evidence {
+            Some(evidence) => self
+                .vote_evidence
+                .get_or_insert_with(BTreeMap::new)
+                .insert(principal_id.clone(), evidence),
+            None => self
+                .vote_evidence
+                .as_mut()
+                .and_then(|vote_evidence| vote_evidence.remove(principal_id)),
+        };
+    }
+
+    /// Drops the evidence and revealed commitments of votes which have been cleared
+    /// and everything of validators which have been removed. The outcome is refreshed.
+    fn drop_stale_vote_data(&mut self) {
+        let validators = &self.validators;
+        if let Some(vote_evidence) = self.vote_evidence.as_mut() {
+            vote_evidence.retain(|principal_id, _| {
+                validators
+                    .iter()
+                    .any(|v| &v.principal_id == principal_id && v.status.is_some())
+            });
+        }
+        if let Some(commitments) = self.commitments.as_mut() {
+            commitments.retain(|principal_id, c| {
+                validators.iter().any(|v| {
+                    &v.principal_id == principal_id
+                        && (c.revealed_at.is_none() || v.status.is_some())
+                })
+            });
+            if commitments.is_empty() {
+                self.commit_deadline = None;
+            }
+        }
+        if let Some(vote_clearances) = self.vote_clearances.as_mut() {
+            vote_clearances.retain(|principal_id, _| {
+                validators.iter().any(|v| &v.principal_id == principal_id)
+            });
+        }
+        self.refresh_outcome();
+    }
+
+    pub fn backup_validators(&self) -> &[Validator] {
+        self.backup_validators.as_deref().unwrap_or_default()
+    }
+
+    /// Removes a validator and promotes the first backup validator for which `is_reachable` holds.
+    /// Unreachable backup validators are dropped. Returns the promoted validator, if any.
+    pub fn substitute_validator(
+        &mut self,
+        principal_id: &PrincipalID,
+        is_reachable: impl Fn(&PrincipalID) -> bool,
+    ) -> Option<Validator> {
+        let backup_validators = self.backup_validators.get_or_insert_with(Vec::new);
+        backup_validators
+            .retain(|v| &v.principal_id != principal_id && is_reachable(&v.principal_id));
+        let position = self
+            .validators
+            .iter()
+            .position(|v| &v.principal_id == principal_id)?;
+
+        let promoted = if backup_validators.is_empty() {
+            self.validators.remove(position);
+            None
+        } else {
+            let mut backup = backup_validators.remove(0);
+            backup.clear_vote();
+            self.validators[position] = backup.clone();
+            Some(backup)
+        };
+
+        self.drop_stale_vote_data();
+        if self.voting_window_start.is_some()
+            && !self.validators.iter().any(|v| v.status == Some(true))
+        {
+            self.voting_window_start = None;
+        }
+        promoted
+    }
+
+    /// Checks whether the quorum can still be reached by the validators for which `is_reachable` holds
+    pub fn quorum_health(&self, is_reachable: impl Fn(&PrincipalID) -> bool) -> QuorumHealth {
+        let reachable_weight =
+            total_weight(self.approvers().filter(|v| is_reachable(&v.principal_id)));
+        let total = total_weight(self.approvers());
+        let quorum_reachable = self.covers_quorum(reachable_weight);
+        QuorumHealth {
+            condition_id: self.id.clone(),
+            quorum: self.quorum,
+            quorum_type: self.quorum_type,
+            reachable_weight,
+            total_weight: total,
+            backup_validators: self
+                .backup_validators()
+                .iter()
+                .filter(|v| is_reachable(&v.principal_id))
+                .count() as u64,
+            quorum_reachable,
+            quorum_unreachable_since: self.quorum_unreachable_since,
+        }
+    }
+
+    /// Flags the condition at `now` if the quorum cannot be reached anymore, and clears the flag otherwise
+    pub fn update_quorum_health(&mut self, is_reachable: impl Fn(&PrincipalID) -> bool, now: u64) {
+        if self.quorum_health(is_reachable).quorum_reachable {
+            self.quorum_unreachable_since = None;
+        } else if self.quorum_unreachable_since.is_none() {
+            self.quorum_unreachable_since = Some(now);
+        }
+    }
+
+    /// End of the running voting window, if any
+    pub fn voting_window_end(&self) -> Option<u64> {
+        let start = self.voting_window_start?;
+        let days = self.voting_window_days?;
+        Some(start.saturating_add(days.saturating_mul(time::NANOS_PER_DAY)))
+    }
+
+    /// Clears expired votes and resets all votes if the voting window has passed without reaching the quorum.
+    /// Returns true if any vote has been changed.
+    pub fn expire_votes(&mut self, now: u64) -> bool {
+        if self.condition_status {
+            return false;
+        }
+
+        if self.voting_window_end().is_some_and(|end| now > end) {
+            for validator in self.validators.iter_mut() {
+                validator.clear_vote();
+            }
+            self.voting_window_start = None;
+            self.commitments = None;
+            self.drop_stale_vote_data();
+            return true;
+        }
+
+        let mut changed = false;
+        for validator in self.validators.iter_mut() {
+            if validator.vote_expires_at.is_some_and(|expiry| now > expiry) {
+                validator.clear_vote();
+                changed = true;
+            }
+        }
+
+        if self.drop_unrevealed_commitments(now) {
+            changed = true;
+        }
+
+        if changed {
+            self.drop_stale_vote_data();
+        }
+
+        // without any yes vote left the window starts again with the next one
+        if self.voting_window_start.is_some()
+            && !self.validators.iter().any(|v| v.status == Some(true))
+        {
+            self.voting_window_start = None;
+            changed = true;
+        }
+        changed
+    }
+}
+
+impl UpdateXOutOfYCondition {
+    /// Checks the validator weights, that the quorum can be reached and the voting periods
+    pub fn validate(&self) -> Result<(), SmartVaultErr> {
+        if self.voting_window_days == Some(0)
+            || self.vote_validity_days == Some(0)
+            || self.secret_ballot_days == Some(0)
+        {
+            return Err(SmartVaultErr::InvalidVotingPeriod(
+                "Voting window, vote validity and secret ballot must be at least one day"
+                    .to_string(),
+            ));
+        }
+        if let Some(v) = self
+            .validators
+            .iter()
+            .chain(self.backup_validators.iter())
+            .find(|v| v.weight == Some(0))
+        {
+            return Err(SmartVaultErr::InvalidValidatorWeight(
+                v.principal_id.to_string(),
+            ));
+        }
+        // a backup validator can only be promoted once and never replaces itself
+        let mut principals: HashSet<&PrincipalID> = HashSet::new();
+        for v in self.validators.iter().chain(self.backup_validators.iter()) {
+            if !principals.insert(&v.principal_id) {
+                return Err(SmartVaultErr::InvalidBackupValidator(
+                    v.principal_id.to_string(),
+                ));
+            }
+        }
+        // guardians can only reject, the quorums have to be reached by the approvers
+        let total = total_weight(self.validators.iter().filter(|v| !v.is_guardian()));
+        if self.reject_quorum == Some(0) {
+            return Err(SmartVaultErr::InvalidQuorum(
+                "0".to_string(),
+                total.to_string(),
+            ));
+        }
+        for quorum in [Some(self.quorum), self.reject_quorum]
+            .into_iter()
+            .flatten()
+        {
+            match self.quorum_type.unwrap_or_default() {
+                QuorumType::Absolute => {
+                    if quorum > total {
+                        return Err(SmartVaultErr::InvalidQuorum(
+                            quorum.to_string(),
+                            total.to_string(),
+                        ));
+                    }
+                }
+                QuorumType::Percentage => {
+                    if quorum == 0 || quorum > 100 || total == 0 {
+                        return Err(SmartVaultErr::InvalidQuorum(
+                            format!("{}%", quorum),
+                            "100%".to_string(),
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
 }
 
 pub type ConditionID = String;
@@ -74,13 +1179,23 @@ pub enum Condition {
     LastLogin(LastLoginTimeCondition),
     XOutOfY(XOutOfYCondition),
     FixedDateTime(FixedDateTimeCondition),
+    CheckIn(CheckInCondition),
+    PolicyDependency(PolicyDependencyCondition),
+    BeneficiaryClaim(BeneficiaryClaimCondition),
+    Oracle(OracleCondition),
+    DeadMansSwitch(DeadMansSwitchCondition),
 }
 
-#[derive(Debug, CandidType, Deserialize, Serialize, Clone)]
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone, PartialEq)]
 pub enum UpdateCondition {
     LastLogin(UpdateLastLoginTimeCondition),
     XOutOfY(UpdateXOutOfYCondition),
     FixedDateTime(UpdateFixedDateTimeCondition),
+    CheckIn(UpdateCheckInCondition),
+    PolicyDependency(UpdatePolicyDependencyCondition),
+    BeneficiaryClaim(UpdateBeneficiaryClaimCondition),
+    Oracle(UpdateOracleCondition),
+    DeadMansSwitch(UpdateDeadMansSwitchCondition),
 }
 
 impl UpdateCondition {
@@ -89,6 +1204,37 @@ impl UpdateCondition {
             UpdateCondition::LastLogin(update) => update.id.clone(),
             UpdateCondition::XOutOfY(update) => update.id.clone(),
             UpdateCondition::FixedDateTime(update) => update.id.clone(),
+            UpdateCondition::CheckIn(update) => update.id.clone(),
+            UpdateCondition::PolicyDependency(update) => update.id.clone(),
+            UpdateCondition::BeneficiaryClaim(update) => update.id.clone(),
+            UpdateCondition::Oracle(update) => update.id.clone(),
+            UpdateCondition::DeadMansSwitch(update) => update.id.clone(),
+        }
+    }
+
+    pub fn set_id(&mut self, id: Option<ConditionID>) {
+        match self {
+            UpdateCondition::LastLogin(update) => update.id = id,
+            UpdateCondition::XOutOfY(update) => update.id = id,
+            UpdateCondition::FixedDateTime(update) => update.id = id,
+            UpdateCondition::CheckIn(update) => update.id = id,
+            UpdateCondition::PolicyDependency(update) => update.id = id,
+            UpdateCondition::BeneficiaryClaim(update) => update.id = id,
+            UpdateCondition::Oracle(update) => update.id = id,
+            UpdateCondition::DeadMansSwitch(update) => update.id = id,
+        }
+    }
+
+    /// Compares the configuration of two conditions. The inactivity of last login conditions
+    /// is compared by its duration, no matter whether it is given in days or as inactivity.
+    pub fn same_configuration(&self, other: &UpdateCondition) -> bool {
+        match (self, other) {
+            (UpdateCondition::LastLogin(a), UpdateCondition::LastLogin(b)) => {
+                a.id == b.id
+                    && a.inactivity() == b.inactivity()
+                    && a.activity_type.unwrap_or_default() == b.activity_type.unwrap_or_default()
+            }
+            _ => self == other,
         }
     }
 }
@@ -100,6 +1246,8 @@ impl From<Condition> for UpdateCondition {
                 UpdateCondition::LastLogin(UpdateLastLoginTimeCondition {
                     id: Some(cond.id.clone()),
                     number_of_days_since_last_login: cond.number_of_days_since_last_login,
+                    inactivity: cond.inactivity.clone(),
+                    activity_type: cond.activity_type,
                 })
             }
             Condition::XOutOfY(cond) => UpdateCondition::XOutOfY(UpdateXOutOfYCondition {
@@ -107,11 +1255,49 @@ impl From<Condition> for UpdateCondition {
                 validators: cond.validators,
                 question: cond.question,
                 quorum: cond.quorum,
+                quorum_type: cond.quorum_type,
+                voting_window_days: cond.voting_window_days,
+                vote_validity_days: cond.vote_validity_days,
+                backup_validators: cond.backup_validators.unwrap_or_default(),
+                secret_ballot_days: cond.secret_ballot_days,
+                reject_quorum: cond.reject_quorum,
+                clear_votes_on_activity: cond.clear_votes_on_activity,
             }),
             Condition::FixedDateTime(cond) => {
                 UpdateCondition::FixedDateTime(UpdateFixedDateTimeCondition {
                     id: Some(cond.id.clone()),
                     datetime: cond.datetime,
+                    end_datetime: cond.end_datetime,
+                    time_zone: cond.time_zone.clone(),
+                })
+            }
+            Condition::CheckIn(cond) => UpdateCondition::CheckIn(UpdateCheckInCondition {
+                id: Some(cond.id.clone()),
+                schedule: cond.schedule,
+                grace_period_days: cond.grace_period_days,
+            }),
+            Condition::PolicyDependency(cond) => {
+                UpdateCondition::PolicyDependency(UpdatePolicyDependencyCondition {
+                    id: Some(cond.id.clone()),
+                    policy_id: cond.policy_id,
+                    delay_days: cond.delay_days,
+                })
+            }
+            Condition::BeneficiaryClaim(cond) => {
+                UpdateCondition::BeneficiaryClaim(UpdateBeneficiaryClaimCondition {
+                    id: Some(cond.id.clone()),
+                    response_period_days: cond.response_period_days,
+                })
+            }
+            Condition::Oracle(cond) => UpdateCondition::Oracle(UpdateOracleCondition {
+                id: Some(cond.id.clone()),
+                canister_id: cond.canister_id,
+                method: cond.method,
+                max_days_inactive: cond.max_days_inactive,
+            }),
+            Condition::DeadMansSwitch(cond) => {
+                UpdateCondition::DeadMansSwitch(UpdateDeadMansSwitchCondition {
+                    id: Some(cond.id.clone()),
                 })
             }
         }
@@ -126,17 +1312,62 @@ trait ConditionStatus {
     fn get_condition_status(&self) -> bool;
 }
 
-impl ConditionStatus for LastLoginTimeCondition {
+impl ConditionStatus for LastLoginTimeCondition {
+    fn set_condition_status(&mut self, status: bool) {
+        self.condition_status = status;
+    }
+
+    fn get_condition_status(&self) -> bool {
+        self.condition_status
+    }
+}
+
+impl ConditionStatus for XOutOfYCondition {
+    fn set_condition_status(&mut self, status: bool) {
+        self.condition_status = status;
+    }
+    fn get_condition_status(&self) -> bool {
+        self.condition_status
+    }
+}
+
+impl ConditionStatus for FixedDateTimeCondition {
+    fn set_condition_status(&mut self, status: bool) {
+        self.condition_status = status;
+    }
+    fn get_condition_status(&self) -> bool {
+        self.condition_status
+    }
+}
+
+impl ConditionStatus for CheckInCondition {
+    fn set_condition_status(&mut self, status: bool) {
+        self.condition_status = status;
+    }
+    fn get_condition_status(&self) -> bool {
+        self.condition_status
+    }
+}
+
+impl ConditionStatus for PolicyDependencyCondition {
+    fn set_condition_status(&mut self, status: bool) {
+        self.condition_status = status;
+    }
+    fn get_condition_status(&self) -> bool {
+        self.condition_status
+    }
+}
+
+impl ConditionStatus for BeneficiaryClaimCondition {
     fn set_condition_status(&mut self, status: bool) {
         self.condition_status = status;
     }
-
     fn get_condition_status(&self) -> bool {
         self.condition_status
     }
 }
 
-impl ConditionStatus for XOutOfYCondition {
+impl ConditionStatus for OracleCondition {
     fn set_condition_status(&mut self, status: bool) {
         self.condition_status = status;
     }
@@ -145,7 +1376,7 @@ impl ConditionStatus for XOutOfYCondition {
     }
 }
 
-impl ConditionStatus for FixedDateTimeCondition {
+impl ConditionStatus for DeadMansSwitchCondition {
     fn set_condition_status(&mut self, status: bool) {
         self.condition_status = status;
     }
@@ -166,8 +1397,9 @@ impl ConditionUpdate for Condition {
         match self {
             Condition::LastLogin(condition) => {
                 if let UpdateCondition::LastLogin(update) = update {
-                    condition.number_of_days_since_last_login =
-                        update.number_of_days_since_last_login;
+                    condition.number_of_days_since_last_login = None;
+                    condition.inactivity = Some(update.inactivity());
+                    condition.activity_type = update.activity_type;
                     return Condition::LastLogin(condition.clone());
                 }
             }
@@ -176,15 +1408,80 @@ impl ConditionUpdate for Condition {
                     condition.validators = update.validators;
                     condition.question = update.question;
                     condition.quorum = update.quorum;
+                    condition.quorum_type = update.quorum_type;
+                    condition.voting_window_days = update.voting_window_days;
+                    condition.vote_validity_days = update.vote_validity_days;
+                    condition.backup_validators = Some(update.backup_validators);
+                    if update.secret_ballot_days.is_none() {
+                        condition.commitments = None;
+                        condition.commit_deadline = None;
+                    }
+                    condition.secret_ballot_days = update.secret_ballot_days;
+                    condition.reject_quorum = update.reject_quorum;
+                    condition.clear_votes_on_activity = update.clear_votes_on_activity;
+                    // the validators of an updated condition are checked to exist
+                    condition.quorum_unreachable_since = None;
+                    condition.drop_stale_vote_data();
                     return Condition::XOutOfY(condition.clone());
                 }
             }
             Condition::FixedDateTime(condition) => {
                 if let UpdateCondition::FixedDateTime(update) = update {
                     condition.datetime = update.datetime;
+                    condition.end_datetime = update.end_datetime;
+                    condition.time_zone = update.time_zone;
                     return Condition::FixedDateTime(condition.clone());
                 }
             }
+            Condition::CheckIn(condition) => {
+                if let UpdateCondition::CheckIn(update) = update {
+                    // the deadline is recalculated based on the last check-in
+                    condition.next_deadline =
+                        update.schedule.next_deadline(condition.last_check_in);
+                    condition.schedule = update.schedule;
+                    condition.grace_period_days = update.grace_period_days;
+                    return Condition::CheckIn(condition.clone());
+                }
+            }
+            Condition::PolicyDependency(condition) => {
+                if let UpdateCondition::PolicyDependency(update) = update {
+                    if condition.policy_id != update.policy_id {
+                        condition.dependency_met_at = None;
+                    }
+                    condition.policy_id = update.policy_id;
+                    condition.delay_days = update.delay_days;
+                    return Condition::PolicyDependency(condition.clone());
+                }
+            }
+            Condition::BeneficiaryClaim(condition) => {
+                if let UpdateCondition::BeneficiaryClaim(update) = update {
+                    condition.response_period_days = update.response_period_days;
+                    return Condition::BeneficiaryClaim(condition.clone());
+                }
+            }
+            Condition::Oracle(condition) => {
+                if let UpdateCondition::Oracle(update) = update {
+                    if condition.canister_id != update.canister_id
+                        || condition.method != update.method
+                    {
+                        // replies of the previous oracle are no longer relevant
+                        condition.last_reply = None;
+                        condition.last_reply_at = None;
+                        condition.last_failure = None;
+                        condition.last_call_at = None;
+                        condition.call_pending = false;
+                    }
+                    condition.canister_id = update.canister_id;
+                    condition.method = update.method;
+                    condition.max_days_inactive = update.max_days_inactive;
+                    return Condition::Oracle(condition.clone());
+                }
+            }
+            Condition::DeadMansSwitch(condition) => {
+                if let UpdateCondition::DeadMansSwitch(_) = update {
+                    return Condition::DeadMansSwitch(condition.clone());
+                }
+            }
         }
         // Return the unchanged condition if no matching update was found.
         self.clone()
@@ -193,30 +1490,80 @@ impl ConditionUpdate for Condition {
 
 impl Condition {
     pub fn evaluate(&self, user: Option<&User>) -> bool {
+        self.evaluate_at(user, time::get_current_time())
+    }
+
+    /// Evaluates the condition as if the current time was `current_time`
+    pub fn evaluate_at(&self, user: Option<&User>, current_time: u64) -> bool {
         match self {
             Condition::LastLogin(condition) => {
-                let current_time: u64 = time::get_current_time();
-
                 // Check last login date
-                //let max_last_login_time: u64 = tb.number_of_days_since_last_login * 86400 * 1000000000; // in nanoseconds
-                let max_last_login_time: u64 =
-                    condition.number_of_days_since_last_login * 1000000000; // in nanoseconds
-                user.unwrap().date_last_login.unwrap()
-                    < current_time.saturating_sub(max_last_login_time)
+                let user = user.unwrap();
+                let last_seen = condition.last_seen(user).unwrap();
+                // time on vacation does not count as inactivity, deadline extenders may postpone the deadline
+                let paused = user.paused_time(last_seen, current_time);
+                condition.inactivity_deadline(
+                    last_seen
+                        .saturating_add(paused)
+                        .saturating_add(user.deadline_extension(last_seen)),
+                ) < current_time
             }
             Condition::XOutOfY(condition) => {
-                let mut i = 0;
-                for confirmer in &condition.validators {
-                    if confirmer.status == Some(true) {
-                        i += 1;
-                    }
-                }
-                i >= condition.quorum
+                condition.tally_open(current_time)
+                    && condition.current_outcome() == VoteOutcome::Approved
             }
             Condition::FixedDateTime(condition) => {
-                let current_time: u64 = time::get_current_time();
-                current_time >= condition.datetime
+                let window_open = match condition.ends_at() {
+                    Some(ends_at) => current_time < ends_at,
+                    None => true,
+                };
+                current_time >= condition.starts_at() && window_open
+            }
+            Condition::CheckIn(condition) => {
+                let grace_period: u64 = condition
+                    .grace_period_days
+                    .saturating_mul(time::NANOS_PER_DAY);
+                // the deadline is postponed by the time the owner spent on vacation
+                let paused =
+                    user.map_or(0, |u| u.paused_time(condition.last_check_in, current_time));
+                current_time
+                    > condition
+                        .next_deadline
+                        .saturating_add(grace_period)
+                        .saturating_add(paused)
+            }
+            Condition::PolicyDependency(condition) => {
+                let delay: u64 = condition
+                    .delay_days
+                    .unwrap_or(0)
+                    .saturating_mul(time::NANOS_PER_DAY);
+                condition
+                    .dependency_met_at
+                    .is_some_and(|met_at| current_time >= met_at.saturating_add(delay))
             }
+            Condition::BeneficiaryClaim(condition) => {
+                // the owner did not sign in since the claim has been filed
+                let last_login = user.and_then(|u| u.date_last_login).unwrap_or(0);
+                condition.pending_claim.as_ref().is_some_and(|claim| {
+                    current_time > claim.response_deadline && last_login <= claim.filed_at
+                })
+            }
+            Condition::Oracle(condition) => match condition.last_reply {
+                Some(OracleReply::Status(status)) => status,
+                Some(OracleReply::LastActivity(last_activity)) => {
+                    let max_inactivity: u64 = condition
+                        .max_days_inactive
+                        .unwrap_or(u64::MAX)
+                        .saturating_mul(time::NANOS_PER_DAY);
+                    current_time.saturating_sub(last_activity) > max_inactivity
+                }
+                None => false,
+            },
+            Condition::DeadMansSwitch(_) => user.is_some_and(|u| {
+                u.dead_mans_switch
+                    .as_ref()
+                    .is_some_and(|switch| switch.met_at(u, current_time))
+            }),
         }
     }
 
@@ -225,6 +1572,11 @@ impl Condition {
             Condition::LastLogin(c) => c.set_condition_status(status),
             Condition::XOutOfY(c) => c.set_condition_status(status),
             Condition::FixedDateTime(c) => c.set_condition_status(status),
+            Condition::CheckIn(c) => c.set_condition_status(status),
+            Condition::PolicyDependency(c) => c.set_condition_status(status),
+            Condition::BeneficiaryClaim(c) => c.set_condition_status(status),
+            Condition::Oracle(c) => c.set_condition_status(status),
+            Condition::DeadMansSwitch(c) => c.set_condition_status(status),
         }
     }
 
@@ -233,6 +1585,11 @@ impl Condition {
             Condition::LastLogin(c) => c.get_condition_status(),
             Condition::XOutOfY(c) => c.get_condition_status(),
             Condition::FixedDateTime(c) => c.get_condition_status(),
+            Condition::CheckIn(c) => c.get_condition_status(),
+            Condition::PolicyDependency(c) => c.get_condition_status(),
+            Condition::BeneficiaryClaim(c) => c.get_condition_status(),
+            Condition::Oracle(c) => c.get_condition_status(),
+            Condition::DeadMansSwitch(c) => c.get_condition_status(),
         }
     }
 
@@ -241,6 +1598,11 @@ impl Condition {
             Condition::LastLogin(cond) => cond.id.clone(),
             Condition::XOutOfY(cond) => cond.id.clone(),
             Condition::FixedDateTime(cond) => cond.id.clone(),
+            Condition::CheckIn(cond) => cond.id.clone(),
+            Condition::PolicyDependency(cond) => cond.id.clone(),
+            Condition::BeneficiaryClaim(cond) => cond.id.clone(),
+            Condition::Oracle(cond) => cond.id.clone(),
+            Condition::DeadMansSwitch(cond) => cond.id.clone(),
         }
     }
 
@@ -250,7 +1612,9 @@ impl Condition {
         match update {
             UpdateCondition::LastLogin(update) => Condition::LastLogin(LastLoginTimeCondition {
                 id: new_condition_id,
-                number_of_days_since_last_login: update.number_of_days_since_last_login,
+                number_of_days_since_last_login: None,
+                inactivity: Some(update.inactivity()),
+                activity_type: update.activity_type,
                 condition_status: false,
             }),
             UpdateCondition::XOutOfY(update) => Condition::XOutOfY(XOutOfYCondition {
@@ -258,16 +1622,136 @@ impl Condition {
                 validators: update.validators,
                 question: update.question,
                 quorum: update.quorum,
+                quorum_type: update.quorum_type,
+                voting_window_days: update.voting_window_days,
+                voting_window_start: None,
+                vote_validity_days: update.vote_validity_days,
+                vote_evidence: None,
+                backup_validators: Some(update.backup_validators),
+                quorum_unreachable_since: None,
+                secret_ballot_days: update.secret_ballot_days,
+                commitments: None,
+                commit_deadline: None,
+                reject_quorum: update.reject_quorum,
+                outcome: None,
+                clear_votes_on_activity: update.clear_votes_on_activity,
+                vote_clearances: None,
                 condition_status: false,
             }),
             UpdateCondition::FixedDateTime(update) => {
                 Condition::FixedDateTime(FixedDateTimeCondition {
                     id: new_condition_id,
                     datetime: update.datetime,
+                    end_datetime: update.end_datetime,
+                    time_zone: update.time_zone,
+                    condition_status: false,
+                })
+            }
+            UpdateCondition::CheckIn(update) => {
+                let now: u64 = time::get_current_time();
+                Condition::CheckIn(CheckInCondition {
+                    id: new_condition_id,
+                    next_deadline: update.schedule.next_deadline(now),
+                    schedule: update.schedule,
+                    grace_period_days: update.grace_period_days,
+                    last_check_in: now,
                     condition_status: false,
                 })
             }
+            UpdateCondition::PolicyDependency(update) => {
+                Condition::PolicyDependency(PolicyDependencyCondition {
+                    id: new_condition_id,
+                    policy_id: update.policy_id,
+                    delay_days: update.delay_days,
+                    dependency_met_at: None,
+                    condition_status: false,
+                })
+            }
+            UpdateCondition::BeneficiaryClaim(update) => {
+                Condition::BeneficiaryClaim(BeneficiaryClaimCondition {
+                    id: new_condition_id,
+                    response_period_days: update.response_period_days,
+                    pending_claim: None,
+                    last_claims: BTreeMap::new(),
+                    condition_status: false,
+                })
+            }
+            UpdateCondition::Oracle(update) => Condition::Oracle(OracleCondition {
+                id: new_condition_id,
+                canister_id: update.canister_id,
+                method: update.method,
+                max_days_inactive: update.max_days_inactive,
+                last_reply: None,
+                last_reply_at: None,
+                last_failure: None,
+                last_call_at: None,
+                call_pending: false,
+                condition_status: false,
+            }),
+            UpdateCondition::DeadMansSwitch(_) => {
+                Condition::DeadMansSwitch(DeadMansSwitchCondition {
+                    id: new_condition_id,
+                    condition_status: false,
+                })
+            }
+        }
+    }
+
+    /// Re-arms the condition: the status is set back to false, validator votes are cleared
+    /// and check-in deadlines restart at `now`.
+    pub fn reset(&mut self, now: u64) {
+        self.set_condition_status(false);
+        match self {
+            Condition::XOutOfY(condition) => {
+                for validator in condition.validators.iter_mut() {
+                    validator.clear_vote();
+                }
+                condition.voting_window_start = None;
+                condition.vote_evidence = None;
+                condition.commitments = None;
+                condition.commit_deadline = None;
+                condition.outcome = None;
+                condition.vote_clearances = None;
+            }
+            Condition::CheckIn(_) => {
+                self.check_in(now);
+            }
+            Condition::PolicyDependency(condition) => {
+                condition.dependency_met_at = None;
+            }
+            Condition::BeneficiaryClaim(condition) => {
+                condition.pending_claim = None;
+            }
+            Condition::Oracle(condition) => {
+                condition.last_reply = None;
+                condition.last_reply_at = None;
+            }
+            Condition::DeadMansSwitch(_) => {
+                // the switch of the owner is re-armed separately, see rearm_dead_mans_switch
+            }
+            _ => {}
+        }
+    }
+
+    /// Outcome of the votes of an XOutOfY condition, none for all other conditions
+    pub fn outcome(&self) -> Option<VoteOutcome> {
+        match self {
+            Condition::XOutOfY(condition) => Some(condition.outcome.unwrap_or_default()),
+            _ => None,
+        }
+    }
+
+    /// Registers a check-in of the owner and moves the deadline of a check-in condition.
+    /// Returns false if the condition is no check-in condition or has already been triggered.
+    pub fn check_in(&mut self, check_in_time: u64) -> bool {
+        if let Condition::CheckIn(condition) = self {
+            if !condition.condition_status {
+                condition.last_check_in = check_in_time;
+                condition.next_deadline = condition.schedule.next_deadline(check_in_time);
+                return true;
+            }
         }
+        false
     }
 
     // create updatecondition from condition
@@ -277,6 +1761,8 @@ impl Condition {
                 UpdateCondition::LastLogin(UpdateLastLoginTimeCondition {
                     id: Some(cond.id.clone()),
                     number_of_days_since_last_login: cond.number_of_days_since_last_login,
+                    inactivity: cond.inactivity.clone(),
+                    activity_type: cond.activity_type,
                 })
             }
             Condition::XOutOfY(cond) => UpdateCondition::XOutOfY(UpdateXOutOfYCondition {
@@ -284,20 +1770,1055 @@ impl Condition {
                 validators: cond.validators.clone(),
                 question: cond.question.clone(),
                 quorum: cond.quorum,
+                quorum_type: cond.quorum_type,
+                voting_window_days: cond.voting_window_days,
+                vote_validity_days: cond.vote_validity_days,
+                backup_validators: cond.backup_validators().to_vec(),
+                secret_ballot_days: cond.secret_ballot_days,
+                reject_quorum: cond.reject_quorum,
+                clear_votes_on_activity: cond.clear_votes_on_activity,
             }),
             Condition::FixedDateTime(cond) => {
                 UpdateCondition::FixedDateTime(UpdateFixedDateTimeCondition {
                     id: Some(cond.id.clone()),
                     datetime: cond.datetime,
+                    end_datetime: cond.end_datetime,
+                    time_zone: cond.time_zone.clone(),
+                })
+            }
+            Condition::CheckIn(cond) => UpdateCondition::CheckIn(UpdateCheckInCondition {
+                id: Some(cond.id.clone()),
+                schedule: cond.schedule.clone(),
+                grace_period_days: cond.grace_period_days,
+            }),
+            Condition::PolicyDependency(cond) => {
+                UpdateCondition::PolicyDependency(UpdatePolicyDependencyCondition {
+                    id: Some(cond.id.clone()),
+                    policy_id: cond.policy_id.clone(),
+                    delay_days: cond.delay_days,
+                })
+            }
+            Condition::BeneficiaryClaim(cond) => {
+                UpdateCondition::BeneficiaryClaim(UpdateBeneficiaryClaimCondition {
+                    id: Some(cond.id.clone()),
+                    response_period_days: cond.response_period_days,
+                })
+            }
+            Condition::Oracle(cond) => UpdateCondition::Oracle(UpdateOracleCondition {
+                id: Some(cond.id.clone()),
+                canister_id: cond.canister_id.clone(),
+                method: cond.method.clone(),
+                max_days_inactive: cond.max_days_inactive,
+            }),
+            Condition::DeadMansSwitch(cond) => {
+                UpdateCondition::DeadMansSwitch(UpdateDeadMansSwitchCondition {
+                    id: Some(cond.id.clone()),
                 })
             }
         }
     }
 }
 
+/// Maximum nesting depth of a condition expression
+pub const MAX_CONDITION_EXPRESSION_DEPTH: usize = 10;
+
+/// Boolean expression combining the conditions of a policy, e.g. (LastLogin AND XOutOfY) OR FixedDateTime.
+/// Leaves reference a condition by its id, so the expression is not affected by the order of the conditions.
+/// A condition which does not exist (anymore) is never met.
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone, PartialEq)]
+pub enum ConditionExpression {
+    Condition(ConditionID),
+    And(Vec<ConditionExpression>),
+    Or(Vec<ConditionExpression>),
+    Not(Box<ConditionExpression>),
+}
+
+impl ConditionExpression {
+    pub fn evaluate(&self, conditions: &[Condition]) -> bool {
+        match self {
+            ConditionExpression::Condition(id) => conditions
+                .iter()
+                .any(|c| c.id() == *id && c.get_condition_status()),
+            ConditionExpression::And(operands) => operands.iter().all(|e| e.evaluate(conditions)),
+            ConditionExpression::Or(operands) => operands.iter().any(|e| e.evaluate(conditions)),
+            ConditionExpression::Not(operand) => !operand.evaluate(conditions),
+        }
+    }
+
+    /// Expression referencing the conditions by their position, see `UpdateConditionExpression`.
+    /// Conditions which do not exist are referenced by a position behind the last condition.
+    pub fn to_update_expression(&self, conditions: &[Condition]) -> UpdateConditionExpression {
+        let map = |operands: &Vec<ConditionExpression>| {
+            operands
+                .iter()
+                .map(|e| e.to_update_expression(conditions))
+                .collect()
+        };
+        match self {
+            ConditionExpression::Condition(id) => UpdateConditionExpression::Condition(
+                conditions
+                    .iter()
+                    .position(|c| c.id() == *id)
+                    .unwrap_or(conditions.len()) as u64,
+            ),
+            ConditionExpression::And(operands) => UpdateConditionExpression::And(map(operands)),
+            ConditionExpression::Or(operands) => UpdateConditionExpression::Or(map(operands)),
+            ConditionExpression::Not(operand) => {
+                UpdateConditionExpression::Not(Box::new(operand.to_update_expression(conditions)))
+            }
+        }
+    }
+}
+
+/// Condition expression for updating a policy or template. Leaves reference a condition by its position
+/// in the provided conditions, since new conditions do not have an id yet. The positions are bound to
+/// the ids of the conditions when the policy is updated.
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone, PartialEq)]
+pub enum UpdateConditionExpression {
+    Condition(u64),
+    And(Vec<UpdateConditionExpression>),
+    Or(Vec<UpdateConditionExpression>),
+    Not(Box<UpdateConditionExpression>),
+}
+
+impl UpdateConditionExpression {
+    fn evaluate_with(&self, status: &impl Fn(u64) -> bool) -> bool {
+        match self {
+            UpdateConditionExpression::Condition(index) => status(*index),
+            UpdateConditionExpression::And(operands) => {
+                operands.iter().all(|e| e.evaluate_with(status))
+            }
+            UpdateConditionExpression::Or(operands) => {
+                operands.iter().any(|e| e.evaluate_with(status))
+            }
+            UpdateConditionExpression::Not(operand) => !operand.evaluate_with(status),
+        }
+    }
+
+    /// Binds the positions to the ids of the conditions, validate the expression beforehand
+    pub fn bind(&self, conditions: &[Condition]) -> ConditionExpression {
+        let map = |operands: &Vec<UpdateConditionExpression>| {
+            operands.iter().map(|e| e.bind(conditions)).collect()
+        };
+        match self {
+            UpdateConditionExpression::Condition(index) => ConditionExpression::Condition(
+                conditions
+                    .get(*index as usize)
+                    .map(|c| c.id().clone())
+                    .unwrap_or_default(),
+            ),
+            UpdateConditionExpression::And(operands) => ConditionExpression::And(map(operands)),
+            UpdateConditionExpression::Or(operands) => ConditionExpression::Or(map(operands)),
+            UpdateConditionExpression::Not(operand) => {
+                ConditionExpression::Not(Box::new(operand.bind(conditions)))
+            }
+        }
+    }
+
+    /// Checks that the expression references existing conditions only, that every condition
+    /// is part of the expression and that each group has at least two operands.
+    /// An expression which is met while none of the conditions is met, e.g. NOT of a condition
+    /// at the top level, would release the policy right away and is rejected.
+    pub fn validate(&self, number_of_conditions: usize) -> Result<(), SmartVaultErr> {
+        let mut referenced = vec![false; number_of_conditions];
+        self.validate_node(&mut referenced, 1)?;
+
+        if let Some(index) = referenced.iter().position(|r| !r) {
+            return Err(SmartVaultErr::InvalidConditionExpression(format!(
+                "Condition {} is not part of the expression",
+                index
+            )));
+        }
+        if self.evaluate_with(&|_| false) {
+            return Err(SmartVaultErr::InvalidConditionExpression(
+                "Expression is met without any condition being met".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn validate_node(&self, referenced: &mut [bool], depth: usize) -> Result<(), SmartVaultErr> {
+        if depth > MAX_CONDITION_EXPRESSION_DEPTH {
+            return Err(SmartVaultErr::InvalidConditionExpression(format!(
+                "Expression is nested deeper than {} levels",
+                MAX_CONDITION_EXPRESSION_DEPTH
+            )));
+        }
+
+        match self {
+            UpdateConditionExpression::Condition(index) => {
+                match referenced.get_mut(*index as usize) {
+                    Some(r) => *r = true,
+                    None => {
+                        return Err(SmartVaultErr::InvalidConditionExpression(format!(
+                            "Condition {} does not exist",
+                            index
+                        )))
+                    }
+                }
+            }
+            UpdateConditionExpression::And(operands) | UpdateConditionExpression::Or(operands) => {
+                if operands.len() < 2 {
+                    return Err(SmartVaultErr::InvalidConditionExpression(
+                        "And/Or groups need at least two operands".to_string(),
+                    ));
+                }
+                for operand in operands {
+                    operand.validate_node(referenced, depth + 1)?;
+                }
+            }
+            UpdateConditionExpression::Not(operand) => {
+                operand.validate_node(referenced, depth + 1)?
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone)]
+pub struct FileClaimArgs {
+    pub policy_id: PolicyID,
+    pub condition_id: ConditionID,
+}
+
 #[derive(Debug, CandidType, Deserialize, Serialize, Clone)]
 pub struct ConfirmXOutOfYConditionArgs {
     pub policy_id: PolicyID,
     pub condition_id: ConditionID,
     pub status: bool,
+    pub reason: Option<String>,
+    pub evidence: Option<Vec<Evidence>>,
+    pub evidence_visibility: Option<EvidenceVisibility>,
+}
+
+impl ConfirmXOutOfYConditionArgs {
+    /// Validates the reason and evidence attached to the vote
+    pub fn vote_evidence(&self, now: u64) -> Result<Option<VoteEvidence>, SmartVaultErr> {
+        validate_vote_evidence(&self.reason, &self.evidence, self.evidence_visibility, now)
+    }
+}
+
+/// Commitment to a vote in a secret ballot, see `vote_commitment`
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone)]
+pub struct CommitXOutOfYVoteArgs {
+    pub policy_id: PolicyID,
+    pub condition_id: ConditionID,
+    pub commitment: String,
+}
+
+/// Reveals a committed vote of a secret ballot, optionally with a reason and evidence
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone)]
+pub struct RevealXOutOfYVoteArgs {
+    pub policy_id: PolicyID,
+    pub condition_id: ConditionID,
+    pub status: bool,
+    pub salt: String,
+    pub reason: Option<String>,
+    pub evidence: Option<Vec<Evidence>>,
+    pub evidence_visibility: Option<EvidenceVisibility>,
+}
+
+impl RevealXOutOfYVoteArgs {
+    /// Validates the reason and evidence attached to the vote
+    pub fn vote_evidence(&self, now: u64) -> Result<Option<VoteEvidence>, SmartVaultErr> {
+        validate_vote_evidence(&self.reason, &self.evidence, self.evidence_visibility, now)
+    }
+}
+
+fn validate_vote_evidence(
+    reason: &Option<String>,
+    evidence: &Option<Vec<Evidence>>,
+    visibility: Option<EvidenceVisibility>,
+    now: u64,
+) -> Result<Option<VoteEvidence>, SmartVaultErr> {
+    if reason.is_none() && evidence.is_none() {
+        return Ok(None);
+    }
+    if reason
+        .as_ref()
+        .is_some_and(|r| r.chars().count() > MAX_VOTE_REASON_LENGTH)
+    {
+        return Err(SmartVaultErr::InvalidVoteEvidence(format!(
+            "reason exceeds {} characters",
+            MAX_VOTE_REASON_LENGTH
+        )));
+    }
+    let evidence = evidence.clone().unwrap_or_default();
+    let evidence_size: usize = evidence.iter().map(|e| e.encrypted_content.len()).sum();
+    if evidence_size > MAX_VOTE_EVIDENCE_SIZE {
+        return Err(SmartVaultErr::InvalidVoteEvidence(format!(
+            "evidence exceeds {} bytes",
+            MAX_VOTE_EVIDENCE_SIZE
+        )));
+    }
+    Ok(Some(VoteEvidence {
+        reason: reason.clone(),
+        evidence,
+        visibility: visibility.unwrap_or_default(),
+        submitted_at: now,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use crate::common::error::SmartVaultErr;
+    use crate::policies::oracles::{OracleReply, ORACLE_CALL_TIMEOUT, ORACLE_POLL_INTERVAL};
+    use crate::users::user::{AddOrUpdateUserArgs, PrincipalID, User, MAX_VACATION_DAYS};
+    use crate::utils::time::{self, days_from_civil, NANOS_PER_DAY};
+
+    use super::{
+        vote_commitment, ActivityType, BeneficiaryClaimCondition, CheckInCondition,
+        CheckInSchedule, Condition, Duration, DurationUnit, FixedDateTimeCondition,
+        LastLoginTimeCondition, OracleCondition, QuorumType, TimeZone, UpdateCondition,
+        UpdateConditionExpression, UpdateLastLoginTimeCondition, UpdateXOutOfYCondition, Validator,
+        ValidatorRole, VoteOutcome, XOutOfYCondition,
+    };
+
+    fn validator(id: &str) -> Validator {
+        Validator {
+            principal_id: id.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Condition with the given validators and quorum, everything else is left at its default
+    fn x_out_of_y(validators: Vec<Validator>, quorum: u64) -> XOutOfYCondition {
+        XOutOfYCondition {
+            id: "1".to_string(),
+            validators,
+            question: "Is the owner gone?".to_string(),
+            quorum,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn utest_check_in_schedule() {
+        // 2024-01-31, 10:00 UTC
+        let check_in_time =
+            days_from_civil(2024, 1, 31) as u64 * NANOS_PER_DAY + 36_000 * 1_000_000_000;
+
+        let every_30_days = CheckInSchedule::EveryNumberOfDays(30);
+        assert!(every_30_days.is_valid());
+        assert_eq!(
+            every_30_days.next_deadline(check_in_time),
+            check_in_time + 30 * NANOS_PER_DAY
+        );
+
+        // due on the 1st of each month: until the end of 2024-02-01
+        let first_of_month = CheckInSchedule::DayOfMonth(1);
+        assert_eq!(
+            first_of_month.next_deadline(check_in_time),
+            days_from_civil(2024, 2, 2) as u64 * NANOS_PER_DAY
+        );
+
+        // due on the 31st: clamped to the end of February (leap year)
+        let end_of_month = CheckInSchedule::DayOfMonth(31);
+        assert_eq!(
+            end_of_month.next_deadline(check_in_time),
+            days_from_civil(2024, 3, 1) as u64 * NANOS_PER_DAY
+        );
+
+        assert!(!CheckInSchedule::EveryNumberOfDays(0).is_valid());
+        assert!(!CheckInSchedule::DayOfMonth(32).is_valid());
+    }
+
+    #[test]
+    fn utest_condition_expression() {
+        let mut conditions = vec![
+            Condition::LastLogin(LastLoginTimeCondition {
+                id: "1".to_string(),
+                number_of_days_since_last_login: None,
+                inactivity: Some(Duration::days(100)),
+                activity_type: None,
+                condition_status: false,
+            }),
+            Condition::LastLogin(LastLoginTimeCondition {
+                id: "2".to_string(),
+                number_of_days_since_last_login: None,
+                inactivity: Some(Duration::days(100)),
+                activity_type: None,
+                condition_status: false,
+            }),
+            Condition::FixedDateTime(FixedDateTimeCondition {
+                id: "3".to_string(),
+                datetime: 0,
+                end_datetime: None,
+                time_zone: None,
+                condition_status: false,
+            }),
+        ];
+
+        // (0 AND 1) OR (2 AND NOT 0)
+        let expression = UpdateConditionExpression::Or(vec![
+            UpdateConditionExpression::And(vec![
+                UpdateConditionExpression::Condition(0),
+                UpdateConditionExpression::Condition(1),
+            ]),
+            UpdateConditionExpression::And(vec![
+                UpdateConditionExpression::Condition(2),
+                UpdateConditionExpression::Not(Box::new(UpdateConditionExpression::Condition(0))),
+            ]),
+        ]);
+        assert!(expression.validate(3).is_ok());
+        let bound = expression.bind(&conditions);
+        assert!(!bound.evaluate(&conditions));
+
+        conditions[2].set_condition_status(true);
+        assert!(bound.evaluate(&conditions));
+
+        // the conditions are referenced by id, reordering them does not change the result
+        conditions.swap(0, 2);
+        assert!(bound.evaluate(&conditions));
+        assert_eq!(
+            bound.to_update_expression(&conditions).bind(&conditions),
+            bound
+        );
+        conditions.swap(0, 2);
+        assert_eq!(bound.to_update_expression(&conditions), expression);
+
+        conditions[0].set_condition_status(true);
+        assert!(!bound.evaluate(&conditions));
+        conditions[1].set_condition_status(true);
+        assert!(bound.evaluate(&conditions));
+
+        // a removed condition is not met
+        let removed = conditions.remove(1);
+        assert!(!bound.evaluate(&conditions));
+        assert!(bound.to_update_expression(&conditions).validate(2).is_err());
+        conditions.insert(1, removed);
+
+        // condition 3 does not exist, condition 3 of 4 is not referenced
+        assert!(expression.validate(2).is_err());
+        assert!(expression.validate(4).is_err());
+
+        // an expression which is met without any met condition would release the policy right away
+        let not = UpdateConditionExpression::Not(Box::new(UpdateConditionExpression::Condition(0)));
+        assert!(not.validate(1).is_err());
+        let or_not = UpdateConditionExpression::Or(vec![
+            UpdateConditionExpression::Condition(0),
+            UpdateConditionExpression::Not(Box::new(UpdateConditionExpression::Condition(1))),
+        ]);
+        assert!(or_not.validate(2).is_err());
+
+        // groups need two operands
+        let single = UpdateConditionExpression::And(vec![UpdateConditionExpression::Condition(0)]);
+        assert!(single.validate(1).is_err());
+
+        // too deeply nested
+        let mut deep = UpdateConditionExpression::Condition(0);
+        for _ in 0..super::MAX_CONDITION_EXPRESSION_DEPTH {
+            deep = UpdateConditionExpression::Not(Box::new(deep));
+        }
+        assert!(deep.validate(1).is_err());
+    }
+
+    #[test]
+    fn utest_weighted_quorum() {
+        let weighted = |id: &str, weight: Option<u64>| Validator {
+            weight,
+            ..validator(id)
+        };
+        // spouse counts double
+        let mut condition = x_out_of_y(
+            vec![
+                weighted("spouse", Some(2)),
+                validator("sibling"),
+                validator("friend"),
+            ],
+            2,
+        );
+
+        condition.validators[1].status = Some(true);
+        assert!(!condition.quorum_reached());
+        condition.validators[0].status = Some(true);
+        assert!(condition.quorum_reached());
+
+        // 75% of the total weight of 4
+        condition.quorum_type = Some(QuorumType::Percentage);
+        condition.quorum = 75;
+        assert!(condition.quorum_reached());
+        condition.validators[1].status = Some(false);
+        assert!(!condition.quorum_reached());
+        condition.validators.push(validator("neighbour"));
+        condition.validators[2].status = Some(true);
+        assert!(!condition.quorum_reached());
+        condition.validators[3].status = Some(true);
+        assert!(condition.quorum_reached());
+
+        // nothing to vote on without approvers, neither for approving nor for rejecting
+        condition.validators.clear();
+        condition.reject_quorum = Some(50);
+        assert!(!condition.quorum_reached());
+        assert!(!condition.rejected());
+
+        let update = |quorum: u64, quorum_type: Option<QuorumType>, weight: Option<u64>| {
+            UpdateXOutOfYCondition {
+                validators: vec![weighted("spouse", weight), validator("sibling")],
+                quorum,
+                quorum_type,
+                ..Default::default()
+            }
+        };
+        assert!(update(3, None, Some(2)).validate().is_ok());
+        assert!(update(4, None, Some(2)).validate().is_err());
+        assert!(update(2, None, Some(0)).validate().is_err());
+        assert!(update(100, Some(QuorumType::Percentage), None)
+            .validate()
+            .is_ok());
+        assert!(update(101, Some(QuorumType::Percentage), None)
+            .validate()
+            .is_err());
+        assert!(update(0, Some(QuorumType::Percentage), None)
+            .validate()
+            .is_err());
+    }
+
+    #[test]
+    fn utest_voting_window_and_vote_expiry() {
+        let mut condition = XOutOfYCondition {
+            voting_window_days: Some(7),
+            vote_validity_days: Some(3),
+            ..x_out_of_y(vec![validator("a"), validator("b"), validator("c")], 3)
+        };
+
+        // a no vote does not open the window
+        assert!(condition.register_vote(&"a".to_string(), false, 0));
+        assert_eq!(condition.voting_window_start, None);
+        assert!(!condition.register_vote(&"unknown".to_string(), true, 0));
+
+        // the first yes vote opens the window
+        assert!(condition.register_vote(&"a".to_string(), true, NANOS_PER_DAY));
+        assert_eq!(condition.voting_window_start, Some(NANOS_PER_DAY));
+        assert_eq!(condition.voting_window_end(), Some(8 * NANOS_PER_DAY));
+        assert_eq!(
+            condition.validators[0].vote_expires_at,
+            Some(4 * NANOS_PER_DAY)
+        );
+
+        // vote of a expires after 3 days, the window closes with it
+        condition.register_vote(&"b".to_string(), true, 3 * NANOS_PER_DAY);
+        assert!(!condition.expire_votes(4 * NANOS_PER_DAY));
+        assert!(condition.expire_votes(5 * NANOS_PER_DAY));
+        assert_eq!(condition.validators[0].status, None);
+        assert_eq!(condition.validators[1].status, Some(true));
+        assert_eq!(condition.voting_window_start, Some(NANOS_PER_DAY));
+
+        // the window has passed without quorum: all votes are reset
+        assert!(condition.expire_votes(9 * NANOS_PER_DAY));
+        assert!(condition.validators.iter().all(|v| v.status.is_none()));
+        assert_eq!(condition.voting_window_start, None);
+        assert!(!condition.expire_votes(10 * NANOS_PER_DAY));
+    }
+
+    #[test]
+    fn utest_substitute_validator() {
+        let backup = Validator {
+            weight: Some(2),
+            ..validator("c")
+        };
+        let mut condition = XOutOfYCondition {
+            backup_validators: Some(vec![validator("gone"), backup]),
+            ..x_out_of_y(vec![validator("a"), validator("b")], 2)
+        };
+        condition.register_vote(&"a".to_string(), true, 0);
+        let deleted = ["a".to_string(), "gone".to_string()];
+        let is_reachable = |id: &PrincipalID| !deleted.contains(id);
+        assert!(!condition.quorum_health(is_reachable).quorum_reachable);
+
+        // deleted backups are skipped, the promoted backup takes the place without a vote
+        let promoted = condition.substitute_validator(&"a".to_string(), is_reachable);
+        assert_eq!(promoted.map(|v| v.principal_id), Some("c".to_string()));
+        assert_eq!(condition.validators[0].principal_id, "c");
+        assert_eq!(condition.validators[0].status, None);
+        assert!(condition.backup_validators().is_empty());
+        // conditions without a secret ballot have no commitments
+        assert!(condition.commitments.is_none());
+        let health = condition.quorum_health(is_reachable);
+        assert!(health.quorum_reachable);
+        assert_eq!(health.reachable_weight, 3);
+
+        // without backups the validator is removed and the quorum gets out of reach
+        assert!(condition
+            .substitute_validator(&"c".to_string(), |_| true)
+            .is_none());
+        assert_eq!(condition.validators.len(), 1);
+        condition.update_quorum_health(|_| true, 5);
+        assert_eq!(condition.quorum_unreachable_since, Some(5));
+        condition.update_quorum_health(|_| true, 6);
+        assert_eq!(condition.quorum_unreachable_since, Some(5));
+    }
+
+    #[test]
+    fn utest_guardian_and_reject_quorum() {
+        let with_role = |id: &str, role: ValidatorRole| Validator {
+            role: Some(role),
+            ..validator(id)
+        };
+        let validators = vec![
+            validator("a"),
+            with_role("b", ValidatorRole::Approver),
+            validator("c"),
+            with_role("lawyer", ValidatorRole::Guardian),
+        ];
+        let mut condition = XOutOfYCondition {
+            reject_quorum: Some(2),
+            ..x_out_of_y(validators, 2)
+        };
+
+        // the yes vote of a guardian does not count towards the quorum
+        condition.register_vote(&"lawyer".to_string(), true, 1);
+        condition.register_vote(&"a".to_string(), true, 1);
+        assert_eq!(condition.outcome, Some(VoteOutcome::Pending));
+        condition.register_vote(&"b".to_string(), true, 1);
+        assert_eq!(condition.outcome, Some(VoteOutcome::Approved));
+        assert!(Condition::XOutOfY(condition.clone()).evaluate_at(None, 1));
+
+        // a single no vote of the guardian rejects the condition even though the quorum is reached
+        condition.register_vote(&"lawyer".to_string(), false, 2);
+        assert!(condition.quorum_reached());
+        assert_eq!(condition.outcome, Some(VoteOutcome::Rejected));
+        let rejected = Condition::XOutOfY(condition.clone());
+        assert!(!rejected.evaluate_at(None, 2));
+        assert_eq!(rejected.outcome(), Some(VoteOutcome::Rejected));
+
+        // a reset clears the rejection
+        let mut reset = rejected.clone();
+        reset.reset(3);
+        assert_eq!(reset.outcome(), Some(VoteOutcome::Pending));
+
+        // the no votes of the approvers reach the reject quorum
+        condition.register_vote(&"lawyer".to_string(), true, 3);
+        assert_eq!(condition.outcome, Some(VoteOutcome::Approved));
+        condition.register_vote(&"c".to_string(), false, 3);
+        condition.register_vote(&"b".to_string(), false, 3);
+        assert_eq!(condition.outcome, Some(VoteOutcome::Rejected));
+
+        // the quorums have to be reachable by the approvers alone
+        let update = |quorum: u64, reject_quorum: Option<u64>, quorum_type: Option<QuorumType>| {
+            UpdateXOutOfYCondition {
+                validators: condition.validators.clone(),
+                quorum,
+                quorum_type,
+                reject_quorum,
+                ..Default::default()
+            }
+        };
+        assert!(update(3, Some(1), None).validate().is_ok());
+        assert!(update(4, None, None).validate().is_err());
+        assert!(update(2, Some(4), None).validate().is_err());
+        assert!(update(2, Some(0), None).validate().is_err());
+        assert!(update(50, Some(100), Some(QuorumType::Percentage))
+            .validate()
+            .is_ok());
+        assert!(update(50, Some(101), Some(QuorumType::Percentage))
+            .validate()
+            .is_err());
+    }
+
+    #[test]
+    fn utest_secret_ballot() {
+        let mut condition = XOutOfYCondition {
+            secret_ballot_days: Some(3),
+            ..x_out_of_y(vec![validator("a"), validator("b"), validator("c")], 2)
+        };
+        let a = "a".to_string();
+        let b = "b".to_string();
+
+        // nothing can be revealed before the commitments cover the quorum
+        condition
+            .commit_vote(&a, vote_commitment(&a, true, "salt-a"), 0)
+            .unwrap();
+        assert_eq!(condition.commit_deadline, Some(3 * NANOS_PER_DAY));
+        assert!(condition.reveal_vote(&a, true, "salt-a", 1).is_err());
+        assert!(condition
+            .commit_vote(&"x".to_string(), "x".to_string(), 1)
+            .is_err());
+
+        condition
+            .commit_vote(&b, vote_commitment(&b, true, "salt-b"), 1)
+            .unwrap();
+        assert!(condition.reveal_open(1));
+        assert!(condition
+            .commit_vote(&"c".to_string(), "c".to_string(), 2)
+            .is_err());
+
+        // a revealed vote has to match the commitment
+        assert!(condition.reveal_vote(&a, false, "salt-a", 2).is_err());
+        condition.reveal_vote(&a, true, "salt-a", 2).unwrap();
+        assert!(condition.reveal_vote(&a, true, "salt-a", 2).is_err());
+        assert!(!Condition::XOutOfY(condition.clone()).evaluate_at(None, 2));
+        condition.reveal_vote(&b, true, "salt-b", 3).unwrap();
+        assert!(Condition::XOutOfY(condition.clone()).evaluate_at(None, 3));
+
+        // without enough commitments the reveal phase starts at the deadline
+        let mut condition = XOutOfYCondition {
+            quorum: 3,
+            commitments: None,
+            commit_deadline: None,
+            ..condition
+        };
+        for validator in condition.validators.iter_mut() {
+            validator.status = None;
+        }
+        condition
+            .commit_vote(&a, vote_commitment(&a, false, "salt"), 0)
+            .unwrap();
+        assert!(!condition.reveal_open(3 * NANOS_PER_DAY));
+        assert!(condition.reveal_open(3 * NANOS_PER_DAY + 1));
+        condition
+            .reveal_vote(&a, false, "salt", 3 * NANOS_PER_DAY + 1)
+            .unwrap();
+
+        // once everything is revealed the next commitment starts a new ballot
+        condition
+            .commit_vote(&b, vote_commitment(&b, true, "salt"), 4 * NANOS_PER_DAY)
+            .unwrap();
+        assert_eq!(condition.commitments.as_ref().unwrap().len(), 1);
+        assert_eq!(condition.commit_deadline, Some(7 * NANOS_PER_DAY));
+
+        // an unrevealed commitment blocks a new ballot until the reveal deadline
+        assert_eq!(condition.reveal_deadline(), Some(10 * NANOS_PER_DAY));
+        let c = "c".to_string();
+        assert!(condition
+            .commit_vote(&c, vote_commitment(&c, true, "salt"), 8 * NANOS_PER_DAY)
+            .is_err());
+        assert!(!condition.expire_votes(10 * NANOS_PER_DAY));
+        assert!(condition.expire_votes(10 * NANOS_PER_DAY + 1));
+        assert!(condition.commitments.as_ref().unwrap().is_empty());
+        assert_eq!(condition.commit_deadline, None);
+        condition
+            .commit_vote(
+                &c,
+                vote_commitment(&c, true, "salt"),
+                10 * NANOS_PER_DAY + 2,
+            )
+            .unwrap();
+        assert_eq!(condition.commit_deadline, Some(13 * NANOS_PER_DAY + 2));
+        assert!(condition
+            .reveal_vote(&b, true, "salt", 13 * NANOS_PER_DAY + 3)
+            .is_err());
+    }
+
+    #[test]
+    fn utest_same_configuration() {
+        let last_login = |days: Option<u64>, inactivity: Option<Duration>| {
+            UpdateCondition::LastLogin(UpdateLastLoginTimeCondition {
+                id: Some("1".to_string()),
+                number_of_days_since_last_login: days,
+                inactivity,
+                activity_type: None,
+            })
+        };
+        // a legacy condition sent back with its inactivity is unchanged
+        let legacy = last_login(Some(30), None);
+        assert!(legacy.same_configuration(&last_login(None, Some(Duration::days(30)))));
+        assert!(!legacy.same_configuration(&last_login(None, Some(Duration::days(31)))));
+        let any_activity = UpdateCondition::LastLogin(UpdateLastLoginTimeCondition {
+            id: Some("1".to_string()),
+            number_of_days_since_last_login: None,
+            inactivity: Some(Duration::days(30)),
+            activity_type: Some(ActivityType::AnyActivity),
+        });
+        assert!(!legacy.same_configuration(&any_activity));
+    }
+
+    #[test]
+    fn utest_last_login_activity_type() {
+        let args = AddOrUpdateUserArgs {
+            name: None,
+            email: None,
+            user_type: None,
+        };
+        // the owner did not log in for ages but used the api just now
+        let mut user = User::new("owner".to_string(), args);
+        user.date_last_login = Some(0);
+
+        let mut condition = Condition::LastLogin(LastLoginTimeCondition {
+            id: "1".to_string(),
+            number_of_days_since_last_login: None,
+            inactivity: Some(Duration::days(1)),
+            activity_type: None,
+            condition_status: false,
+        });
+        assert!(condition.evaluate(Some(&user)));
+
+        if let Condition::LastLogin(c) = &mut condition {
+            c.activity_type = Some(ActivityType::AnyActivity);
+        }
+        assert!(!condition.evaluate(Some(&user)));
+    }
+
+    #[test]
+    fn utest_vacation() {
+        let args = AddOrUpdateUserArgs {
+            name: None,
+            email: None,
+            user_type: None,
+        };
+        let mut user = User::new("owner".to_string(), args);
+        user.date_last_login = Some(0);
+        user.date_last_activity = Some(0);
+
+        let last_login = Condition::LastLogin(LastLoginTimeCondition {
+            id: "1".to_string(),
+            number_of_days_since_last_login: None,
+            inactivity: Some(Duration::days(10)),
+            activity_type: None,
+            condition_status: false,
+        });
+        let inactivity = match &last_login {
+            Condition::LastLogin(c) => c.inactivity_deadline(0),
+            _ => unreachable!(),
+        };
+        let check_in = Condition::CheckIn(CheckInCondition {
+            id: "2".to_string(),
+            schedule: CheckInSchedule::EveryNumberOfDays(10),
+            grace_period_days: 0,
+            last_check_in: 0,
+            next_deadline: inactivity,
+            condition_status: false,
+        });
+        assert!(last_login.evaluate_at(Some(&user), inactivity + 1));
+        assert!(check_in.evaluate_at(Some(&user), inactivity + 1));
+
+        // the time on vacation is not counted
+        let half = inactivity / 2;
+        assert!(user.start_vacation(half, half).is_err());
+        user.start_vacation(half + 5, half).unwrap();
+        assert!(!last_login.evaluate_at(Some(&user), inactivity + 1));
+        assert!(last_login.evaluate_at(Some(&user), inactivity + 6));
+        assert!(!check_in.evaluate_at(Some(&user), inactivity + 5));
+        assert!(check_in.evaluate_at(Some(&user), inactivity + 6));
+
+        // a vacation cannot be extended beyond the maximum length
+        assert!(user
+            .start_vacation(half + (MAX_VACATION_DAYS + 1) * NANOS_PER_DAY, half + 1)
+            .is_err());
+        assert_eq!(user.vacations().len(), 1);
+
+        // ending the vacation early
+        user.end_vacation(half + 2).unwrap();
+        assert!(last_login.evaluate_at(Some(&user), inactivity + 3));
+        assert!(user.end_vacation(half + 3).is_err());
+    }
+
+    #[test]
+    fn utest_fixed_date_time_window() {
+        let window = |end_datetime: Option<u64>| {
+            Condition::FixedDateTime(FixedDateTimeCondition {
+                id: "1".to_string(),
+                datetime: 1,
+                end_datetime,
+                time_zone: None,
+                condition_status: false,
+            })
+        };
+        assert!(window(None).evaluate(None));
+        assert!(window(Some(u64::MAX)).evaluate(None));
+        // the window is over
+        assert!(!window(Some(2)).evaluate(None));
+    }
+
+    #[test]
+    fn utest_duration_and_time_zone() {
+        let date = |year: i64, month: u32, day: u32| {
+            days_from_civil(year, month, day) as u64 * NANOS_PER_DAY
+        };
+        let mut user = User::new(
+            "owner".to_string(),
+            AddOrUpdateUserArgs {
+                name: None,
+                email: None,
+                user_type: None,
+            },
+        );
+        user.date_last_login = Some(date(2024, 1, 31));
+
+        // the legacy number of days is counted in days, not seconds
+        let mut condition = LastLoginTimeCondition {
+            id: "1".to_string(),
+            number_of_days_since_last_login: Some(30),
+            inactivity: None,
+            activity_type: None,
+            condition_status: false,
+        };
+        assert_eq!(condition.inactivity(), Duration::days(30));
+        assert_eq!(
+            condition.inactivity_deadline(date(2024, 1, 31)),
+            date(2024, 3, 1)
+        );
+
+        // one calendar month after January 31 is the end of February
+        condition.inactivity = Some(Duration {
+            value: 1,
+            unit: DurationUnit::Months,
+        });
+        assert_eq!(
+            condition.inactivity_deadline(date(2024, 1, 31)),
+            date(2024, 2, 29)
+        );
+        let last_login = Condition::LastLogin(condition);
+        assert!(!last_login.evaluate_at(Some(&user), date(2024, 2, 29)));
+        assert!(last_login.evaluate_at(Some(&user), date(2024, 2, 29) + 1));
+
+        let after =
+            |value: u64, unit: DurationUnit| Duration { value, unit }.after(date(2023, 2, 28));
+        assert_eq!(
+            after(36, DurationUnit::Hours),
+            date(2023, 3, 1) + 12 * time::NANOS_PER_HOUR
+        );
+        assert_eq!(after(2, DurationUnit::Weeks), date(2023, 3, 14));
+        assert_eq!(after(1, DurationUnit::Years), date(2024, 2, 28));
+
+        // midnight in UTC+2 is 22:00 UTC of the previous day
+        let fixed_date_time = |utc_offset_minutes: i32| FixedDateTimeCondition {
+            id: "2".to_string(),
+            datetime: date(2024, 6, 1),
+            end_datetime: Some(date(2024, 6, 2)),
+            time_zone: Some(TimeZone {
+                name: Some("Europe/Zurich".to_string()),
+                utc_offset_minutes,
+            }),
+            condition_status: false,
+        };
+        let cest = fixed_date_time(120);
+        assert_eq!(
+            cest.starts_at(),
+            date(2024, 5, 31) + 22 * time::NANOS_PER_HOUR
+        );
+        assert_eq!(
+            cest.ends_at(),
+            Some(date(2024, 6, 1) + 22 * time::NANOS_PER_HOUR)
+        );
+        assert!(Condition::FixedDateTime(cest)
+            .evaluate_at(None, date(2024, 5, 31) + 23 * time::NANOS_PER_HOUR));
+        assert_eq!(
+            fixed_date_time(-300).starts_at(),
+            date(2024, 6, 1) + 5 * time::NANOS_PER_HOUR
+        );
+        assert!(fixed_date_time(14 * 60).time_zone.unwrap().is_valid());
+        assert!(!fixed_date_time(15 * 60).time_zone.unwrap().is_valid());
+    }
+
+    #[test]
+    fn utest_beneficiary_claim() {
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        let mut claim_condition = BeneficiaryClaimCondition {
+            id: "1".to_string(),
+            response_period_days: 14,
+            pending_claim: None,
+            last_claims: BTreeMap::new(),
+            condition_status: false,
+        };
+        let args = AddOrUpdateUserArgs {
+            name: None,
+            email: None,
+            user_type: None,
+        };
+        let mut owner = User::new("owner".to_string(), args);
+        owner.date_last_login = Some(0);
+
+        // alice files a claim, nobody else can file while it is pending
+        claim_condition.file_claim(&alice, NANOS_PER_DAY).unwrap();
+        assert_eq!(
+            claim_condition
+                .pending_claim
+                .as_ref()
+                .unwrap()
+                .response_deadline,
+            15 * NANOS_PER_DAY
+        );
+        assert!(claim_condition
+            .file_claim(&bob, 2 * NANOS_PER_DAY)
+            .is_err_and(|e| matches!(e, SmartVaultErr::ClaimAlreadyPending(_))));
+        assert!(!claim_condition.reject_claim_on_login(&owner));
+
+        // the owner signs in: the claim is rejected
+        owner.date_last_login = Some(3 * NANOS_PER_DAY);
+        assert!(claim_condition.reject_claim_on_login(&owner));
+        assert!(claim_condition.pending_claim.is_none());
+
+        // alice has to wait for the cooldown, bob does not
+        assert!(claim_condition
+            .file_claim(&alice, 4 * NANOS_PER_DAY)
+            .is_err_and(|e| matches!(e, SmartVaultErr::ClaimRateLimited(_))));
+        claim_condition.file_claim(&bob, 4 * NANOS_PER_DAY).unwrap();
+
+        // the owner does not respond in time (the deadline lies in the past)
+        let condition = Condition::BeneficiaryClaim(claim_condition);
+        assert!(condition.evaluate(Some(&owner)));
+    }
+
+    #[test]
+    fn utest_oracle_condition() {
+        let mut oracle_condition = OracleCondition {
+            id: "1".to_string(),
+            canister_id: "aaaaa-aa".to_string(),
+            method: "proof_of_life".to_string(),
+            max_days_inactive: Some(30),
+            last_reply: None,
+            last_reply_at: None,
+            last_failure: None,
+            last_call_at: None,
+            call_pending: false,
+            condition_status: false,
+        };
+        let start = 1_000 * NANOS_PER_DAY;
+
+        // a failed call is reported but does not change the reply
+        assert!(oracle_condition.is_due(start));
+        oracle_condition.start_call(start);
+        assert!(!oracle_condition.is_due(start + 1));
+        assert!(oracle_condition.record_reply(start, start + 1, Err("rejected".to_string())));
+        assert!(oracle_condition.last_failure.is_some());
+        assert!(oracle_condition.last_reply.is_none());
+        assert!(!oracle_condition.is_due(start + 1));
+
+        // a call which is not answered in time fails on the next tick, its late reply is ignored
+        let second_call = start + ORACLE_POLL_INTERVAL;
+        assert!(oracle_condition.is_due(second_call));
+        oracle_condition.start_call(second_call);
+        oracle_condition.last_failure = None;
+        assert!(!oracle_condition.expire_call(second_call + ORACLE_CALL_TIMEOUT));
+        let timed_out = second_call + ORACLE_CALL_TIMEOUT + 1;
+        assert!(oracle_condition.expire_call(timed_out));
+        assert!(oracle_condition.last_failure.is_some());
+        assert!(!oracle_condition.expire_call(timed_out + 1));
+        assert!(!oracle_condition.is_due(timed_out));
+        assert!(!oracle_condition.record_reply(
+            second_call,
+            timed_out + 1,
+            Ok(OracleReply::Status(true))
+        ));
+        assert!(oracle_condition.last_reply.is_none());
+
+        // the oracle is called again after the poll interval
+        let third_call = second_call + ORACLE_POLL_INTERVAL;
+        assert!(oracle_condition.is_due(third_call));
+        oracle_condition.start_call(third_call);
+
+        // the last activity reported by the oracle is older than 30 days
+        assert!(oracle_condition.record_reply(
+            third_call,
+            third_call + 1,
+            Ok(OracleReply::LastActivity(0))
+        ));
+        assert!(oracle_condition.last_failure.is_none());
+        let condition = Condition::Oracle(oracle_condition.clone());
+        assert!(condition.evaluate(None));
+
+        // recent activity
+        oracle_condition.start_call(third_call + ORACLE_POLL_INTERVAL);
+        oracle_condition.record_reply(
+            third_call + ORACLE_POLL_INTERVAL,
+            third_call + ORACLE_POLL_INTERVAL + 1,
+            Ok(OracleReply::LastActivity(time::get_current_time())),
+        );
+        let condition = Condition::Oracle(oracle_condition);
+        assert!(!condition.evaluate(None));
+    }
 }
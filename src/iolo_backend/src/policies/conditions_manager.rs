@@ -1,16 +1,25 @@
 use std::cell::RefCell;
 
+use candid::Principal;
+
 use crate::{
     common::error::SmartVaultErr,
     policies::policy::LogicalOperator,
-    smart_vaults::smart_vault::USER_STORE,
-    users::{user::User, user_store::UserStore},
+    smart_vaults::smart_vault::{CONDITION_TEMPLATE_STORE, POLICY_STORE, USER_STORE},
+    users::{
+        dead_mans_switch::DeadMansSwitch,
+        user::{PrincipalID, User},
+        user_store::UserStore,
+        users_interface_impl::get_user_from_user_store,
+    },
+    utils::time,
 };
 
 use super::{
-    conditions::Condition,
+    conditions::{ActivityType, Condition, UpdateCondition},
+    oracles::{is_allowed_oracle, query_oracle, OracleCall, OracleRequest},
     policies_interface_impl::{get_policy_from_policy_store, update_policy_in_policy_store},
-    policy::PolicyID,
+    policy::{Policy, PolicyID},
 };
 
 /// This function is called every time a policy condition is updated (by the login date condition checks or through XooY validation).
@@ -24,7 +33,9 @@ pub fn evaluate_overall_conditions_status(policy_id: &PolicyID) -> Result<(), Sm
         overall_conditions_status = policy.conditions().first().unwrap().get_condition_status();
     }
 
-    if let Some(operator) = logical_operator {
+    if let Some(expression) = policy.conditions_expression() {
+        overall_conditions_status = expression.evaluate(policy.conditions());
+    } else if let Some(operator) = logical_operator {
         match operator {
             LogicalOperator::And => {
                 overall_conditions_status =
@@ -40,15 +51,257 @@ pub fn evaluate_overall_conditions_status(policy_id: &PolicyID) -> Result<(), Sm
     if overall_conditions_status {
         policy.set_conditions_status(true);
         update_policy_in_policy_store(policy.clone())?;
+        if policy.is_released() {
+            propagate_to_dependent_policies(&policy)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Spreads the release of a policy, or its withdrawal by a veto or reset,
+/// to the policies of the same owner which depend on it.
+pub fn propagate_to_dependent_policies(policy: &Policy) -> Result<(), SmartVaultErr> {
+    let owner = get_user_from_user_store(policy.owner())?;
+    for policy_id in owner.policies() {
+        if policy_id == policy.id {
+            continue;
+        }
+        let mut dependent_policy = get_policy_from_policy_store(&policy_id)?;
+        if update_policy_dependencies(&mut dependent_policy) {
+            update_policy_in_policy_store(dependent_policy)?;
+            evaluate_overall_conditions_status(&policy_id)?;
+        }
+    }
+    Ok(())
+}
+
+/// Records when the policies referenced by dependency conditions have been released
+/// and sets the status of dependency conditions whose delay has passed. Dependencies which are
+/// no longer released, e.g. after a reset, are re-armed unless this policy has been released.
+/// Returns true if the policy has been changed.
+fn update_policy_dependencies(policy: &mut Policy) -> bool {
+    let now = time::get_current_time();
+    let policy_released = policy.is_released();
+    let mut policy_changed = false;
+    let mut dependency_withdrawn = false;
+    for condition in policy.conditions_mut().iter_mut() {
+        if let Condition::PolicyDependency(cond) = condition {
+            let dependency_released = get_policy_from_policy_store(&cond.policy_id)
+                .is_ok_and(|dependency| dependency.is_released());
+            if dependency_released && cond.dependency_met_at.is_none() {
+                cond.dependency_met_at = Some(now);
+                policy_changed = true;
+            } else if !dependency_released && cond.dependency_met_at.is_some() && !policy_released {
+                condition.reset(now);
+                dependency_withdrawn = true;
+                policy_changed = true;
+            }
+
+            if !condition.get_condition_status() && condition.evaluate(None) {
+                condition.set_condition_status(true);
+                policy_changed = true;
+            }
+        }
+    }
+    if dependency_withdrawn && *policy.conditions_status() && !policy.overall_conditions_met() {
+        policy.set_conditions_status(false);
+    }
+    policy_changed
+}
+
+/// Stores the inactivity of last login conditions which only have the deprecated number of days.
+/// Called after every upgrade, conditions which already have an inactivity are left untouched.
+pub fn migrate_conditions() {
+    POLICY_STORE.with(|ps| {
+        let mut policy_store = ps.borrow_mut();
+        let migrated: Vec<Policy> = policy_store
+            .policies
+            .iter()
+            .filter_map(|(_, mut policy)| {
+                migrate_last_login_conditions(policy.conditions_mut()).then_some(policy)
+            })
+            .collect();
+        for policy in migrated {
+            policy_store.policies.insert(policy.id().clone(), policy);
+        }
+    });
+
+    USER_STORE.with(|ur: &RefCell<UserStore>| {
+        let mut user_store = ur.borrow_mut();
+        let migrated: Vec<User> = user_store
+            .users()
+            .into_iter()
+            .filter_map(|mut user| {
+                let dead_mans_switch = user.dead_mans_switch.as_mut()?;
+                migrate_last_login_conditions(&mut dead_mans_switch.conditions).then_some(user)
+            })
+            .collect();
+        for user in migrated {
+            user_store.users.insert(user.id().clone(), user);
+        }
+    });
+
+    CONDITION_TEMPLATE_STORE.with(|cts| {
+        let mut condition_template_store = cts.borrow_mut();
+        let migrated: Vec<_> = condition_template_store
+            .templates
+            .iter()
+            .filter_map(|(_, mut template)| {
+                let mut migrated = false;
+                for condition in template.conditions.iter_mut() {
+                    if let UpdateCondition::LastLogin(cond) = condition {
+                        if cond.inactivity.is_none() {
+                            cond.inactivity = Some(cond.inactivity());
+                            cond.number_of_days_since_last_login = None;
+                            migrated = true;
+                        }
+                    }
+                }
+                migrated.then_some(template)
+            })
+            .collect();
+        for template in migrated {
+            condition_template_store
+                .templates
+                .insert(template.id.clone(), template);
+        }
+    });
+}
+
+/// Returns true if one of the conditions has been migrated
+fn migrate_last_login_conditions(conditions: &mut [Condition]) -> bool {
+    let mut migrated = false;
+    for condition in conditions.iter_mut() {
+        if let Condition::LastLogin(cond) = condition {
+            if cond.inactivity.is_none() {
+                cond.inactivity = Some(cond.inactivity());
+                cond.number_of_days_since_last_login = None;
+                migrated = true;
+            }
+        }
     }
+    migrated
+}
 
+/// Sets the dead man's switch conditions of the owner's policies once the switch has been triggered
+pub fn evaluate_dead_mans_switch(owner: &PrincipalID) -> Result<(), SmartVaultErr> {
+    let owner = get_user_from_user_store(owner)?;
+    let Some(DeadMansSwitch {
+        triggered_at: Some(_),
+        ..
+    }) = owner.dead_mans_switch
+    else {
+        return Ok(());
+    };
+    for policy_id in owner.policies() {
+        let mut policy = get_policy_from_policy_store(&policy_id)?;
+        let mut policy_needs_update = false;
+        for condition in policy.conditions_mut().iter_mut() {
+            if let Condition::DeadMansSwitch(_cond) = &condition {
+                if !condition.get_condition_status() {
+                    condition.set_condition_status(true);
+                    policy_needs_update = true;
+                }
+            }
+        }
+        if policy_needs_update {
+            update_policy_in_policy_store(policy)?;
+            evaluate_overall_conditions_status(&policy_id)?;
+        }
+    }
+    Ok(())
+}
+
+/// Clears the pending yes votes of the owner's XOutOfY conditions which are set to be cleared by the given activity
+pub fn clear_votes_on_owner_activity(
+    owner: &PrincipalID,
+    activity: ActivityType,
+) -> Result<(), SmartVaultErr> {
+    let user = get_user_from_user_store(owner)?;
+    let now = time::get_current_time();
+    if let Some(mut dead_mans_switch) = user.dead_mans_switch.clone() {
+        let mut cleared = false;
+        if dead_mans_switch.triggered_at.is_none() {
+            for condition in dead_mans_switch.conditions.iter_mut() {
+                if let Condition::XOutOfY(xooy) = condition {
+                    cleared |= xooy.clear_votes_for_owner_activity(activity, now);
+                }
+            }
+        }
+        if cleared {
+            USER_STORE.with(|ur: &RefCell<UserStore>| {
+                let mut user_store = ur.borrow_mut();
+                user_store.update_dead_mans_switch(owner, Some(dead_mans_switch))
+            })?;
+        }
+    }
+    for policy_id in user.policies() {
+        let mut policy = get_policy_from_policy_store(&policy_id)?;
+        if policy.conditions_status {
+            continue;
+        }
+        let mut cleared = false;
+        for condition in policy.conditions_mut().iter_mut() {
+            if let Condition::XOutOfY(xooy) = condition {
+                cleared |= xooy.clear_votes_for_owner_activity(activity, now);
+            }
+        }
+        if cleared {
+            update_policy_in_policy_store(policy)?;
+        }
+    }
     Ok(())
 }
 
+/// Re-arms the triggered dead man's switch of the owner, e.g. after activity of the owner
+/// or a veto or reset of a policy referencing the switch
+pub fn rearm_dead_mans_switch(owner: &PrincipalID) -> Result<(), SmartVaultErr> {
+    let user = get_user_from_user_store(owner)?;
+    let Some(mut dead_mans_switch) = user.dead_mans_switch else {
+        return Ok(());
+    };
+    if dead_mans_switch.triggered_at.is_none() {
+        return Ok(());
+    }
+    dead_mans_switch.reset(time::get_current_time());
+    USER_STORE.with(|ur: &RefCell<UserStore>| {
+        let mut user_store = ur.borrow_mut();
+        user_store.update_dead_mans_switch(owner, Some(dead_mans_switch))
+    })?;
+    Ok(())
+}
+
+/// Evaluates the dead man's switch of the user once for all policies referencing it.
+/// Returns true if the switch has been triggered.
+fn evaluate_user_dead_mans_switch(user: &mut User) -> bool {
+    let Some(mut dead_mans_switch) = user.dead_mans_switch.clone() else {
+        return false;
+    };
+    if dead_mans_switch.evaluate(user, time::get_current_time()) {
+        USER_STORE
+            .with(|ur: &RefCell<UserStore>| {
+                let mut user_store = ur.borrow_mut();
+                user_store.update_dead_mans_switch(user.id(), Some(dead_mans_switch.clone()))
+            })
+            .ok();
+    }
+    let triggered = dead_mans_switch.triggered_at.is_some();
+    user.dead_mans_switch = Some(dead_mans_switch);
+    triggered
+}
+
 /// This function is called every time the timer fires.
 /// It will check the last login date of all users and set the condition status of all policies
 /// to true if the last login date is older than the allowed number of days.
+/// The same applies to fixed date time conditions, missed check-ins, delayed policy dependencies
+/// and unanswered beneficiary claims.
+/// Oracles of oracle conditions are called once their poll interval is over.
+/// The dead man's switch of a user is evaluated once, before the policies referencing it.
+/// Finally, pending releases are finalized once their cooling-off period is over.
 pub fn check_time_based_conditions() {
+    let mut oracle_calls: Vec<OracleCall> = vec![];
+
     // read all users
     let users: Vec<User> = USER_STORE.with(|ur: &RefCell<UserStore>| -> Vec<User> {
         let user_store = ur.borrow();
@@ -56,7 +309,9 @@ pub fn check_time_based_conditions() {
     });
 
     // iterate over all existing users
-    for user in users {
+    for mut user in users {
+        let dead_mans_switch_triggered = evaluate_user_dead_mans_switch(&mut user);
+
         // iterate over all policies
         for policy_id in user.policies() {
             // get policy from policy store
@@ -64,6 +319,7 @@ pub fn check_time_based_conditions() {
 
             // track whether policy needs to be updated
             let mut policy_needs_update = false;
+            let mut release_window_closed = false;
 
             // iterate over policy conditions
             for condition in policy.conditions_mut().iter_mut() {
@@ -77,19 +333,111 @@ pub fn check_time_based_conditions() {
                         //ic_cdk::println!("Last login date of user {:?} is NOT older than {:?} days!", user.id, cond.number_of_days_since_last_login);
                     }
                 }
-                
+
                 if let Condition::FixedDateTime(_cond) = &condition {
                     if condition.evaluate(None) {
                         // Time based condition is met, set condition status of all user policies to true
                         //ic_cdk::println!("Time based condition is met, condition status is set to true");
                         condition.set_condition_status(true);
                         policy_needs_update = true;
-                    } else {
-                        //ic_cdk::println!("Time based condition is NOT met!");
+                    } else if condition.get_condition_status() {
+                        // The release window is over
+                        condition.set_condition_status(false);
+                        release_window_closed = true;
+                        policy_needs_update = true;
+                    }
+                }
+
+                if let Condition::XOutOfY(cond) = condition {
+                    // Reset votes which expired or whose voting window has passed
+                    if cond.expire_votes(time::get_current_time()) {
+                        policy_needs_update = true;
+                    }
+                }
+
+                if let Condition::BeneficiaryClaim(cond) = condition {
+                    // The owner rejected the claim by signing in
+                    if cond.reject_claim_on_login(&user) {
+                        policy_needs_update = true;
+                    }
+                }
+                if let Condition::BeneficiaryClaim(_cond) = &condition {
+                    if !condition.get_condition_status() && condition.evaluate(Some(&user)) {
+                        // The owner did not respond to the claim in time
+                        condition.set_condition_status(true);
+                        policy_needs_update = true;
+                    }
+                }
+
+                if let Condition::Oracle(cond) = condition {
+                    let now = time::get_current_time();
+                    // The pending call has not been answered in time
+                    if cond.expire_call(now) {
+                        policy_needs_update = true;
+                    }
+                    // Call the oracle, its reply is recorded asynchronously.
+                    // Only oracles allowed by the controllers are called.
+                    if cond.is_due(now) && is_allowed_oracle(&cond.canister_id) {
+                        if let Ok(canister_id) = Principal::from_text(&cond.canister_id) {
+                            cond.start_call(now);
+                            oracle_calls.push(OracleCall {
+                                canister_id,
+                                method: cond.method.clone(),
+                                request: OracleRequest {
+                                    owner: user.id().clone(),
+                                    policy_id: policy_id.clone(),
+                                    condition_id: cond.id.clone(),
+                                },
+                                started_at: now,
+                            });
+                            policy_needs_update = true;
+                        }
+                    }
+                }
+                if let Condition::Oracle(_cond) = &condition {
+                    if !condition.get_condition_status() && condition.evaluate(None) {
+                        // The last activity reported by the oracle is too old
+                        condition.set_condition_status(true);
+                        policy_needs_update = true;
+                    }
+                }
+
+                if let Condition::CheckIn(_cond) = &condition {
+                    if condition.evaluate(Some(&user)) {
+                        // Owner missed the check-in deadline including the grace period
+                        condition.set_condition_status(true);
+                        policy_needs_update = true;
+                    }
+                }
+
+                if let Condition::DeadMansSwitch(_cond) = &condition {
+                    if !condition.get_condition_status() && dead_mans_switch_triggered {
+                        // The switch has been evaluated once for all policies of the user
+                        condition.set_condition_status(true);
+                        policy_needs_update = true;
                     }
                 }
             }
 
+            // withdraw a pending release if it depended on a closed release window,
+            // a policy which has been released stays released
+            if release_window_closed
+                && policy.is_release_pending()
+                && !policy.overall_conditions_met()
+            {
+                policy.set_conditions_status(false);
+            }
+
+            // check the policies this policy depends on
+            if update_policy_dependencies(&mut policy) {
+                policy_needs_update = true;
+            }
+
+            // release the policy if the cooling-off period is over
+            if policy.finalize_release(time::get_current_time()) {
+                policy_needs_update = true;
+            }
+
             // update policy in policy store?
             if policy_needs_update {
                 update_policy_in_policy_store(policy.clone()).ok();
@@ -97,4 +445,9 @@ pub fn check_time_based_conditions() {
             }
         }
     }
-}
\ No newline at end of file
+
+    // the oracle calls are issued once all policies are stored
+    for call in oracle_calls {
+        ic_cdk::spawn(query_oracle(call));
+    }
+}
@@ -0,0 +1,244 @@
+//! Forecast of the release of a policy.
+//! The conditions of a policy are evaluated at future points in time with optional hypothetical inputs
+//! (last login, current time, validator votes). The forecast works on a copy of the policy
+//! and never changes the policy store.
+
+use candid::{CandidType, Deserialize};
+use serde::Serialize;
+
+use crate::users::user::{PrincipalID, User};
+use crate::utils::time;
+
+use super::{
+    conditions::{Condition, ConditionExpression, ConditionID},
+    oracles::OracleReply,
+    policies_interface_impl::get_policy_from_policy_store,
+    policy::{LogicalOperator, Policy, PolicyID},
+};
+
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone)]
+pub struct ForecastPolicyArgs {
+    pub policy_id: PolicyID,
+    /// Hypothetical last login (and last activity) of the owner
+    pub last_login: Option<u64>,
+    /// Hypothetical current time
+    pub now: Option<u64>,
+    /// Hypothetical votes of validators
+    pub votes: Vec<SimulatedVote>,
+}
+
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone)]
+pub struct SimulatedVote {
+    pub condition_id: ConditionID,
+    pub validator: PrincipalID,
+    pub status: bool,
+}
+
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone)]
+pub struct ConditionForecast {
+    pub condition_id: ConditionID,
+    /// Projected status at the (simulated) current time
+    pub status: bool,
+    /// Earliest point in time at which the condition is projected to be met
+    pub met_at: Option<u64>,
+}
+
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone)]
+pub struct PolicyForecast {
+    pub policy_id: PolicyID,
+    pub now: u64,
+    pub conditions: Vec<ConditionForecast>,
+    pub conditions_logical_operator: Option<LogicalOperator>,
+    pub conditions_expression: Option<ConditionExpression>,
+    /// Projected overall status at the (simulated) current time
+    pub conditions_status: bool,
+    /// Earliest point in time at which the conditions are projected to be met
+    pub conditions_met_at: Option<u64>,
+    /// Earliest projected release, taking the cooling-off period, not before and expiry into account
+    pub release_date: Option<u64>,
+}
+
+/// Forecasts the release of a policy. Dependencies on other policies which are not yet released
+/// are replaced by the forecast release of the referenced policies.
+pub fn forecast_policy(
+    mut policy: Policy,
+    owner: &User,
+    args: &ForecastPolicyArgs,
+) -> PolicyForecast {
+    for condition in policy.conditions_mut().iter_mut() {
+        if let Condition::PolicyDependency(cond) = condition {
+            if cond.dependency_met_at.is_some() {
+                continue;
+            }
+            if let Ok(dependency) = get_policy_from_policy_store(&cond.policy_id) {
+                let dependency_args = ForecastPolicyArgs {
+                    policy_id: cond.policy_id.clone(),
+                    ..args.clone()
+                };
+                cond.dependency_met_at =
+                    forecast_policy(dependency, owner, &dependency_args).release_date;
+            }
+        }
+    }
+    forecast_conditions(policy, owner, args)
+}
+
+/// Forecasts the release of a policy based on its own conditions only
+fn forecast_conditions(
+    mut policy: Policy,
+    owner: &User,
+    args: &ForecastPolicyArgs,
+) -> PolicyForecast {
+    let now = args.now.unwrap_or_else(time::get_current_time);
+
+    let mut owner = owner.clone();
+    if let Some(last_login) = args.last_login {
+        owner.date_last_login = Some(last_login);
+        owner.date_last_activity = Some(last_login);
+    }
+
+    for vote in args.votes.iter() {
+        for condition in policy.conditions_mut().iter_mut() {
+            if let Condition::XOutOfY(cond) = condition {
+                if cond.id == vote.condition_id {
+                    cond.register_vote(&vote.validator, vote.status, now);
+                }
+            }
+        }
+    }
+
+    // points in time at which the status of a condition may change
+    let mut candidates: Vec<u64> = vec![now];
+    for condition in policy.conditions() {
+        candidates.extend(transition_times(condition, &owner));
+    }
+    candidates.retain(|t| *t >= now);
+    candidates.sort_unstable();
+    candidates.dedup();
+
+    let projected_status = |condition: &Condition, at: u64| {
+        condition.get_condition_status() || condition.evaluate_at(Some(&owner), at)
+    };
+
+    let conditions: Vec<ConditionForecast> = policy
+        .conditions()
+        .iter()
+        .map(|condition| ConditionForecast {
+            condition_id: condition.id(),
+            status: projected_status(condition, now),
+            met_at: candidates
+                .iter()
+                .copied()
+                .find(|t| projected_status(condition, *t)),
+        })
+        .collect();
+
+    let overall_status_at = |at: u64| {
+        let mut projection = policy.clone();
+        for condition in projection.conditions_mut().iter_mut() {
+            let status = projected_status(condition, at);
+            condition.set_condition_status(status);
+        }
+        projection.overall_conditions_met()
+    };
+
+    let cooling_off_period: u64 = policy
+        .cooling_off_period_days
+        .unwrap_or(0)
+        .saturating_mul(time::NANOS_PER_DAY);
+    let (conditions_status, conditions_met_at, release_date) = if *policy.conditions_status() {
+        (true, Some(now), policy.release_date)
+    } else if policy.conditions().is_empty() {
+        (false, None, None)
+    } else {
+        let conditions_met_at = candidates.iter().copied().find(|t| overall_status_at(*t));
+        let release_date = conditions_met_at
+            .map(|met_at| {
+                met_at
+                    .saturating_add(cooling_off_period)
+                    .max(policy.not_before.unwrap_or(0))
+            })
+            .filter(|release_date| !policy.is_expired(*release_date));
+        (
+            conditions_met_at == Some(now),
+            conditions_met_at,
+            release_date,
+        )
+    };
+
+    PolicyForecast {
+        policy_id: policy.id().to_string(),
+        now,
+        conditions,
+        conditions_logical_operator: policy.conditions_logical_operator().clone(),
+        conditions_expression: policy.conditions_expression().clone(),
+        conditions_status,
+        conditions_met_at,
+        release_date,
+    }
+}
+
+/// Points in time at which the result of `Condition::evaluate_at` may change
+fn transition_times(condition: &Condition, owner: &User) -> Vec<u64> {
+    match condition {
+        Condition::LastLogin(cond) => cond
+            .last_seen(owner)
+            .map(|last_seen| {
+                cond.inactivity_deadline(
+                    last_seen
+                        .saturating_add(owner.paused_time(last_seen, u64::MAX))
+                        .saturating_add(owner.deadline_extension(last_seen)),
+                )
+                .saturating_add(1)
+            })
+            .into_iter()
+            .collect(),
+        Condition::FixedDateTime(cond) => [Some(cond.starts_at()), cond.ends_at()]
+            .into_iter()
+            .flatten()
+            .collect(),
+        Condition::CheckIn(cond) => {
+            let grace_period: u64 = cond.grace_period_days.saturating_mul(time::NANOS_PER_DAY);
+            vec![cond
+                .next_deadline
+                .saturating_add(grace_period)
+                .saturating_add(owner.paused_time(cond.last_check_in, u64::MAX))
+                .saturating_add(1)]
+        }
+        Condition::PolicyDependency(cond) => {
+            let delay: u64 = cond
+                .delay_days
+                .unwrap_or(0)
+                .saturating_mul(time::NANOS_PER_DAY);
+            cond.dependency_met_at
+                .map(|met_at| met_at.saturating_add(delay))
+                .into_iter()
+                .collect()
+        }
+        Condition::BeneficiaryClaim(cond) => cond
+            .pending_claim
+            .as_ref()
+            .map(|claim| claim.response_deadline.saturating_add(1))
+            .into_iter()
+            .collect(),
+        Condition::Oracle(cond) => match (&cond.last_reply, cond.max_days_inactive) {
+            (Some(OracleReply::LastActivity(last_activity)), Some(days)) => vec![last_activity
+                .saturating_add(days.saturating_mul(time::NANOS_PER_DAY))
+                .saturating_add(1)],
+            _ => vec![],
+        },
+        Condition::DeadMansSwitch(_) => match &owner.dead_mans_switch {
+            Some(switch) => match switch.triggered_at {
+                Some(triggered_at) => vec![triggered_at],
+                None => switch
+                    .conditions
+                    .iter()
+                    .flat_map(|c| transition_times(c, owner))
+                    .collect(),
+            },
+            None => vec![],
+        },
+        // votes are not projected
+        Condition::XOutOfY(_) => vec![],
+    }
+}
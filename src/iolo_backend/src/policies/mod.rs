@@ -1,5 +1,11 @@
+pub mod beneficiary_challenges;
+pub mod condition_template_store;
+pub mod condition_templates;
 pub mod conditions;
 pub mod conditions_manager;
+pub mod forecast;
+pub mod oracle_store;
+pub mod oracles;
 pub mod policies_interface_impl;
 pub mod policies_interface_impl_utests;
 pub mod policy;
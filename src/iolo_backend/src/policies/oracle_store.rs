@@ -0,0 +1,52 @@
+use ic_stable_structures::StableBTreeMap;
+use serde::{Deserialize, Serialize};
+
+use crate::common::memory::{get_stable_btree_memory_for_oracles, Memory};
+use crate::users::user::PrincipalID;
+
+/// Oracle canisters which may be called by oracle conditions, managed by the controllers.
+/// Maps the canister id to the date it was added.
+#[derive(Serialize, Deserialize)]
+pub struct OracleStore {
+    #[serde(skip, default = "init_stable_data")]
+    pub oracles: StableBTreeMap<PrincipalID, u64, Memory>,
+}
+
+fn init_stable_data() -> StableBTreeMap<PrincipalID, u64, Memory> {
+    StableBTreeMap::init(get_stable_btree_memory_for_oracles())
+}
+
+impl Default for OracleStore {
+    fn default() -> Self {
+        Self {
+            oracles: init_stable_data(),
+        }
+    }
+}
+
+impl OracleStore {
+    pub fn new() -> Self {
+        Self {
+            oracles: init_stable_data(),
+        }
+    }
+
+    pub fn is_allowed(&self, canister_id: &PrincipalID) -> bool {
+        self.oracles.contains_key(canister_id)
+    }
+
+    pub fn get_oracles(&self) -> Vec<PrincipalID> {
+        self.oracles
+            .iter()
+            .map(|(canister_id, _)| canister_id)
+            .collect()
+    }
+
+    pub fn add_oracle(&mut self, canister_id: PrincipalID, date_added: u64) {
+        self.oracles.insert(canister_id, date_added);
+    }
+
+    pub fn remove_oracle(&mut self, canister_id: &PrincipalID) -> Option<u64> {
+        self.oracles.remove(canister_id)
+    }
+}
@@ -0,0 +1,127 @@
+//! Oracles are canisters which provide external proof-of-life signals for oracle conditions,
+//! e.g. a registry, a ledger-activity or a notary canister.
+//! They are called periodically by the timer and have to implement the interface defined in `oracle.did`.
+
+use candid::{CandidType, Deserialize, Principal};
+use serde::Serialize;
+
+use crate::common::error::SmartVaultErr;
+use crate::smart_vaults::smart_vault::ORACLE_STORE;
+use crate::users::user::PrincipalID;
+use crate::utils::time;
+
+use super::{
+    conditions::{Condition, ConditionID},
+    conditions_manager::evaluate_overall_conditions_status,
+    policies_interface_impl::{get_policy_from_policy_store, update_policy_in_policy_store},
+    policy::PolicyID,
+};
+
+/// Minimum time between two calls to the oracle of a condition (1 hour)
+pub const ORACLE_POLL_INTERVAL: u64 = 60 * 60 * 1_000_000_000;
+
+/// Time after which an unanswered oracle call is considered as failed (5 minutes)
+pub const ORACLE_CALL_TIMEOUT: u64 = 5 * 60 * 1_000_000_000;
+
+/// Argument passed to the oracle method
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone, PartialEq)]
+pub struct OracleRequest {
+    pub owner: String,
+    pub policy_id: PolicyID,
+    pub condition_id: ConditionID,
+}
+
+/// Reply of the oracle method
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone, PartialEq)]
+pub enum OracleReply {
+    /// The oracle decides itself whether the condition is met
+    Status(bool),
+    /// Last activity of the owner known to the oracle (in nanoseconds)
+    LastActivity(u64),
+}
+
+/// Failed call to an oracle. Failures are reported on the condition but never change its status.
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone, PartialEq)]
+pub struct OracleFailure {
+    pub date: u64,
+    pub message: String,
+}
+
+/// An oracle call issued by the timer
+#[derive(Debug, Clone)]
+pub struct OracleCall {
+    pub canister_id: Principal,
+    pub method: String,
+    pub request: OracleRequest,
+    pub started_at: u64,
+}
+
+/// Adds a canister to the oracles which may be called by oracle conditions
+pub fn add_oracle_impl(canister_id: PrincipalID) -> Result<(), SmartVaultErr> {
+    if Principal::from_text(&canister_id).is_err() {
+        return Err(SmartVaultErr::InvalidOracleCondition(canister_id));
+    }
+    ORACLE_STORE.with(|os| {
+        os.borrow_mut()
+            .add_oracle(canister_id, time::get_current_time())
+    });
+    Ok(())
+}
+
+/// Removes a canister from the oracles. Its conditions are no longer called.
+pub fn remove_oracle_impl(canister_id: PrincipalID) -> Result<(), SmartVaultErr> {
+    ORACLE_STORE
+        .with(|os| os.borrow_mut().remove_oracle(&canister_id))
+        .map(|_| ())
+        .ok_or(SmartVaultErr::OracleNotAllowed(canister_id))
+}
+
+pub fn get_oracle_list_impl() -> Vec<PrincipalID> {
+    ORACLE_STORE.with(|os| os.borrow().get_oracles())
+}
+
+pub fn is_allowed_oracle(canister_id: &PrincipalID) -> bool {
+    ORACLE_STORE.with(|os| os.borrow().is_allowed(canister_id))
+}
+
+/// Calls the oracle and records its reply on the condition.
+pub async fn query_oracle(call: OracleCall) {
+    let result: Result<(OracleReply,), String> =
+        ic_cdk::api::call::call(call.canister_id, &call.method, (call.request.clone(),))
+            .await
+            .map_err(|(code, message)| format!("{:?}: {}", code, message));
+
+    record_oracle_result(
+        &call,
+        time::get_current_time(),
+        result.map(|(reply,)| reply),
+    )
+    .ok();
+}
+
+fn record_oracle_result(
+    call: &OracleCall,
+    now: u64,
+    result: Result<OracleReply, String>,
+) -> Result<(), SmartVaultErr> {
+    let mut policy = get_policy_from_policy_store(&call.request.policy_id)?;
+    let mut policy_needs_update = false;
+
+    for condition in policy.conditions_mut().iter_mut() {
+        if condition.id() != call.request.condition_id {
+            continue;
+        }
+        if let Condition::Oracle(cond) = condition {
+            policy_needs_update = cond.record_reply(call.started_at, now, result.clone());
+        }
+        if policy_needs_update && !condition.get_condition_status() && condition.evaluate(None) {
+            condition.set_condition_status(true);
+        }
+    }
+
+    if policy_needs_update {
+        update_policy_in_policy_store(policy)?;
+        evaluate_overall_conditions_status(&call.request.policy_id)?;
+    }
+    Ok(())
+}
@@ -1,21 +1,46 @@
-use crate::policies::conditions::{ConfirmXOutOfYConditionArgs, UpdateCondition};
+use crate::policies::conditions::{
+    CommitXOutOfYVoteArgs, ConfirmXOutOfYConditionArgs, EvidenceVisibility, FileClaimArgs,
+    RevealXOutOfYVoteArgs, TimeZone, UpdateCondition,
+};
 use crate::policies::policy::UpdatePolicyArgs;
 use crate::secrets::secrets_interface_impl::get_secret_from_secret_store;
 use crate::users::users_interface_impl::get_user_from_user_store;
+use crate::utils::random::get_new_random;
 use crate::{
     common::{error::SmartVaultErr, uuid::UUID},
     secrets::{
-        secret::{Secret, SecretListEntry},
+        secret::{Secret, SecretID, SecretListEntry},
         secrets_interface_impl::get_secret_impl,
     },
-    smart_vaults::smart_vault::{POLICY_REGISTRIES, POLICY_STORE, SECRET_STORE, USER_STORE},
-    users::user::PrincipalID,
+    smart_vaults::smart_vault::{
+        CONDITION_TEMPLATE_STORE, POLICY_REGISTRIES, POLICY_STORE, SECRET_STORE, USER_STORE,
+    },
+    users::{
+        dead_mans_switch::DeadMansSwitch,
+        user::{PrincipalID, User},
+    },
 };
 use crate::utils::time;
+use candid::Principal;
+use std::collections::{BTreeMap, HashSet};
 
+use super::beneficiary_challenges::{
+    AnswerBeneficiaryChallengeArgs, BeneficiaryChallenge, ChallengeToken,
+    SetBeneficiaryChallengeArgs,
+};
+use super::condition_templates::{
+    builtin_templates, get_builtin_template, ApplyConditionTemplateArgs, ConditionTemplate,
+    ConditionTemplateID, ConditionTemplateUpdate, CreateConditionTemplateArgs,
+    UpdateConditionTemplateArgs,
+};
 use super::conditions::ConditionUpdate;
-use super::conditions_manager::evaluate_overall_conditions_status;
-use super::policy::PolicyForValidator;
+use super::conditions::{ConditionID, QuorumHealth, Validator};
+use super::conditions_manager::{
+    evaluate_overall_conditions_status, propagate_to_dependent_policies, rearm_dead_mans_switch,
+};
+use super::forecast::{forecast_policy, ForecastPolicyArgs, PolicyForecast};
+use super::oracles::is_allowed_oracle;
+use super::policy::{ConditionResetReason, PolicyForValidator, VotingDeadline};
 use super::{
     conditions::Condition,
     policy::{CreatePolicyArgs, Policy, PolicyID, PolicyListEntry, PolicyWithSecretListEntries},
@@ -67,7 +92,14 @@ pub fn get_policy_as_owner_impl(
 ) -> Result<PolicyWithSecretListEntries, SmartVaultErr> {
     // get policy from policy store
     let policy: Policy = get_policy_from_policy_store(&policy_id)?;
+    get_policy_response_for_owner(policy, &caller)
+}
 
+/// Policy as returned to the owner: with the secret list entries, without the challenge hashes
+pub fn get_policy_response_for_owner(
+    policy: Policy,
+    caller: &PrincipalID,
+) -> Result<PolicyWithSecretListEntries, SmartVaultErr> {
     let mut policy_response = PolicyWithSecretListEntries::from(policy.clone());
     for secret_id in policy.secrets() {
         // get secret from secret store
@@ -114,25 +146,28 @@ pub fn get_policy_as_beneficiary_impl(
     };
 
     // Check that beneficiary is allowed to read the policy
-    if *policy.conditions_status() {
-        // Get secrets from defined in policy
-        let mut policy_for_beneficiary = PolicyWithSecretListEntries::from(policy.clone());
-        for secret_ref in policy.secrets() {
-            let secret = SECRET_STORE.with(|ss| {
-                let secret_store = ss.borrow();
-                secret_store.get(secret_ref)
-            })?;
-            let secret_list_entry = SecretListEntry {
-                id: secret.id(),
-                category: secret.category(),
-                name: secret.name(),
-            };
-            policy_for_beneficiary.secrets().insert(secret_list_entry);
-        }
-        Ok(policy_for_beneficiary)
-    } else {
-        Err(SmartVaultErr::InvalidPolicyCondition)
+    policy.check_beneficiary_access()?;
+
+    // Only the secrets assigned to the beneficiary and of unlocked release stages are exposed
+    let mut policy = policy;
+    policy.retain_secrets_of_beneficiary(&beneficiary);
+    policy.retain_unlocked_secrets(time::get_current_time());
+
+    // Get secrets from defined in policy
+    let mut policy_for_beneficiary = PolicyWithSecretListEntries::from(policy.clone());
+    for secret_ref in policy.secrets() {
+        let secret = SECRET_STORE.with(|ss| {
+            let secret_store = ss.borrow();
+            secret_store.get(secret_ref)
+        })?;
+        let secret_list_entry = SecretListEntry {
+            id: secret.id(),
+            category: secret.category(),
+            name: secret.name(),
+        };
+        policy_for_beneficiary.secrets().insert(secret_list_entry);
     }
+    Ok(policy_for_beneficiary)
 }
 
 pub fn get_policy_as_validator_impl(
@@ -141,7 +176,12 @@ pub fn get_policy_as_validator_impl(
 ) -> Result<PolicyForValidator, SmartVaultErr> {
     // get policy from policy store
     let policy: Policy = get_policy_from_policy_store(&policy_id)?;
-    if policy.conditions_status {
+    // guardians can still reject triggered conditions until the policy is released
+    let guardian = policy.conditions().iter().any(|c| match c {
+        Condition::XOutOfY(xooy) => xooy.is_guardian(&validator),
+        _ => false,
+    });
+    if policy.is_released() || (policy.conditions_status && !guardian) {
         return Err(SmartVaultErr::NoPolicyForValidator(format!(
             "Policy {:?} has nothing to validate", policy_id
         )));
@@ -172,8 +212,9 @@ fn map_policy_for_validator(policy: &Policy, validator: &PrincipalID) -> Result<
         .into_iter()
         .filter_map(|c| match c {
             Condition::XOutOfY(xooy) => {
-                if !xooy.condition_status {
-                    //only conditions which are not already true should be validated
+                if !xooy.condition_status || (xooy.is_guardian(validator) && !policy.is_released())
+                {
+                    //only conditions which are not already true should be validated, guardians can still reject them
                     let mut xooy_clone = xooy.clone();
                     xooy_clone.validators = xooy.validators.iter().filter_map(
                         |v| {
@@ -183,6 +224,23 @@ fn map_policy_for_validator(policy: &Policy, validator: &PrincipalID) -> Result<
                             return None
                         }
                     ).collect(); // Reset the validators to an empty vector
+                    xooy_clone.backup_validators = None;
+                    // other validators only see evidence which has been shared with them,
+                    // in a secret ballot nothing of other validators is disclosed
+                    let secret_ballot = xooy.secret_ballot_days.is_some();
+                    if let Some(vote_evidence) = xooy_clone.vote_evidence.as_mut() {
+                        vote_evidence.retain(|principal_id, e| {
+                            principal_id == validator
+                                || (!secret_ballot
+                                    && e.visibility == EvidenceVisibility::Validators)
+                        });
+                    }
+                    if let Some(commitments) = xooy_clone.commitments.as_mut() {
+                        commitments.retain(|principal_id, _| principal_id == validator);
+                    }
+                    if let Some(vote_clearances) = xooy_clone.vote_clearances.as_mut() {
+                        vote_clearances.retain(|principal_id, _| principal_id == validator);
+                    }
                     return Some(Condition::XOutOfY(xooy_clone)) // Return the modified condition
                 }
                 return None
@@ -191,6 +249,34 @@ fn map_policy_for_validator(policy: &Policy, validator: &PrincipalID) -> Result<
         })
         .collect();
 
+    // remaining time of running voting windows and of the validator's own votes,
+    // the phases of secret ballots depend on all validators and are taken from the unfiltered conditions
+    let now = time::get_current_time();
+    let voting_deadlines: Vec<VotingDeadline> = policy
+        .conditions()
+        .iter()
+        .filter_map(|c| match c {
+            Condition::XOutOfY(xooy) if !xooy.condition_status => Some(VotingDeadline {
+                condition_id: xooy.id.clone(),
+                voting_window_remaining: xooy
+                    .voting_window_end()
+                    .map(|end| end.saturating_sub(now)),
+                vote_expiry_remaining: xooy
+                    .validators
+                    .iter()
+                    .find(|v| &v.principal_id == validator)
+                    .and_then(|v| v.vote_expires_at)
+                    .map(|expiry| expiry.saturating_sub(now)),
+                commit_phase_remaining: xooy
+                    .commit_deadline
+                    .filter(|_| !xooy.reveal_open(now))
+                    .map(|deadline| deadline.saturating_sub(now)),
+                reveal_open: xooy.secret_ballot_days.map(|_| xooy.reveal_open(now)),
+            }),
+            _ => None,
+        })
+        .collect();
+
     if filtered_xooy_conditions.len() == 0 {
         return Err(SmartVaultErr::NoPolicyForValidator(format!(
             "Policy {:?} has nothing to validate", policy.id
@@ -201,6 +287,10 @@ fn map_policy_for_validator(policy: &Policy, validator: &PrincipalID) -> Result<
         id: policy.id().to_string(),
         owner: policy.owner().to_string(),
         conditions: filtered_xooy_conditions,
+        voting_deadlines,
+        owner_vacations: get_user_from_user_store(policy.owner())
+            .map(|owner| owner.vacations.unwrap_or_default())
+            .unwrap_or_default(),
     })
 }
 
@@ -238,7 +328,7 @@ pub fn get_policy_list_as_validator_impl(
 /// 2) provided ConditionUpdates without IDs (None) will create new conditions
 /// 3) existing conditions without a matching ConditionUpdate will be removed
 pub async fn update_policy_impl(
-    upa: UpdatePolicyArgs,
+    mut upa: UpdatePolicyArgs,
     caller: PrincipalID,
 ) -> Result<Policy, SmartVaultErr> {
     // check if policy exists
@@ -249,6 +339,27 @@ pub async fn update_policy_impl(
         return Err(SmartVaultErr::CallerNotPolicyOwner(upa.id));
     }
 
+    // vetoers, release stages and secret assignments which are not sent keep their current value,
+    // without the beneficiaries and secrets which are no longer part of the policy
+    if upa.vetoers.is_none() {
+        upa.vetoers = Some(old_policy.vetoers());
+    }
+    if upa.release_stages.is_none() {
+        let mut release_stages = old_policy.release_stages.clone().unwrap_or_default();
+        for stage in release_stages.iter_mut() {
+            stage.secrets.retain(|s| upa.secrets.contains(s));
+        }
+        upa.release_stages = Some(release_stages);
+    }
+    if upa.beneficiary_secrets.is_none() {
+        let mut beneficiary_secrets = old_policy.beneficiary_secrets.clone().unwrap_or_default();
+        beneficiary_secrets.retain(|beneficiary, secrets| {
+            secrets.retain(|s| upa.secrets.contains(s));
+            upa.beneficiaries.contains(beneficiary)
+        });
+        upa.beneficiary_secrets = Some(beneficiary_secrets);
+    }
+
     // check if secrets in policy exist in secret store
     // check that caller is owner of the secrets
     // check that each secret is related in the key box
@@ -282,35 +393,161 @@ pub async fn update_policy_impl(
         }
     }
 
+    // Check that vetoers exist in user store
+    for vetoer in upa.vetoers.iter().flatten() {
+        if get_user_from_user_store(&vetoer.to_string()).is_err() {
+            return Err(SmartVaultErr::UserDoesNotExist(vetoer.to_string()));
+        }
+    }
+
+    // Check that staged secrets are part of the policy and assigned to one stage only
+    let mut staged_secrets: HashSet<&SecretID> = HashSet::new();
+    for stage in upa.release_stages.iter().flatten() {
+        for secret_id in stage.secrets.iter() {
+            if !upa.secrets.contains(secret_id) || !staged_secrets.insert(secret_id) {
+                return Err(SmartVaultErr::InvalidReleaseStage(secret_id.to_string()));
+            }
+        }
+    }
+
+    // Check that secrets are only assigned to beneficiaries of the policy
+    for (beneficiary, secrets) in upa.beneficiary_secrets.iter().flatten() {
+        if !upa.beneficiaries.contains(beneficiary) {
+            return Err(SmartVaultErr::InvalidBeneficiarySecrets(
+                beneficiary.to_string(),
+            ));
+        }
+        if let Some(secret_id) = secrets.iter().find(|s| !upa.secrets.contains(*s)) {
+            return Err(SmartVaultErr::InvalidBeneficiarySecrets(
+                secret_id.to_string(),
+            ));
+        }
+    }
+
+    // Check that the policy does not expire before it can be released
+    if let (Some(not_before), Some(expires_at)) = (upa.not_before, upa.expires_at) {
+        if expires_at <= not_before {
+            return Err(SmartVaultErr::InvalidDateTime(expires_at.to_string()));
+        }
+    }
+
+    // Check that changed not before and expiry dates are not in the past
+    let now = time::get_current_time();
+    if let Some(not_before) = upa.not_before {
+        if upa.not_before != old_policy.not_before && not_before < now {
+            return Err(SmartVaultErr::InvalidDateTime(not_before.to_string()));
+        }
+    }
+    if let Some(expires_at) = upa.expires_at {
+        if upa.expires_at != old_policy.expires_at && expires_at < now {
+            return Err(SmartVaultErr::InvalidDateTime(expires_at.to_string()));
+        }
+    }
+
     // Check that logical operator is only set if two or more conditions are provided
     if upa.conditions.len() < 2 && upa.conditions_logical_operator.is_some() {
         return Err(SmartVaultErr::LogicalOperatorWithLessThanTwoConditions);
     }
 
+    // Check that a condition expression is not combined with a logical operator and covers all conditions
+    if let Some(expression) = &upa.conditions_expression {
+        if upa.conditions_logical_operator.is_some() {
+            return Err(SmartVaultErr::InvalidConditionExpression(
+                "Logical operator and condition expression cannot be used together".to_string(),
+            ));
+        }
+        expression.validate(upa.conditions.len())?;
+    }
+
+    // Check that dependencies reference own policies only and do not form a cycle
+    check_policy_dependencies(&upa.id, &upa.conditions, &caller)?;
+
     // let's keep track of the new conditions
     let mut new_final_condition_set: Vec<Condition> = vec![];
+    // triggered conditions whose configuration has been changed are set back to false
+    let mut edited_triggered_conditions: Vec<ConditionID> = vec![];
 
     // Find out which conditions are brand new and which conditions need updates
     for uc in upa.conditions.iter() {
         // Validation of x out of y conditions
         if let UpdateCondition::XOutOfY(c) = uc.clone() {
-            // Check that validators do exist if it's an XOutOfY condition
-            for v in c.validators.clone() {
+            // Check that validators and backup validators do exist if it's an XOutOfY condition
+            for v in c.validators.iter().chain(c.backup_validators.iter()) {
                 if get_user_from_user_store(&v.principal_id).is_err() {
                     return Err(SmartVaultErr::UserDoesNotExist(v.principal_id.to_string()));
                 }
             }
-            // Check that the quorum is less than or equal to the number of validators
-            if c.quorum > c.validators.len() as u64 {
-                return Err(SmartVaultErr::InvalidQuorum(c.quorum.to_string(), c.validators.len().to_string()));
-            }
+            // Check that the quorum can be reached with the weights of the validators
+            c.validate()?;
+        }
+        // Check that the inactivity of last login conditions is given
+        if let UpdateCondition::LastLogin(c) = uc {
+            c.validate()?;
         }
-        // Check that the fixed date time is in the future
+        // Check that the fixed date time of new or changed conditions is in the future,
+        // a release window which has already opened can be kept
         if let UpdateCondition::FixedDateTime(c) = uc.clone() {
-            if c.datetime < time::get_current_time() {
-                return Err(SmartVaultErr::InvalidDateTime(c.datetime.to_string()));
+            if let Some(time_zone) = &c.time_zone {
+                if !time_zone.is_valid() {
+                    return Err(SmartVaultErr::InvalidTimeZone(format!("{:?}", time_zone)));
+                }
+            }
+            // errors report the instant in UTC
+            let datetime = TimeZone::to_utc(&c.time_zone, c.datetime);
+            let unchanged = old_policy
+                .conditions
+                .iter()
+                .any(|old| c.id.as_ref() == Some(&old.id()) && old.into_update_condition() == *uc);
+            if !unchanged && datetime < now {
+                return Err(SmartVaultErr::InvalidDateTime(datetime.to_string()));
+            }
+            // and that a release window ends after it starts
+            if let Some(end_datetime) = c.end_datetime {
+                if end_datetime <= c.datetime {
+                    return Err(SmartVaultErr::InvalidDateTime(
+                        TimeZone::to_utc(&c.time_zone, end_datetime).to_string(),
+                    ));
+                }
+            }
+        }
+        // Check that the owner has time to respond to a claim
+        if let UpdateCondition::BeneficiaryClaim(c) = uc.clone() {
+            if c.response_period_days == 0 {
+                return Err(SmartVaultErr::InvalidResponsePeriod(
+                    c.response_period_days.to_string(),
+                ));
+            }
+        }
+        // Check that the oracle can be called
+        if let UpdateCondition::Oracle(c) = uc.clone() {
+            if Principal::from_text(&c.canister_id).is_err() {
+                return Err(SmartVaultErr::InvalidOracleCondition(c.canister_id));
+            }
+            if !is_allowed_oracle(&c.canister_id) {
+                return Err(SmartVaultErr::OracleNotAllowed(c.canister_id));
+            }
+            if c.method.is_empty() {
+                return Err(SmartVaultErr::InvalidOracleCondition(
+                    "method must not be empty".to_string(),
+                ));
+            }
+        }
+        // Check that the owner has a dead man's switch to reference
+        if let UpdateCondition::DeadMansSwitch(_) = uc {
+            if get_user_from_user_store(&caller)?.dead_mans_switch.is_none() {
+                return Err(SmartVaultErr::InvalidDeadMansSwitch(
+                    "No dead man's switch configured".to_string(),
+                ));
+            }
+        }
+        // Check that the check-in schedule is valid
+        if let UpdateCondition::CheckIn(c) = uc.clone() {
+            if !c.schedule.is_valid() {
+                return Err(SmartVaultErr::InvalidCheckInSchedule(format!(
+                    "{:?}",
+                    c.schedule
+                )));
             }
-
         }
         match uc.id() {
             Some(existing_id) => {
@@ -327,6 +564,13 @@ pub async fn update_policy_impl(
                     .find(|c| c.id() == existing_id)
                     .unwrap()
                     .clone();
+                if existing_condition.get_condition_status()
+                    && !existing_condition
+                        .into_update_condition()
+                        .same_configuration(uc)
+                {
+                    edited_triggered_conditions.push(existing_id.clone());
+                }
                 let updated_condition: Condition = existing_condition.update_condition(uc.clone());
                 new_final_condition_set.push(updated_condition);
             }
@@ -339,7 +583,7 @@ pub async fn update_policy_impl(
     }
 
     // Create policy from UpdatePolicyArgs
-    let policy: Policy = Policy::from_update_policy_args(
+    let mut policy: Policy = Policy::from_update_policy_args(
         &upa.id,
         &old_policy.owner().to_string(),
         old_policy.conditions_status,
@@ -348,6 +592,35 @@ pub async fn update_policy_impl(
         upa.clone(),
     );
 
+    // keep the release state of the policy
+    policy.release_date = old_policy.release_date;
+    policy.released = old_policy.released;
+    policy.condition_resets = old_policy.condition_resets.clone();
+
+    // challenges are kept for the remaining beneficiaries
+    policy.beneficiary_challenges = old_policy.beneficiary_challenges.clone();
+    if let Some(beneficiary_challenges) = policy.beneficiary_challenges.as_mut() {
+        beneficiary_challenges.retain(|beneficiary, _| upa.beneficiaries.contains(beneficiary));
+    }
+
+    // conditions edited by hand are no longer linked to their template
+    let old_conditions = old_policy.update_conditions();
+    if upa.conditions.len() == old_conditions.len()
+        && upa
+            .conditions
+            .iter()
+            .zip(old_conditions.iter())
+            .all(|(c, old)| c.same_configuration(old))
+        && upa.conditions_logical_operator == *old_policy.conditions_logical_operator()
+        && upa.conditions_expression == old_policy.update_conditions_expression()
+    {
+        policy.condition_template = old_policy.condition_template.clone();
+    }
+
+    for condition_id in edited_triggered_conditions.iter() {
+        policy.reset_condition(condition_id, ConditionResetReason::ConditionEdited)?;
+    }
+
     // update policy in policy store
     let updated_policy = update_policy_in_policy_store(policy.clone())?;
 
@@ -358,7 +631,16 @@ pub async fn update_policy_impl(
         Ok(())
     })?;
 
-    // Update policy registry for validators (reverse index) if there is a XOutOfYCondition
+    // Update policy registry for validators (reverse index) if there is a XOutOfYCondition,
+    // validators which have been removed from the policy lose access to it
+    for condition in old_policy.conditions().iter() {
+        if let Condition::XOutOfY(xoutofy) = condition {
+            POLICY_REGISTRIES.with(|x| {
+                let mut policy_registries = x.borrow_mut();
+                policy_registries.remove_policy_from_validators(&xoutofy.validators, policy.id());
+            });
+        }
+    }
     for condition in policy.conditions().iter() {
         if let Condition::XOutOfY(xoutofy) = condition {
             POLICY_REGISTRIES.with(|x| {
@@ -412,6 +694,49 @@ pub fn delete_policy_impl(policy_id: String, caller: PrincipalID) -> Result<(),
     Ok(())
 }
 
+/// Checks that all policies referenced by dependency conditions are owned by the caller
+/// and that following the dependencies never leads back to the policy itself.
+fn check_policy_dependencies(
+    policy_id: &PolicyID,
+    conditions: &[UpdateCondition],
+    caller: &PrincipalID,
+) -> Result<(), SmartVaultErr> {
+    let mut to_visit: Vec<PolicyID> = conditions
+        .iter()
+        .filter_map(|c| match c {
+            UpdateCondition::PolicyDependency(d) => Some(d.policy_id.clone()),
+            _ => None,
+        })
+        .collect();
+
+    for dependency_id in to_visit.iter() {
+        let dependency = get_policy_from_policy_store(dependency_id)?;
+        if dependency.owner() != caller {
+            return Err(SmartVaultErr::CallerNotPolicyOwner(
+                dependency_id.to_string(),
+            ));
+        }
+    }
+
+    let mut visited: HashSet<PolicyID> = HashSet::new();
+    while let Some(dependency_id) = to_visit.pop() {
+        if &dependency_id == policy_id {
+            return Err(SmartVaultErr::PolicyDependencyCycle(policy_id.to_string()));
+        }
+        if !visited.insert(dependency_id.clone()) {
+            continue;
+        }
+        if let Ok(dependency) = get_policy_from_policy_store(&dependency_id) {
+            for condition in dependency.conditions() {
+                if let Condition::PolicyDependency(d) = condition {
+                    to_visit.push(d.policy_id.clone());
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
 /// A validator confirming the status of the policy
 /// ConfirmXOutOfYConditionArgs contains policy_id (PolicyID) and status (bool)
 pub fn confirm_x_out_of_y_condition_impl(
@@ -429,16 +754,23 @@ pub fn confirm_x_out_of_y_condition_impl(
 
     let mut policy_needs_update = false;
     let mut condition_needs_evaluation = false;
+    let now = time::get_current_time();
+    let vote_evidence = args.vote_evidence(now)?;
     for condition in &mut policy.conditions {
         if condition.id() == args.condition_id {
             if let Condition::XOutOfY(x_out_of_y) = condition {
-                for v in &mut x_out_of_y.validators {
-                    if v.principal_id == validator {
-                        // there is a condition for which the validator is authorized validator
-                        v.status = Some(args.status);
-                        condition_needs_evaluation = true;
-                        policy_needs_update = true;
-                    }
+                if x_out_of_y.secret_ballot_days.is_some() {
+                    return Err(SmartVaultErr::InvalidVoteCommitment(
+                        "Votes of a secret ballot have to be committed and revealed".to_string(),
+                    ));
+                }
+                // drop outdated votes before counting the new one
+                x_out_of_y.expire_votes(now);
+                if x_out_of_y.register_vote(&validator, args.status, now) {
+                    // there is a condition for which the validator is authorized validator
+                    x_out_of_y.set_vote_evidence(&validator, vote_evidence.clone());
+                    condition_needs_evaluation = true;
+                    policy_needs_update = true;
                 }
             }
         }
@@ -458,6 +790,7 @@ pub fn confirm_x_out_of_y_condition_impl(
     }
 
     if condition_needs_evaluation {
+        policy.reset_rejected_condition(&args.condition_id)?;
         policy.validate_overall_conditions_status();
     }
 
@@ -469,6 +802,535 @@ pub fn confirm_x_out_of_y_condition_impl(
     Ok(())
 }
 
+/// A validator commits to a vote of a secret ballot without disclosing it
+pub fn commit_x_out_of_y_vote_impl(
+    args: CommitXOutOfYVoteArgs,
+    validator: PrincipalID,
+) -> Result<(), SmartVaultErr> {
+    let mut policy: Policy = get_policy_from_policy_store(&args.policy_id)?;
+    let now = time::get_current_time();
+
+    let condition = policy
+        .conditions
+        .iter_mut()
+        .find(|c| c.id() == args.condition_id)
+        .ok_or_else(|| SmartVaultErr::PolicyConditionDoesNotExist(args.condition_id.clone()))?;
+    let Condition::XOutOfY(x_out_of_y) = condition else {
+        return Err(SmartVaultErr::InvalidVoteCommitment(
+            "Condition has no secret ballot".to_string(),
+        ));
+    };
+    x_out_of_y.expire_votes(now);
+    x_out_of_y.commit_vote(&validator, args.commitment, now)?;
+
+    update_policy_in_policy_store(policy)?;
+    Ok(())
+}
+
+/// A validator reveals a committed vote of a secret ballot, which is counted from then on
+pub fn reveal_x_out_of_y_vote_impl(
+    args: RevealXOutOfYVoteArgs,
+    validator: PrincipalID,
+) -> Result<(), SmartVaultErr> {
+    let mut policy: Policy = get_policy_from_policy_store(&args.policy_id)?;
+    let now = time::get_current_time();
+    let vote_evidence = args.vote_evidence(now)?;
+
+    let condition = policy
+        .conditions
+        .iter_mut()
+        .find(|c| c.id() == args.condition_id)
+        .ok_or_else(|| SmartVaultErr::PolicyConditionDoesNotExist(args.condition_id.clone()))?;
+    let Condition::XOutOfY(x_out_of_y) = condition else {
+        return Err(SmartVaultErr::InvalidVoteCommitment(
+            "Condition has no secret ballot".to_string(),
+        ));
+    };
+    x_out_of_y.expire_votes(now);
+    x_out_of_y.reveal_vote(&validator, args.status, &args.salt, now)?;
+    x_out_of_y.set_vote_evidence(&validator, vote_evidence);
+
+    // check if the revealed votes reach the quorum
+    if condition.evaluate(None) {
+        condition.set_condition_status(true);
+    }
+    policy.reset_rejected_condition(&args.condition_id)?;
+    policy.validate_overall_conditions_status();
+
+    update_policy_in_policy_store(policy.clone())?;
+    evaluate_overall_conditions_status(policy.id())?;
+    Ok(())
+}
+
+/// Cancels a pending release of a policy during its cooling-off period.
+/// The veto can be cast by the owner or one of the vetoers of the policy and re-arms all conditions.
+pub fn veto_policy_release_impl(
+    policy_id: PolicyID,
+    caller: PrincipalID,
+) -> Result<Policy, SmartVaultErr> {
+    let mut policy: Policy = get_policy_from_policy_store(&policy_id)?;
+
+    let caller_is_owner = policy.owner() == &caller;
+    if !caller_is_owner && !policy.vetoers().contains(&caller) {
+        return Err(SmartVaultErr::CallerNotVetoer(policy_id));
+    }
+
+    if !policy.is_release_pending() {
+        return Err(SmartVaultErr::PolicyReleaseNotPending(policy_id));
+    }
+
+    policy.veto_release();
+    let policy = update_policy_in_policy_store(policy)?;
+    propagate_to_dependent_policies(&policy)?;
+
+    // otherwise the triggered switch would trigger the policy again right away
+    if references_dead_mans_switch(&policy, None) {
+        rearm_dead_mans_switch(policy.owner())?;
+    }
+
+    // A veto of the owner is a sign of life as well,
+    // otherwise last login conditions would trigger again right away
+    if caller_is_owner {
+        update_owner_login_date(&caller)?;
+    }
+
+    Ok(policy)
+}
+
+/// The owner simulates when a policy would be released, the policy itself is not changed
+pub fn forecast_policy_impl(
+    args: ForecastPolicyArgs,
+    caller: PrincipalID,
+) -> Result<PolicyForecast, SmartVaultErr> {
+    let policy: Policy = get_policy_from_policy_store(&args.policy_id)?;
+    if policy.owner() != &caller {
+        return Err(SmartVaultErr::CallerNotPolicyOwner(args.policy_id));
+    }
+    let owner: User = get_user_from_user_store(&caller)?;
+    Ok(forecast_policy(policy, &owner, &args))
+}
+
+/// The owner checks whether the quorums of the XOutOfY conditions can still be reached
+pub fn get_quorum_health_impl(
+    policy_id: PolicyID,
+    caller: PrincipalID,
+) -> Result<Vec<QuorumHealth>, SmartVaultErr> {
+    let policy: Policy = get_policy_from_policy_store(&policy_id)?;
+    if policy.owner() != &caller {
+        return Err(SmartVaultErr::CallerNotPolicyOwner(policy_id));
+    }
+    Ok(policy
+        .conditions()
+        .iter()
+        .filter_map(|c| match c {
+            Condition::XOutOfY(xooy) => Some(xooy.quorum_health(user_exists)),
+            _ => None,
+        })
+        .collect())
+}
+
+/// Removes a deleted user from the XOutOfY conditions of all policies and dead man's switches they validate.
+/// Backup validators take their place and conditions whose quorum got out of reach are flagged.
+pub fn remove_validator_from_policies(validator: &PrincipalID) -> Result<(), SmartVaultErr> {
+    let policies: Vec<Policy> = POLICY_REGISTRIES.with(|pr| {
+        let policy_registries = pr.borrow();
+        policy_registries.get_policy_ids_as_validator(validator)
+    })?;
+    let now = time::get_current_time();
+
+    for mut policy in policies {
+        let mut promoted: Vec<Validator> = vec![];
+        for condition in policy.conditions_mut().iter_mut() {
+            // the removal never approves a condition, only a new vote does
+            if let Condition::XOutOfY(xooy) = condition {
+                promoted.extend(xooy.substitute_validator(validator, user_exists));
+                xooy.update_quorum_health(user_exists, now);
+            }
+        }
+        update_policy_in_policy_store(policy.clone())?;
+
+        POLICY_REGISTRIES.with(|pr| {
+            let mut policy_registries = pr.borrow_mut();
+            policy_registries.add_policy_to_validators(&promoted, policy.id());
+        });
+    }
+
+    // validators of dead man's switches are not part of the registry
+    let owners: Vec<User> = USER_STORE.with(|us| us.borrow().users());
+    for owner in owners {
+        if let Some(mut dead_mans_switch) = owner.dead_mans_switch.clone() {
+            if dead_mans_switch.remove_validator(validator, user_exists, now) {
+                update_owner_dead_mans_switch(owner.id(), dead_mans_switch)?;
+            }
+        }
+    }
+
+    POLICY_REGISTRIES.with(|pr| {
+        let mut policy_registries = pr.borrow_mut();
+        policy_registries.remove_validator(validator);
+    });
+    Ok(())
+}
+
+fn user_exists(principal_id: &PrincipalID) -> bool {
+    get_user_from_user_store(principal_id).is_ok()
+}
+
+pub async fn create_condition_template_impl(
+    args: CreateConditionTemplateArgs,
+    caller: PrincipalID,
+) -> Result<ConditionTemplate, SmartVaultErr> {
+    let template = ConditionTemplate::new(UUID::new().await, &caller, args);
+    template.validate()?;
+
+    CONDITION_TEMPLATE_STORE.with(|cts| {
+        let mut condition_template_store = cts.borrow_mut();
+        condition_template_store.add_template(template)
+    })
+}
+
+/// Returns the built-in templates and the templates of the caller
+pub fn get_condition_template_list_impl(
+    caller: PrincipalID,
+) -> Result<Vec<ConditionTemplate>, SmartVaultErr> {
+    let mut templates = builtin_templates();
+    templates.extend(CONDITION_TEMPLATE_STORE.with(|cts| {
+        let condition_template_store = cts.borrow();
+        condition_template_store.get_templates_of_owner(&caller)
+    }));
+    Ok(templates)
+}
+
+/// Updates a template of the caller and applies it to all policies linked to it.
+/// Policies whose validation fails with the new conditions keep their conditions, are unlinked and reported.
+pub async fn update_condition_template_impl(
+    args: UpdateConditionTemplateArgs,
+    caller: PrincipalID,
+) -> Result<ConditionTemplateUpdate, SmartVaultErr> {
+    let mut template = get_condition_template(&args.id, &caller)?;
+    if template.is_builtin() {
+        return Err(SmartVaultErr::InvalidConditionTemplate(
+            "built-in templates cannot be changed".to_string(),
+        ));
+    }
+    template.update(args);
+    template.validate()?;
+
+    let template = CONDITION_TEMPLATE_STORE.with(|cts| {
+        let mut condition_template_store = cts.borrow_mut();
+        condition_template_store.update_template(template)
+    })?;
+
+    let mut unlinked_policies: Vec<PolicyID> = vec![];
+    for policy in get_linked_policies(&template.id, &caller)? {
+        let policy_id = policy.id().to_string();
+        if apply_condition_template(&template, policy, &caller, true)
+            .await
+            .is_err()
+        {
+            unlink_condition_template(&policy_id)?;
+            unlinked_policies.push(policy_id);
+        }
+    }
+    Ok(ConditionTemplateUpdate {
+        template,
+        unlinked_policies,
+    })
+}
+
+/// Deletes a template of the caller, linked policies keep their conditions
+pub fn delete_condition_template_impl(
+    template_id: ConditionTemplateID,
+    caller: PrincipalID,
+) -> Result<(), SmartVaultErr> {
+    let template = get_condition_template(&template_id, &caller)?;
+    if template.is_builtin() {
+        return Err(SmartVaultErr::InvalidConditionTemplate(
+            "built-in templates cannot be deleted".to_string(),
+        ));
+    }
+
+    CONDITION_TEMPLATE_STORE.with(|cts| {
+        let mut condition_template_store = cts.borrow_mut();
+        condition_template_store.delete_template(&template_id)
+    })?;
+
+    for policy in get_linked_policies(&template_id, &caller)? {
+        unlink_condition_template(policy.id())?;
+    }
+    Ok(())
+}
+
+/// Replaces the conditions of a policy with the conditions of a template.
+/// The policy is validated like an update through update_policy.
+pub async fn apply_condition_template_impl(
+    args: ApplyConditionTemplateArgs,
+    caller: PrincipalID,
+) -> Result<Policy, SmartVaultErr> {
+    let template = get_condition_template(&args.template_id, &caller)?;
+    let policy: Policy = get_policy_from_policy_store(&args.policy_id)?;
+    if policy.owner() != &caller {
+        return Err(SmartVaultErr::CallerNotPolicyOwner(args.policy_id));
+    }
+    apply_condition_template(&template, policy, &caller, args.linked).await
+}
+
+async fn apply_condition_template(
+    template: &ConditionTemplate,
+    policy: Policy,
+    caller: &PrincipalID,
+    linked: bool,
+) -> Result<Policy, SmartVaultErr> {
+    let conditions = template.instantiate(policy.conditions());
+    let mut upa = UpdatePolicyArgs::from(policy);
+    upa.conditions = conditions;
+    upa.conditions_logical_operator = template.conditions_logical_operator.clone();
+    upa.conditions_expression = template.conditions_expression.clone();
+
+    let mut policy = update_policy_impl(upa, caller.to_string()).await?;
+    policy.condition_template = linked.then(|| template.id.clone());
+    update_policy_in_policy_store(policy)
+}
+
+/// Built-in templates and the templates of the caller can be used
+fn get_condition_template(
+    template_id: &ConditionTemplateID,
+    caller: &PrincipalID,
+) -> Result<ConditionTemplate, SmartVaultErr> {
+    if let Some(template) = get_builtin_template(template_id) {
+        return Ok(template);
+    }
+    let template = CONDITION_TEMPLATE_STORE.with(|cts| {
+        let condition_template_store = cts.borrow();
+        condition_template_store.get(template_id)
+    })?;
+    if template.owner.as_ref() != Some(caller) {
+        return Err(SmartVaultErr::ConditionTemplateDoesNotExist(
+            template_id.to_string(),
+        ));
+    }
+    Ok(template)
+}
+
+fn get_linked_policies(
+    template_id: &ConditionTemplateID,
+    owner: &PrincipalID,
+) -> Result<Vec<Policy>, SmartVaultErr> {
+    let user: User = get_user_from_user_store(owner)?;
+    Ok(get_policies_from_policy_store(user.policies())?
+        .into_iter()
+        .filter(|p| p.condition_template.as_ref() == Some(template_id))
+        .collect())
+}
+
+fn unlink_condition_template(policy_id: &PolicyID) -> Result<(), SmartVaultErr> {
+    let mut policy = get_policy_from_policy_store(policy_id)?;
+    policy.condition_template = None;
+    update_policy_in_policy_store(policy)?;
+    Ok(())
+}
+
+/// The owner re-arms a single triggered condition, e.g. after a validator voted by mistake
+pub fn reset_policy_condition_impl(
+    policy_id: PolicyID,
+    condition_id: ConditionID,
+    caller: PrincipalID,
+) -> Result<Policy, SmartVaultErr> {
+    let mut policy: Policy = get_policy_from_policy_store(&policy_id)?;
+    if policy.owner() != &caller {
+        return Err(SmartVaultErr::CallerNotPolicyOwner(policy_id));
+    }
+
+    policy.reset_condition(&condition_id, ConditionResetReason::OwnerReset)?;
+    let policy = update_policy_in_policy_store(policy)?;
+    propagate_to_dependent_policies(&policy)?;
+    if references_dead_mans_switch(&policy, Some(&condition_id)) {
+        rearm_dead_mans_switch(&caller)?;
+    }
+
+    // otherwise last login conditions would trigger again right away
+    update_owner_login_date(&caller)?;
+    Ok(policy)
+}
+
+/// The owner re-arms all conditions of a policy
+pub fn reset_policy_conditions_impl(
+    policy_id: PolicyID,
+    caller: PrincipalID,
+) -> Result<Policy, SmartVaultErr> {
+    let mut policy: Policy = get_policy_from_policy_store(&policy_id)?;
+    if policy.owner() != &caller {
+        return Err(SmartVaultErr::CallerNotPolicyOwner(policy_id));
+    }
+
+    policy.reset_conditions(ConditionResetReason::OwnerReset);
+    let policy = update_policy_in_policy_store(policy)?;
+    propagate_to_dependent_policies(&policy)?;
+    if references_dead_mans_switch(&policy, None) {
+        rearm_dead_mans_switch(&caller)?;
+    }
+    update_owner_login_date(&caller)?;
+    Ok(policy)
+}
+
+/// Whether the policy, or the given condition of it, references the dead man's switch of the owner
+fn references_dead_mans_switch(policy: &Policy, condition_id: Option<&ConditionID>) -> bool {
+    policy.conditions().iter().any(|c| {
+        matches!(c, Condition::DeadMansSwitch(_))
+            && (condition_id.is_none() || condition_id == Some(&c.id()))
+    })
+}
+
+fn update_owner_dead_mans_switch(
+    owner: &PrincipalID,
+    dead_mans_switch: DeadMansSwitch,
+) -> Result<User, SmartVaultErr> {
+    USER_STORE.with(|us| {
+        let mut user_store = us.borrow_mut();
+        user_store.update_dead_mans_switch(owner, Some(dead_mans_switch))
+    })
+}
+
+/// Registers an action of the owner on its policy as sign of life
+fn update_owner_login_date(owner: &PrincipalID) -> Result<User, SmartVaultErr> {
+    USER_STORE.with(|us| {
+        let mut user_store = us.borrow_mut();
+        user_store.update_user_login_date(owner)
+    })
+}
+
+/// A beneficiary claiming that the owner has passed away.
+/// The owner can reject the claim by signing in before the response deadline.
+pub fn file_claim_impl(args: FileClaimArgs, beneficiary: PrincipalID) -> Result<(), SmartVaultErr> {
+    // only beneficiaries of the policy may file a claim
+    let beneficiary_policies =
+        POLICY_REGISTRIES.with(|pr| -> Result<Vec<PolicyListEntry>, SmartVaultErr> {
+            let policy_registries = pr.borrow();
+            policy_registries.get_policy_ids_as_beneficiary(&beneficiary)
+        })?;
+    if !beneficiary_policies.iter().any(|p| p.id == args.policy_id) {
+        return Err(SmartVaultErr::CallerNotBeneficiary(args.policy_id));
+    }
+
+    let mut policy: Policy = get_policy_from_policy_store(&args.policy_id)?;
+    let now: u64 = time::get_current_time();
+    match policy
+        .conditions_mut()
+        .iter_mut()
+        .find(|c| c.id() == args.condition_id)
+    {
+        Some(Condition::BeneficiaryClaim(claim_condition)) => {
+            claim_condition.file_claim(&beneficiary, now)?;
+        }
+        _ => {
+            return Err(SmartVaultErr::PolicyConditionDoesNotExist(
+                args.condition_id,
+            ))
+        }
+    }
+
+    update_policy_in_policy_store(policy)?;
+    Ok(())
+}
+
+/// The owner setting the challenge a beneficiary has to answer before its secrets are revealed
+pub async fn set_beneficiary_challenge_impl(
+    args: SetBeneficiaryChallengeArgs,
+    caller: PrincipalID,
+) -> Result<Policy, SmartVaultErr> {
+    let salt = hex::encode(get_new_random().await);
+    let mut policy: Policy = get_policy_from_policy_store(&args.policy_id)?;
+    if policy.owner() != &caller {
+        return Err(SmartVaultErr::CallerNotPolicyOwner(args.policy_id));
+    }
+    if !policy.beneficiaries().contains(&args.beneficiary) {
+        return Err(SmartVaultErr::InvalidBeneficiaryChallenge(
+            args.beneficiary.to_string(),
+        ));
+    }
+
+    let challenge = BeneficiaryChallenge::new(args.question, &args.answer, salt)?;
+    policy
+        .beneficiary_challenges
+        .get_or_insert_with(BTreeMap::new)
+        .insert(args.beneficiary, challenge);
+    update_policy_in_policy_store(policy)
+}
+
+pub fn remove_beneficiary_challenge_impl(
+    policy_id: PolicyID,
+    beneficiary: PrincipalID,
+    caller: PrincipalID,
+) -> Result<Policy, SmartVaultErr> {
+    let mut policy: Policy = get_policy_from_policy_store(&policy_id)?;
+    if policy.owner() != &caller {
+        return Err(SmartVaultErr::CallerNotPolicyOwner(policy_id));
+    }
+    if policy
+        .beneficiary_challenges
+        .as_mut()
+        .and_then(|beneficiary_challenges| beneficiary_challenges.remove(&beneficiary))
+        .is_none()
+    {
+        return Err(SmartVaultErr::InvalidBeneficiaryChallenge(beneficiary));
+    }
+    update_policy_in_policy_store(policy)
+}
+
+/// A beneficiary answering its challenge of a released policy.
+/// Failed attempts are recorded, so the policy is stored in any case.
+pub async fn answer_beneficiary_challenge_impl(
+    args: AnswerBeneficiaryChallengeArgs,
+    caller: PrincipalID,
+) -> Result<ChallengeToken, SmartVaultErr> {
+    let token = hex::encode(get_new_random().await);
+    let mut policy: Policy = get_policy_from_policy_store(&args.policy_id)?;
+    if !policy.beneficiaries().contains(&caller) {
+        return Err(SmartVaultErr::CallerNotBeneficiary(args.policy_id));
+    }
+    policy.check_beneficiary_access()?;
+
+    let result = match policy
+        .beneficiary_challenges
+        .as_mut()
+        .and_then(|beneficiary_challenges| beneficiary_challenges.get_mut(&caller))
+    {
+        Some(challenge) => challenge.answer(&args.answer, token, time::get_current_time()),
+        None => return Err(SmartVaultErr::InvalidBeneficiaryChallenge(caller)),
+    };
+    update_policy_in_policy_store(policy)?;
+    result
+}
+
+/// The owner checking in (proof of life)
+/// Moves the deadline of all check-in conditions of the owner's policies and dead man's switch
+/// which have not yet been triggered
+pub fn check_in_impl(caller: PrincipalID) -> Result<(), SmartVaultErr> {
+    let user = get_user_from_user_store(&caller)?;
+    let now: u64 = time::get_current_time();
+
+    if let Some(mut dead_mans_switch) = user.dead_mans_switch.clone() {
+        if dead_mans_switch.triggered_at.is_none() && dead_mans_switch.check_in(now) {
+            update_owner_dead_mans_switch(&caller, dead_mans_switch)?;
+        }
+    }
+
+    for policy_id in user.policies() {
+        let mut policy = get_policy_from_policy_store(&policy_id)?;
+        let mut policy_needs_update = false;
+        for condition in policy.conditions_mut().iter_mut() {
+            if condition.check_in(now) {
+                policy_needs_update = true;
+            }
+        }
+        if policy_needs_update {
+            update_policy_in_policy_store(policy)?;
+        }
+    }
+
+    Ok(())
+}
+
 /**
  * Helper CRUD functions
  */
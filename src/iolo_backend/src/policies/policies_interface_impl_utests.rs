@@ -1,12 +1,28 @@
 #[cfg(test)]
 mod tests {
-    use candid::Principal;
+    use candid::{CandidType, Encode, Principal};
+    use ic_stable_structures::Storable;
     use rand::Rng;
-    use std::collections::HashSet;
+    use serde::Serialize;
+    use std::borrow::Cow;
+    use std::collections::{BTreeMap, HashSet};
 
+    use crate::policies::beneficiary_challenges::{
+        AnswerBeneficiaryChallengeArgs, SetBeneficiaryChallengeArgs,
+    };
+    use crate::policies::condition_templates::{
+        ApplyConditionTemplateArgs, CreateConditionTemplateArgs, UpdateConditionTemplateArgs,
+    };
     use crate::policies::conditions::{
-        UpdateCondition, UpdateLastLoginTimeCondition, UpdateXOutOfYCondition,
+        vote_commitment, ActivityType, CommitXOutOfYVoteArgs, Condition,
+        ConfirmXOutOfYConditionArgs, Duration, Evidence, EvidenceVisibility, FileClaimArgs,
+        QuorumType, RevealXOutOfYVoteArgs, UpdateBeneficiaryClaimCondition, UpdateCondition,
+        UpdateDeadMansSwitchCondition, UpdateFixedDateTimeCondition, UpdateLastLoginTimeCondition,
+        UpdateOracleCondition, UpdatePolicyDependencyCondition, UpdateXOutOfYCondition,
+        ValidatorRole, VoteClearanceReason, VoteOutcome,
     };
+    use crate::policies::forecast::{ForecastPolicyArgs, SimulatedVote};
+    use crate::policies::oracles::{add_oracle_impl, get_oracle_list_impl, remove_oracle_impl};
     use crate::policies::policy::UpdatePolicyArgs;
     use crate::secrets::secret::Secret;
     use crate::users::user::User;
@@ -14,19 +30,48 @@ mod tests {
         common::error::SmartVaultErr,
         policies::{
             conditions::Validator,
+            conditions_manager::{
+                check_time_based_conditions, evaluate_overall_conditions_status, migrate_conditions,
+            },
             policies_interface_impl::{
-                create_policy_impl, get_policy_as_beneficiary_impl, get_policy_as_owner_impl,
+                answer_beneficiary_challenge_impl, apply_condition_template_impl,
+                commit_x_out_of_y_vote_impl, confirm_x_out_of_y_condition_impl,
+                create_condition_template_impl, create_policy_impl, delete_condition_template_impl,
+                file_claim_impl, forecast_policy_impl, get_condition_template_list_impl,
+                get_policy_as_beneficiary_impl, get_policy_as_owner_impl,
+                get_policy_as_validator_impl, get_policy_from_policy_store,
                 get_policy_list_as_beneficiary_impl, get_policy_list_as_owner_impl,
-                get_policy_list_as_validator_impl, update_policy_impl,
+                get_policy_list_as_validator_impl, get_policy_response_for_owner,
+                get_quorum_health_impl, reset_policy_condition_impl, reset_policy_conditions_impl,
+                reveal_x_out_of_y_vote_impl, set_beneficiary_challenge_impl,
+                update_condition_template_impl, update_policy_impl, update_policy_in_policy_store,
+                veto_policy_release_impl,
+            },
+            policy::{
+                ConditionResetReason, CreatePolicyArgs, LogicalOperator, Policy,
+                PolicyWithSecretListEntries, ReleaseStage,
+            },
+        },
+        secrets::{
+            secret::CreateSecretArgs,
+            secrets_interface_impl::{
+                create_secret_impl, get_encrypted_symmetric_key_as_beneficiary_impl,
+                get_secret_as_beneficiary_impl,
             },
-            policy::{CreatePolicyArgs, Policy, PolicyWithSecretListEntries},
         },
-        secrets::{secret::CreateSecretArgs, secrets_interface_impl::create_secret_impl},
         smart_vaults::smart_vault::POLICY_STORE,
         users::{
+            activity_tracker::track_activity,
+            dead_mans_switch::{ConfirmDeadMansSwitchArgs, UpdateDeadMansSwitchArgs},
             user::{AddOrUpdateUserArgs, KeyBox},
-            users_interface_impl::create_user_impl,
+            users_interface_impl::{
+                confirm_dead_mans_switch_impl, create_user_impl, delete_dead_mans_switch_impl,
+                delete_user_impl, end_vacation_impl, get_current_user_impl,
+                get_dead_mans_switch_list_as_validator_impl, start_vacation_impl,
+                update_dead_mans_switch_impl, update_user_login_date_impl,
+            },
         },
+        utils::time,
     };
 
     #[tokio::test]
@@ -117,7 +162,7 @@ mod tests {
             .map(UpdateCondition::from)
             .map(|c| match c {
                 UpdateCondition::LastLogin(mut llc) => {
-                    llc.number_of_days_since_last_login = 99;
+                    llc.inactivity = Some(Duration::days(99));
                     return UpdateCondition::LastLogin(llc);
                 }
                 UpdateCondition::XOutOfY(mut xooy) => {
@@ -125,6 +170,11 @@ mod tests {
                     return UpdateCondition::XOutOfY(xooy);
                 }
                 UpdateCondition::FixedDateTime(_) => c,
+                UpdateCondition::CheckIn(_) => c,
+                UpdateCondition::PolicyDependency(_) => c,
+                UpdateCondition::BeneficiaryClaim(_) => c,
+                UpdateCondition::Oracle(_) => c,
+                UpdateCondition::DeadMansSwitch(_) => c,
             })
             .collect();
         let updated_policy = update_policy_conditions(
@@ -146,6 +196,1942 @@ mod tests {
         dbg!(&updated_policy);
     }
 
+    #[tokio::test]
+    async fn itest_policy_cooling_off_and_veto() {
+        let principal = create_principal();
+        let beneficiary = create_principal();
+        let validator = create_principal();
+        create_test_users(&principal).await;
+        create_test_users(&beneficiary).await;
+        create_test_users(&validator).await;
+
+        // policy with a single x out of y condition and a cooling-off period of 30 days
+        let policy: Policy = create_and_add_policy(principal.to_string()).await;
+        let upa: UpdatePolicyArgs = UpdatePolicyArgs {
+            id: policy.id().to_string(),
+            name: policy.name().clone(),
+            beneficiaries: [beneficiary.to_string()].iter().cloned().collect(),
+            secrets: HashSet::new(),
+            key_box: KeyBox::new(),
+            conditions_logical_operator: None,
+            conditions: vec![create_new_x_oo_y_condition(validator.to_string())],
+            conditions_expression: None,
+            cooling_off_period_days: Some(30),
+            vetoers: None,
+            not_before: None,
+            expires_at: None,
+            release_stages: None,
+            beneficiary_secrets: None,
+        };
+        let policy = update_policy_impl(upa, principal.to_string())
+            .await
+            .unwrap();
+        let condition_id = policy.conditions()[0].id();
+
+        // validator confirms: conditions are met, but the release is pending
+        let args = ConfirmXOutOfYConditionArgs {
+            policy_id: policy.id().to_string(),
+            condition_id: condition_id.clone(),
+            status: true,
+            reason: None,
+            evidence: None,
+            evidence_visibility: None,
+        };
+        confirm_x_out_of_y_condition_impl(args, validator.to_string()).unwrap();
+        let policy = get_policy_from_policy_store(policy.id()).unwrap();
+        assert!(policy.conditions_status);
+        assert!(policy.is_release_pending());
+        assert!(!policy.is_released());
+        assert!(policy.release_date.is_some());
+        let policy_response =
+            get_policy_as_beneficiary_impl(policy.id().clone(), beneficiary.to_string());
+        assert!(policy_response.is_err_and(|e| matches!(e, SmartVaultErr::InvalidPolicyCondition)));
+
+        // only the owner and vetoers can veto
+        let veto = veto_policy_release_impl(policy.id().clone(), beneficiary.to_string());
+        assert!(veto.is_err_and(|e| matches!(e, SmartVaultErr::CallerNotVetoer(_))));
+
+        // the owner vetoes: conditions are re-armed
+        let policy = veto_policy_release_impl(policy.id().clone(), principal.to_string()).unwrap();
+        assert!(!policy.conditions_status);
+        assert!(policy.release_date.is_none());
+        match &policy.conditions()[0] {
+            Condition::XOutOfY(xooy) => {
+                assert!(!xooy.condition_status);
+                assert!(xooy.validators.iter().all(|v| v.status.is_none()));
+            }
+            _ => panic!("x out of y condition expected"),
+        }
+
+        // nothing left to veto
+        let veto = veto_policy_release_impl(policy.id().clone(), principal.to_string());
+        assert!(veto.is_err_and(|e| matches!(e, SmartVaultErr::PolicyReleaseNotPending(_))));
+    }
+
+    #[tokio::test]
+    async fn itest_policy_dependencies() {
+        let principal = create_principal();
+        let other_principal = create_principal();
+        let validator = create_principal();
+        create_test_users(&principal).await;
+        create_test_users(&other_principal).await;
+        create_test_users(&validator).await;
+
+        let conditions_args =
+            |policy: &Policy, conditions: Vec<UpdateCondition>| UpdatePolicyArgs {
+                id: policy.id().to_string(),
+                name: policy.name().clone(),
+                beneficiaries: HashSet::new(),
+                secrets: HashSet::new(),
+                key_box: KeyBox::new(),
+                conditions_logical_operator: None,
+                conditions,
+                conditions_expression: None,
+                cooling_off_period_days: None,
+                vetoers: None,
+                not_before: None,
+                expires_at: None,
+                release_stages: None,
+                beneficiary_secrets: None,
+            };
+        let dependency = |policy: &Policy, delay_days: Option<u64>| {
+            UpdateCondition::PolicyDependency(UpdatePolicyDependencyCondition {
+                id: None,
+                policy_id: policy.id().to_string(),
+                delay_days,
+            })
+        };
+
+        // executor policy a, family policy b right after a, family policy c 10 days after a
+        let policy_a = create_and_add_policy(principal.to_string()).await;
+        let policy_a = update_policy_impl(
+            conditions_args(
+                &policy_a,
+                vec![create_new_x_oo_y_condition(validator.to_string())],
+            ),
+            principal.to_string(),
+        )
+        .await
+        .unwrap();
+        let policy_b = create_and_add_policy(principal.to_string()).await;
+        let upa = conditions_args(&policy_b, vec![dependency(&policy_a, None)]);
+        update_policy_impl(upa, principal.to_string())
+            .await
+            .unwrap();
+        let policy_c = create_and_add_policy(principal.to_string()).await;
+        let upa = conditions_args(&policy_c, vec![dependency(&policy_a, Some(10))]);
+        update_policy_impl(upa, principal.to_string())
+            .await
+            .unwrap();
+
+        // a must not depend on b
+        let mut conditions: Vec<UpdateCondition> = policy_a
+            .conditions()
+            .iter()
+            .map(|c| c.into_update_condition())
+            .collect();
+        conditions.push(dependency(&policy_b, None));
+        let upa = conditions_args(&policy_a, conditions);
+        let result = update_policy_impl(upa, principal.to_string()).await;
+        assert!(result.is_err_and(|e| matches!(e, SmartVaultErr::PolicyDependencyCycle(_))));
+
+        // policies of other users cannot be referenced
+        let policy_d = create_and_add_policy(other_principal.to_string()).await;
+        let upa = conditions_args(&policy_d, vec![dependency(&policy_a, None)]);
+        let result = update_policy_impl(upa, other_principal.to_string()).await;
+        assert!(result.is_err_and(|e| matches!(e, SmartVaultErr::CallerNotPolicyOwner(_))));
+
+        // the validator confirms a: b follows immediately, c waits for the delay
+        let args = ConfirmXOutOfYConditionArgs {
+            policy_id: policy_a.id().to_string(),
+            condition_id: policy_a.conditions()[0].id(),
+            status: true,
+            reason: None,
+            evidence: None,
+            evidence_visibility: None,
+        };
+        confirm_x_out_of_y_condition_impl(args.clone(), validator.to_string()).unwrap();
+        assert!(
+            get_policy_from_policy_store(policy_a.id())
+                .unwrap()
+                .conditions_status
+        );
+        assert!(
+            get_policy_from_policy_store(policy_b.id())
+                .unwrap()
+                .conditions_status
+        );
+        let dependency_met_at = |policy: &Policy| match &get_policy_from_policy_store(policy.id())
+            .unwrap()
+            .conditions()[0]
+        {
+            Condition::PolicyDependency(d) => d.dependency_met_at,
+            _ => panic!("policy dependency condition expected"),
+        };
+        let policy_c = get_policy_from_policy_store(policy_c.id()).unwrap();
+        assert!(!policy_c.conditions_status);
+        assert!(dependency_met_at(&policy_c).is_some());
+        assert!(!policy_c.conditions()[0].get_condition_status());
+
+        // resetting a re-arms c, the release of b is final
+        reset_policy_conditions_impl(policy_a.id().to_string(), principal.to_string()).unwrap();
+        assert!(dependency_met_at(&policy_c).is_none());
+        assert!(get_policy_from_policy_store(policy_b.id())
+            .unwrap()
+            .is_released());
+
+        // with a cooling-off period c waits for the release of a, which is vetoed
+        let policy_a = get_policy_from_policy_store(policy_a.id()).unwrap();
+        let mut upa = UpdatePolicyArgs::from(policy_a.clone());
+        upa.cooling_off_period_days = Some(7);
+        update_policy_impl(upa, principal.to_string())
+            .await
+            .unwrap();
+        confirm_x_out_of_y_condition_impl(args, validator.to_string()).unwrap();
+        assert!(get_policy_from_policy_store(policy_a.id())
+            .unwrap()
+            .is_release_pending());
+        assert!(dependency_met_at(&policy_c).is_none());
+        veto_policy_release_impl(policy_a.id().to_string(), principal.to_string()).unwrap();
+        assert!(dependency_met_at(&policy_c).is_none());
+    }
+
+    #[tokio::test]
+    async fn itest_policy_not_before_and_expiry() {
+        let principal = create_principal();
+        let beneficiary = create_principal();
+        let validator = create_principal();
+        create_test_users(&principal).await;
+        create_test_users(&beneficiary).await;
+        create_test_users(&validator).await;
+
+        let update_args =
+            |policy: &Policy, not_before: Option<u64>, expires_at: Option<u64>| UpdatePolicyArgs {
+                id: policy.id().to_string(),
+                name: policy.name().clone(),
+                beneficiaries: [beneficiary.to_string()].iter().cloned().collect(),
+                secrets: HashSet::new(),
+                key_box: KeyBox::new(),
+                conditions_logical_operator: None,
+                conditions: vec![create_new_x_oo_y_condition(validator.to_string())],
+                conditions_expression: None,
+                cooling_off_period_days: None,
+                vetoers: None,
+                not_before,
+                expires_at,
+                release_stages: None,
+                beneficiary_secrets: None,
+            };
+        let confirm = |policy: &Policy| {
+            let args = ConfirmXOutOfYConditionArgs {
+                policy_id: policy.id().to_string(),
+                condition_id: policy.conditions()[0].id(),
+                status: true,
+                reason: None,
+                evidence: None,
+                evidence_visibility: None,
+            };
+            confirm_x_out_of_y_condition_impl(args, validator.to_string()).unwrap();
+            get_policy_from_policy_store(policy.id()).unwrap()
+        };
+        let now = time::get_current_time();
+
+        // the policy must not expire before it can be released
+        let policy = create_and_add_policy(principal.to_string()).await;
+        let upa = update_args(&policy, Some(now + 1000), Some(now));
+        let result = update_policy_impl(upa, principal.to_string()).await;
+        assert!(result.is_err_and(|e| matches!(e, SmartVaultErr::InvalidDateTime(_))));
+
+        // conditions are met, but the release waits for the not before date
+        let not_before = now + 365 * time::NANOS_PER_DAY;
+        let upa = update_args(&policy, Some(not_before), None);
+        let policy = update_policy_impl(upa, principal.to_string())
+            .await
+            .unwrap();
+        let policy = confirm(&policy);
+        assert!(policy.conditions_status);
+        assert!(!policy.is_released());
+        assert_eq!(policy.release_date, Some(not_before));
+        let result = get_policy_as_beneficiary_impl(policy.id().clone(), beneficiary.to_string());
+        assert!(result.is_err_and(|e| matches!(e, SmartVaultErr::InvalidPolicyCondition)));
+
+        // dates in the past are rejected
+        let policy = create_and_add_policy(principal.to_string()).await;
+        let upa = update_args(&policy, Some(now), None);
+        let result = update_policy_impl(upa, principal.to_string()).await;
+        assert!(result.is_err_and(|e| matches!(e, SmartVaultErr::InvalidDateTime(_))));
+        let upa = update_args(&policy, None, Some(now));
+        let result = update_policy_impl(upa, principal.to_string()).await;
+        assert!(result.is_err_and(|e| matches!(e, SmartVaultErr::InvalidDateTime(_))));
+
+        // an expired policy is void
+        let upa = update_args(&policy, None, Some(now + time::NANOS_PER_DAY));
+        let mut policy = update_policy_impl(upa, principal.to_string())
+            .await
+            .unwrap();
+        policy.expires_at = Some(now);
+        update_policy_in_policy_store(policy.clone()).unwrap();
+        let policy = confirm(&policy);
+        assert!(policy.conditions_status);
+        assert!(!policy.is_released());
+        let result = get_policy_as_beneficiary_impl(policy.id().clone(), beneficiary.to_string());
+        assert!(result.is_err_and(|e| matches!(e, SmartVaultErr::PolicyExpired(_))));
+
+        // a release window which has already opened can be kept on update
+        let window = |policy: &Policy, cooling_off_period_days: Option<u64>| UpdatePolicyArgs {
+            conditions: vec![UpdateCondition::FixedDateTime(
+                UpdateFixedDateTimeCondition {
+                    id: None,
+                    datetime: now + time::NANOS_PER_DAY,
+                    end_datetime: Some(now + 2 * time::NANOS_PER_DAY),
+                    time_zone: None,
+                },
+            )],
+            cooling_off_period_days,
+            ..update_args(policy, None, None)
+        };
+        let open_window = |mut policy: Policy| {
+            if let Condition::FixedDateTime(c) = &mut policy.conditions_mut()[0] {
+                c.datetime = now - time::NANOS_PER_DAY;
+            }
+            update_policy_in_policy_store(policy.clone()).unwrap();
+            policy
+        };
+        let policy = create_and_add_policy(principal.to_string()).await;
+        let released = update_policy_impl(window(&policy, None), principal.to_string())
+            .await
+            .unwrap();
+        let released = open_window(released);
+        let mut upa = UpdatePolicyArgs::from(released.clone());
+        let mut released = update_policy_impl(upa.clone(), principal.to_string())
+            .await
+            .unwrap();
+        if let UpdateCondition::FixedDateTime(c) = &mut upa.conditions[0] {
+            c.datetime = now - time::NANOS_PER_DAY + 1;
+        }
+        let result = update_policy_impl(upa, principal.to_string()).await;
+        assert!(result.is_err_and(|e| matches!(e, SmartVaultErr::InvalidDateTime(_))));
+
+        // closing the window withdraws a pending release, but not a policy which has been released
+        let policy = create_and_add_policy(principal.to_string()).await;
+        let pending = update_policy_impl(window(&policy, Some(30)), principal.to_string())
+            .await
+            .unwrap();
+        let mut pending = open_window(pending);
+        for policy in [&mut released, &mut pending] {
+            if let Condition::FixedDateTime(c) = &mut policy.conditions_mut()[0] {
+                c.end_datetime = Some(now);
+            }
+            policy.conditions_mut()[0].set_condition_status(true);
+            policy.set_conditions_status(true);
+            update_policy_in_policy_store(policy.clone()).unwrap();
+        }
+        assert!(released.is_released());
+        assert!(pending.is_release_pending());
+        check_time_based_conditions();
+        assert!(get_policy_from_policy_store(released.id())
+            .unwrap()
+            .is_released());
+        assert!(
+            !get_policy_from_policy_store(pending.id())
+                .unwrap()
+                .conditions_status
+        );
+    }
+
+    #[tokio::test]
+    async fn itest_file_claim() {
+        let principal = create_principal();
+        let beneficiary = create_principal();
+        let stranger = create_principal();
+        create_test_users(&principal).await;
+        create_test_users(&beneficiary).await;
+        create_test_users(&stranger).await;
+
+        let policy: Policy = create_and_add_policy(principal.to_string()).await;
+        let upa: UpdatePolicyArgs = UpdatePolicyArgs {
+            id: policy.id().to_string(),
+            name: policy.name().clone(),
+            beneficiaries: [beneficiary.to_string()].iter().cloned().collect(),
+            secrets: HashSet::new(),
+            key_box: KeyBox::new(),
+            conditions_logical_operator: None,
+            conditions: vec![UpdateCondition::BeneficiaryClaim(
+                UpdateBeneficiaryClaimCondition {
+                    id: None,
+                    response_period_days: 14,
+                },
+            )],
+            conditions_expression: None,
+            cooling_off_period_days: None,
+            vetoers: None,
+            not_before: None,
+            expires_at: None,
+            release_stages: None,
+            beneficiary_secrets: None,
+        };
+        let policy = update_policy_impl(upa, principal.to_string())
+            .await
+            .unwrap();
+        let args = FileClaimArgs {
+            policy_id: policy.id().to_string(),
+            condition_id: policy.conditions()[0].id(),
+        };
+
+        // only beneficiaries can file claims
+        let result = file_claim_impl(args.clone(), stranger.to_string());
+        assert!(result.is_err_and(|e| matches!(e, SmartVaultErr::CallerNotBeneficiary(_))));
+
+        file_claim_impl(args.clone(), beneficiary.to_string()).unwrap();
+        let policy = get_policy_from_policy_store(policy.id()).unwrap();
+        match &policy.conditions()[0] {
+            Condition::BeneficiaryClaim(c) => {
+                assert_eq!(
+                    c.pending_claim.as_ref().unwrap().claimant,
+                    beneficiary.to_string()
+                );
+                assert!(!c.condition_status);
+            }
+            _ => panic!("beneficiary claim condition expected"),
+        }
+
+        let result = file_claim_impl(args, beneficiary.to_string());
+        assert!(result.is_err_and(|e| matches!(e, SmartVaultErr::ClaimAlreadyPending(_))));
+    }
+
+    #[tokio::test]
+    async fn itest_oracle_allowlist() {
+        let principal = create_principal();
+        create_test_users(&principal).await;
+        let oracle = "aaaaa-aa".to_string();
+
+        let policy: Policy = create_and_add_policy(principal.to_string()).await;
+        let mut upa = UpdatePolicyArgs::from(policy.clone());
+        upa.conditions = vec![UpdateCondition::Oracle(UpdateOracleCondition {
+            id: None,
+            canister_id: oracle.clone(),
+            method: "proof_of_life".to_string(),
+            max_days_inactive: Some(30),
+        })];
+
+        // only oracles allowed by the controllers can be used
+        let result = update_policy_impl(upa.clone(), principal.to_string()).await;
+        assert!(result.is_err_and(|e| matches!(e, SmartVaultErr::OracleNotAllowed(_))));
+        assert!(add_oracle_impl("not a principal".to_string()).is_err());
+
+        add_oracle_impl(oracle.clone()).unwrap();
+        assert_eq!(get_oracle_list_impl(), vec![oracle.clone()]);
+        let policy = update_policy_impl(upa, principal.to_string())
+            .await
+            .unwrap();
+
+        // a removed oracle is no longer called
+        remove_oracle_impl(oracle.clone()).unwrap();
+        assert!(remove_oracle_impl(oracle)
+            .is_err_and(|e| matches!(e, SmartVaultErr::OracleNotAllowed(_))));
+        check_time_based_conditions();
+        let policy = get_policy_from_policy_store(policy.id()).unwrap();
+        match &policy.conditions()[0] {
+            Condition::Oracle(c) => {
+                assert!(!c.call_pending);
+                assert!(c.last_call_at.is_none());
+            }
+            _ => panic!("oracle condition expected"),
+        }
+    }
+
+    #[tokio::test]
+    async fn itest_forecast_policy() {
+        let principal = create_principal();
+        let validator = create_principal();
+        create_test_users(&principal).await;
+        create_test_users(&validator).await;
+
+        let now = time::get_current_time();
+        let datetime = now + 10 * time::NANOS_PER_DAY;
+        let policy: Policy = create_and_add_policy(principal.to_string()).await;
+        let upa: UpdatePolicyArgs = UpdatePolicyArgs {
+            id: policy.id().to_string(),
+            name: policy.name().clone(),
+            beneficiaries: HashSet::new(),
+            secrets: HashSet::new(),
+            key_box: KeyBox::new(),
+            conditions_logical_operator: Some(LogicalOperator::And),
+            conditions: vec![
+                create_new_x_oo_y_condition(validator.to_string()),
+                UpdateCondition::FixedDateTime(UpdateFixedDateTimeCondition {
+                    id: None,
+                    datetime,
+                    end_datetime: None,
+                    time_zone: None,
+                }),
+            ],
+            conditions_expression: None,
+            cooling_off_period_days: Some(2),
+            vetoers: None,
+            not_before: None,
+            expires_at: None,
+            release_stages: None,
+            beneficiary_secrets: None,
+        };
+        let policy = update_policy_impl(upa, principal.to_string())
+            .await
+            .unwrap();
+        let mut args = ForecastPolicyArgs {
+            policy_id: policy.id().to_string(),
+            last_login: None,
+            now: Some(now),
+            votes: vec![],
+        };
+
+        // only the owner can forecast a policy
+        let result = forecast_policy_impl(args.clone(), validator.to_string());
+        assert!(result.is_err_and(|e| matches!(e, SmartVaultErr::CallerNotPolicyOwner(_))));
+
+        // without a vote the policy is never released
+        let forecast = forecast_policy_impl(args.clone(), principal.to_string()).unwrap();
+        assert!(!forecast.conditions_status);
+        assert_eq!(forecast.conditions[0].met_at, None);
+        assert_eq!(forecast.conditions[1].met_at, Some(datetime));
+        assert_eq!(forecast.conditions_met_at, None);
+        assert_eq!(forecast.release_date, None);
+
+        // with a yes vote it is released after the fixed date and the cooling-off period
+        args.votes = vec![SimulatedVote {
+            condition_id: policy.conditions()[0].id(),
+            validator: validator.to_string(),
+            status: true,
+        }];
+        let forecast = forecast_policy_impl(args.clone(), principal.to_string()).unwrap();
+        assert!(forecast.conditions[0].status);
+        assert_eq!(forecast.conditions[0].met_at, Some(now));
+        assert_eq!(forecast.conditions_met_at, Some(datetime));
+        assert_eq!(
+            forecast.release_date,
+            Some(datetime + 2 * time::NANOS_PER_DAY)
+        );
+
+        // a simulated time after the fixed date
+        args.now = Some(datetime + 1);
+        let forecast = forecast_policy_impl(args, principal.to_string()).unwrap();
+        assert!(forecast.conditions_status);
+
+        // the policy itself is unchanged
+        let stored_policy = get_policy_from_policy_store(policy.id()).unwrap();
+        assert!(!stored_policy.conditions_status);
+        match &stored_policy.conditions()[0] {
+            Condition::XOutOfY(c) => assert_eq!(c.validators[0].status, Some(false)),
+            _ => panic!("x out of y condition expected"),
+        }
+    }
+
+    #[tokio::test]
+    async fn itest_forecast_inactivity_in_days() {
+        let principal = create_principal();
+        create_test_users(&principal).await;
+
+        let policy: Policy = create_and_add_policy(principal.to_string()).await;
+        let upa: UpdatePolicyArgs = UpdatePolicyArgs {
+            id: policy.id().to_string(),
+            name: policy.name().clone(),
+            beneficiaries: HashSet::new(),
+            secrets: HashSet::new(),
+            key_box: KeyBox::new(),
+            conditions_logical_operator: None,
+            conditions: vec![UpdateCondition::LastLogin(UpdateLastLoginTimeCondition {
+                id: None,
+                number_of_days_since_last_login: Some(30),
+                inactivity: None,
+                activity_type: None,
+            })],
+            conditions_expression: None,
+            cooling_off_period_days: None,
+            vetoers: None,
+            not_before: None,
+            expires_at: None,
+            release_stages: None,
+            beneficiary_secrets: None,
+        };
+        let policy = update_policy_impl(upa, principal.to_string())
+            .await
+            .unwrap();
+
+        // the inactivity is counted in days, not in seconds
+        let last_login = time::get_current_time();
+        let deadline = last_login + 30 * time::NANOS_PER_DAY;
+        let mut args = ForecastPolicyArgs {
+            policy_id: policy.id().to_string(),
+            last_login: Some(last_login),
+            now: Some(last_login + 31 * time::NANOS_PER_SECOND),
+            votes: vec![],
+        };
+        let forecast = forecast_policy_impl(args.clone(), principal.to_string()).unwrap();
+        assert!(!forecast.conditions[0].status);
+        assert_eq!(forecast.conditions[0].met_at, Some(deadline + 1));
+        assert_eq!(forecast.release_date, Some(deadline + 1));
+
+        // the condition is met only once the 30 days are over
+        args.now = Some(deadline);
+        let forecast = forecast_policy_impl(args.clone(), principal.to_string()).unwrap();
+        assert!(!forecast.conditions_status);
+        args.now = Some(deadline + 1);
+        let forecast = forecast_policy_impl(args, principal.to_string()).unwrap();
+        assert!(forecast.conditions_status);
+        assert_eq!(forecast.conditions_met_at, Some(deadline + 1));
+    }
+
+    #[tokio::test]
+    async fn itest_vote_evidence() {
+        let principal = create_principal();
+        let validator_a = create_principal();
+        let validator_b = create_principal();
+        create_test_users(&principal).await;
+        create_test_users(&validator_a).await;
+        create_test_users(&validator_b).await;
+
+        let policy: Policy = create_and_add_policy(principal.to_string()).await;
+        let mut condition = create_new_x_oo_y_condition(validator_a.to_string());
+        if let UpdateCondition::XOutOfY(c) = &mut condition {
+            let mut second_validator = c.validators[0].clone();
+            second_validator.principal_id = validator_b.to_string();
+            c.validators.push(second_validator);
+            c.quorum = 2;
+        }
+        let upa: UpdatePolicyArgs = UpdatePolicyArgs {
+            id: policy.id().to_string(),
+            name: policy.name().clone(),
+            beneficiaries: HashSet::new(),
+            secrets: HashSet::new(),
+            key_box: KeyBox::new(),
+            conditions_logical_operator: None,
+            conditions: vec![condition],
+            conditions_expression: None,
+            cooling_off_period_days: None,
+            vetoers: None,
+            not_before: None,
+            expires_at: None,
+            release_stages: None,
+            beneficiary_secrets: None,
+        };
+        let policy = update_policy_impl(upa, principal.to_string())
+            .await
+            .unwrap();
+        let vote = |reason: &str, visibility: EvidenceVisibility| ConfirmXOutOfYConditionArgs {
+            policy_id: policy.id().to_string(),
+            condition_id: policy.conditions()[0].id(),
+            status: true,
+            reason: Some(reason.to_string()),
+            evidence: Some(vec![Evidence {
+                name: Some("certificate.pdf".to_string()),
+                encrypted_content: vec![1, 2, 3],
+            }]),
+            evidence_visibility: Some(visibility),
+        };
+
+        // a reason which is too long is rejected
+        let result = confirm_x_out_of_y_condition_impl(
+            vote(&"x".repeat(1_001), EvidenceVisibility::Validators),
+            validator_a.to_string(),
+        );
+        assert!(result.is_err_and(|e| matches!(e, SmartVaultErr::InvalidVoteEvidence(_))));
+
+        // validator a shares the evidence with the other validators, validator b does not
+        confirm_x_out_of_y_condition_impl(
+            vote("Saw the certificate", EvidenceVisibility::Validators),
+            validator_a.to_string(),
+        )
+        .unwrap();
+        let mut private_vote = vote("Was at the funeral", EvidenceVisibility::Beneficiaries);
+        private_vote.status = false;
+        confirm_x_out_of_y_condition_impl(private_vote, validator_b.to_string()).unwrap();
+
+        let evidence_seen_by = |validator: &Principal| {
+            let policy =
+                get_policy_as_validator_impl(policy.id().to_string(), validator.to_string())
+                    .unwrap();
+            match &policy.conditions[0] {
+                Condition::XOutOfY(c) => c
+                    .vote_evidence
+                    .iter()
+                    .flatten()
+                    .map(|(principal_id, _)| principal_id.clone())
+                    .collect::<Vec<_>>(),
+                _ => panic!("x out of y condition expected"),
+            }
+        };
+        assert_eq!(
+            evidence_seen_by(&validator_a),
+            vec![validator_a.to_string()]
+        );
+        let mut seen_by_b = evidence_seen_by(&validator_b);
+        seen_by_b.sort();
+        let mut expected = vec![validator_a.to_string(), validator_b.to_string()];
+        expected.sort();
+        assert_eq!(seen_by_b, expected);
+
+        // the owner sees all evidence
+        let stored_policy = get_policy_from_policy_store(policy.id()).unwrap();
+        match &stored_policy.conditions()[0] {
+            Condition::XOutOfY(c) => {
+                let vote_evidence = c.vote_evidence.as_ref().unwrap();
+                assert_eq!(vote_evidence.len(), 2);
+                assert_eq!(
+                    vote_evidence[&validator_a.to_string()].reason,
+                    Some("Saw the certificate".to_string())
+                );
+            }
+            _ => panic!("x out of y condition expected"),
+        }
+    }
+
+    #[tokio::test]
+    async fn itest_release_stages() {
+        let principal = create_principal();
+        let beneficiary = create_principal();
+        let validator = create_principal();
+        create_test_users(&principal).await;
+        create_test_users(&beneficiary).await;
+        create_test_users(&validator).await;
+
+        let checklist = create_and_add_secret(principal.to_string()).await;
+        let bank_account = create_and_add_secret(principal.to_string()).await;
+        let policy: Policy = create_and_add_policy(principal.to_string()).await;
+        let mut key_box = KeyBox::new();
+        key_box.insert(checklist.id().to_string(), vec![1]);
+        key_box.insert(bank_account.id().to_string(), vec![2]);
+        let mut upa: UpdatePolicyArgs = UpdatePolicyArgs {
+            id: policy.id().to_string(),
+            name: policy.name().clone(),
+            beneficiaries: [beneficiary.to_string()].iter().cloned().collect(),
+            secrets: [checklist.id().to_string()].iter().cloned().collect(),
+            key_box: KeyBox::new(),
+            conditions_logical_operator: None,
+            conditions: vec![create_new_x_oo_y_condition(validator.to_string())],
+            conditions_expression: None,
+            cooling_off_period_days: None,
+            vetoers: None,
+            not_before: None,
+            expires_at: None,
+            release_stages: Some(vec![ReleaseStage {
+                name: Some("Bank".to_string()),
+                delay_days: Some(30),
+                not_before: None,
+                secrets: [bank_account.id().to_string()].iter().cloned().collect(),
+            }]),
+            beneficiary_secrets: None,
+        };
+        upa.key_box.insert(checklist.id().to_string(), vec![1]);
+
+        // staged secrets must be part of the policy
+        let result = update_policy_impl(upa.clone(), principal.to_string()).await;
+        assert!(result.is_err_and(|e| matches!(e, SmartVaultErr::InvalidReleaseStage(_))));
+
+        upa.secrets.insert(bank_account.id().to_string());
+        upa.key_box = key_box;
+        let policy = update_policy_impl(upa, principal.to_string())
+            .await
+            .unwrap();
+        let args = ConfirmXOutOfYConditionArgs {
+            policy_id: policy.id().to_string(),
+            condition_id: policy.conditions()[0].id(),
+            status: true,
+            reason: None,
+            evidence: None,
+            evidence_visibility: None,
+        };
+        confirm_x_out_of_y_condition_impl(args, validator.to_string()).unwrap();
+
+        // the checklist is released right away, the bank account 30 days later
+        let mut policy_for_beneficiary =
+            get_policy_as_beneficiary_impl(policy.id().to_string(), beneficiary.to_string())
+                .unwrap();
+        assert_eq!(policy_for_beneficiary.secrets().len(), 1);
+        assert_eq!(
+            policy_for_beneficiary.secrets().iter().next().unwrap().id,
+            checklist.id().to_string()
+        );
+        assert!(policy_for_beneficiary.release_stages().is_empty());
+        get_secret_as_beneficiary_impl(
+            checklist.id().to_string(),
+            policy.id().to_string(),
+            None,
+            beneficiary.to_string(),
+        )
+        .unwrap();
+        let result = get_secret_as_beneficiary_impl(
+            bank_account.id().to_string(),
+            policy.id().to_string(),
+            None,
+            beneficiary.to_string(),
+        );
+        assert!(result.is_err_and(|e| matches!(e, SmartVaultErr::SecretNotReleased(_))));
+        let key = get_encrypted_symmetric_key_as_beneficiary_impl(
+            checklist.id().to_string(),
+            policy.id().to_string(),
+            beneficiary.to_string(),
+        );
+        assert_eq!(key.unwrap(), vec![1]);
+        let result = get_encrypted_symmetric_key_as_beneficiary_impl(
+            bank_account.id().to_string(),
+            policy.id().to_string(),
+            beneficiary.to_string(),
+        );
+        assert!(result.is_err_and(|e| matches!(e, SmartVaultErr::SecretNotReleased(_))));
+
+        // the stage is shown to the beneficiary once it is unlocked
+        let mut stored_policy = get_policy_from_policy_store(policy.id()).unwrap();
+        stored_policy.release_date = Some(time::get_current_time() - 30 * time::NANOS_PER_DAY);
+        update_policy_in_policy_store(stored_policy).unwrap();
+        let mut policy_for_beneficiary =
+            get_policy_as_beneficiary_impl(policy.id().to_string(), beneficiary.to_string())
+                .unwrap();
+        assert_eq!(policy_for_beneficiary.secrets().len(), 2);
+        assert_eq!(policy_for_beneficiary.release_stages().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn itest_beneficiary_challenge() {
+        let principal = create_principal();
+        let beneficiary = create_principal();
+        let validator = create_principal();
+        create_test_users(&principal).await;
+        create_test_users(&beneficiary).await;
+        create_test_users(&validator).await;
+
+        let secret = create_and_add_secret(principal.to_string()).await;
+        let policy: Policy = create_and_add_policy(principal.to_string()).await;
+        let mut key_box = KeyBox::new();
+        key_box.insert(secret.id().to_string(), vec![1]);
+        let upa: UpdatePolicyArgs = UpdatePolicyArgs {
+            id: policy.id().to_string(),
+            name: policy.name().clone(),
+            beneficiaries: [beneficiary.to_string()].iter().cloned().collect(),
+            secrets: [secret.id().to_string()].iter().cloned().collect(),
+            key_box,
+            conditions_logical_operator: None,
+            conditions: vec![create_new_x_oo_y_condition(validator.to_string())],
+            conditions_expression: None,
+            cooling_off_period_days: None,
+            vetoers: None,
+            not_before: None,
+            expires_at: None,
+            release_stages: None,
+            beneficiary_secrets: None,
+        };
+        let policy = update_policy_impl(upa, principal.to_string())
+            .await
+            .unwrap();
+
+        // only beneficiaries can be challenged
+        let challenge = |beneficiary: &Principal| SetBeneficiaryChallengeArgs {
+            policy_id: policy.id().to_string(),
+            beneficiary: beneficiary.to_string(),
+            question: "Where did we spend the summer of 1999?".to_string(),
+            answer: "Lake Como".to_string(),
+        };
+        let result =
+            set_beneficiary_challenge_impl(challenge(&validator), principal.to_string()).await;
+        assert!(result.is_err_and(|e| matches!(e, SmartVaultErr::InvalidBeneficiaryChallenge(_))));
+        let policy = set_beneficiary_challenge_impl(challenge(&beneficiary), principal.to_string())
+            .await
+            .unwrap();
+
+        // the owner gets the status of the challenge, but neither the salt nor the hashes
+        let response = get_policy_response_for_owner(policy.clone(), &principal.to_string());
+        let response = format!("{:?}", response.unwrap());
+        assert!(response.contains("Where did we spend the summer of 1999?"));
+        assert!(!response.contains("salt") && !response.contains("answer_hash"));
+
+        let answer = |answer: &str| AnswerBeneficiaryChallengeArgs {
+            policy_id: policy.id().to_string(),
+            answer: answer.to_string(),
+        };
+        let result =
+            answer_beneficiary_challenge_impl(answer("Lake Como"), beneficiary.to_string()).await;
+        assert!(result.is_err_and(|e| matches!(e, SmartVaultErr::InvalidPolicyCondition)));
+
+        let args = ConfirmXOutOfYConditionArgs {
+            policy_id: policy.id().to_string(),
+            condition_id: policy.conditions()[0].id(),
+            status: true,
+            reason: None,
+            evidence: None,
+            evidence_visibility: None,
+        };
+        confirm_x_out_of_y_condition_impl(args, validator.to_string()).unwrap();
+
+        // the secret is only revealed with the token granted for the correct answer
+        let get_secret = |token: Option<String>| {
+            get_secret_as_beneficiary_impl(
+                secret.id().to_string(),
+                policy.id().to_string(),
+                token,
+                beneficiary.to_string(),
+            )
+        };
+        let result = get_secret(None);
+        assert!(result.is_err_and(|e| matches!(e, SmartVaultErr::BeneficiaryChallengeRequired(_))));
+        let token = answer_beneficiary_challenge_impl(answer("Lake Como"), beneficiary.to_string())
+            .await
+            .unwrap();
+        get_secret(Some(token.token.clone())).unwrap();
+        let result = get_secret(Some("guessed".to_string()));
+        assert!(result.is_err_and(|e| matches!(e, SmartVaultErr::BeneficiaryChallengeRequired(_))));
+
+        // failed attempts are recorded and rate-limited
+        let result =
+            answer_beneficiary_challenge_impl(answer("Lake Garda"), beneficiary.to_string()).await;
+        assert!(result.is_err_and(|e| matches!(e, SmartVaultErr::WrongChallengeAnswer(_))));
+        let result =
+            answer_beneficiary_challenge_impl(answer("Lake Como"), beneficiary.to_string()).await;
+        assert!(result.is_err_and(|e| matches!(e, SmartVaultErr::ChallengeRateLimited(_))));
+        let policy = get_policy_from_policy_store(policy.id()).unwrap();
+        assert_eq!(
+            policy.beneficiary_challenges.unwrap()[&beneficiary.to_string()].failed_attempts,
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn itest_beneficiary_secrets() {
+        let principal = create_principal();
+        let daughter = create_principal();
+        let son = create_principal();
+        let validator = create_principal();
+        create_test_users(&principal).await;
+        create_test_users(&daughter).await;
+        create_test_users(&son).await;
+        create_test_users(&validator).await;
+
+        let house = create_and_add_secret(principal.to_string()).await;
+        let car = create_and_add_secret(principal.to_string()).await;
+        let policy: Policy = create_and_add_policy(principal.to_string()).await;
+        let mut key_box = KeyBox::new();
+        key_box.insert(house.id().to_string(), vec![1]);
+        key_box.insert(car.id().to_string(), vec![2]);
+        let mut upa: UpdatePolicyArgs = UpdatePolicyArgs {
+            id: policy.id().to_string(),
+            name: policy.name().clone(),
+            beneficiaries: [daughter.to_string(), son.to_string()]
+                .iter()
+                .cloned()
+                .collect(),
+            secrets: [house.id().to_string(), car.id().to_string()]
+                .iter()
+                .cloned()
+                .collect(),
+            key_box,
+            conditions_logical_operator: None,
+            conditions: vec![create_new_x_oo_y_condition(validator.to_string())],
+            conditions_expression: None,
+            cooling_off_period_days: None,
+            vetoers: None,
+            not_before: None,
+            expires_at: None,
+            release_stages: None,
+            beneficiary_secrets: None,
+        };
+
+        // secrets can only be assigned to beneficiaries
+        upa.beneficiary_secrets = Some(BTreeMap::from([(
+            validator.to_string(),
+            [house.id().to_string()].iter().cloned().collect(),
+        )]));
+        let result = update_policy_impl(upa.clone(), principal.to_string()).await;
+        assert!(result.is_err_and(|e| matches!(e, SmartVaultErr::InvalidBeneficiarySecrets(_))));
+
+        // the daughter gets the house, the son (without assignment) gets everything
+        upa.beneficiary_secrets = Some(BTreeMap::from([(
+            daughter.to_string(),
+            [house.id().to_string()].iter().cloned().collect(),
+        )]));
+        let policy = update_policy_impl(upa.clone(), principal.to_string())
+            .await
+            .unwrap();
+
+        // clients which do not send the assignments keep them
+        let assignments = policy.beneficiary_secrets.clone();
+        upa.beneficiary_secrets = None;
+        let policy = update_policy_impl(upa, principal.to_string())
+            .await
+            .unwrap();
+        assert!(assignments.is_some());
+        assert_eq!(policy.beneficiary_secrets, assignments);
+        let args = ConfirmXOutOfYConditionArgs {
+            policy_id: policy.id().to_string(),
+            condition_id: policy.conditions()[0].id(),
+            status: true,
+            reason: None,
+            evidence: None,
+            evidence_visibility: None,
+        };
+        confirm_x_out_of_y_condition_impl(args, validator.to_string()).unwrap();
+
+        let mut policy_for_daughter =
+            get_policy_as_beneficiary_impl(policy.id().to_string(), daughter.to_string()).unwrap();
+        assert_eq!(policy_for_daughter.secrets().len(), 1);
+        let mut policy_for_son =
+            get_policy_as_beneficiary_impl(policy.id().to_string(), son.to_string()).unwrap();
+        assert_eq!(policy_for_son.secrets().len(), 2);
+
+        let result = get_secret_as_beneficiary_impl(
+            car.id().to_string(),
+            policy.id().to_string(),
+            None,
+            daughter.to_string(),
+        );
+        assert!(result.is_err_and(|e| matches!(e, SmartVaultErr::SecretNotAssigned(_))));
+        let result = get_encrypted_symmetric_key_as_beneficiary_impl(
+            car.id().to_string(),
+            policy.id().to_string(),
+            daughter.to_string(),
+        );
+        assert!(result.is_err_and(|e| matches!(e, SmartVaultErr::SecretNotAssigned(_))));
+        get_secret_as_beneficiary_impl(
+            house.id().to_string(),
+            policy.id().to_string(),
+            None,
+            daughter.to_string(),
+        )
+        .unwrap();
+        get_secret_as_beneficiary_impl(
+            car.id().to_string(),
+            policy.id().to_string(),
+            None,
+            son.to_string(),
+        )
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn itest_backup_validators() {
+        let principal = create_principal();
+        let validator = create_principal();
+        let backup = create_principal();
+        let other = create_principal();
+        create_test_users(&principal).await;
+        create_test_users(&validator).await;
+        create_test_users(&backup).await;
+        create_test_users(&other).await;
+
+        let policy: Policy = create_and_add_policy(principal.to_string()).await;
+        let mut condition = create_new_x_oo_y_condition(validator.to_string());
+        if let UpdateCondition::XOutOfY(c) = &mut condition {
+            c.backup_validators = vec![Validator {
+                principal_id: validator.to_string(),
+                ..Default::default()
+            }];
+        }
+        let mut upa: UpdatePolicyArgs = UpdatePolicyArgs {
+            id: policy.id().to_string(),
+            name: policy.name().clone(),
+            beneficiaries: HashSet::new(),
+            secrets: HashSet::new(),
+            key_box: KeyBox::new(),
+            conditions_logical_operator: None,
+            conditions: vec![condition.clone()],
+            conditions_expression: None,
+            cooling_off_period_days: None,
+            vetoers: None,
+            not_before: None,
+            expires_at: None,
+            release_stages: None,
+            beneficiary_secrets: None,
+        };
+
+        // a validator cannot be its own backup
+        let result = update_policy_impl(upa.clone(), principal.to_string()).await;
+        assert!(result.is_err_and(|e| matches!(e, SmartVaultErr::InvalidBackupValidator(_))));
+
+        if let UpdateCondition::XOutOfY(c) = &mut condition {
+            c.backup_validators[0].principal_id = backup.to_string();
+        }
+        upa.conditions = vec![condition];
+        let policy = update_policy_impl(upa, principal.to_string())
+            .await
+            .unwrap();
+        assert_eq!(
+            get_policy_list_as_validator_impl(backup.to_string())
+                .unwrap()
+                .len(),
+            0
+        );
+
+        // the backup takes over when the validator leaves
+        delete_user_impl(validator.to_string()).unwrap();
+        assert_eq!(
+            get_policy_list_as_validator_impl(validator.to_string())
+                .unwrap()
+                .len(),
+            0
+        );
+        assert_eq!(
+            get_policy_list_as_validator_impl(backup.to_string())
+                .unwrap()
+                .len(),
+            1
+        );
+        let health =
+            get_quorum_health_impl(policy.id().to_string(), principal.to_string()).unwrap();
+        assert!(health[0].quorum_reachable);
+        assert_eq!(health[0].backup_validators, 0);
+
+        // without backups left the quorum cannot be reached anymore
+        delete_user_impl(backup.to_string()).unwrap();
+        let health =
+            get_quorum_health_impl(policy.id().to_string(), principal.to_string()).unwrap();
+        assert!(!health[0].quorum_reachable);
+        assert!(health[0].quorum_unreachable_since.is_some());
+        assert_eq!(health[0].total_weight, 0);
+
+        let result = get_quorum_health_impl(policy.id().to_string(), other.to_string());
+        assert!(result.is_err_and(|e| matches!(e, SmartVaultErr::CallerNotPolicyOwner(_))));
+    }
+
+    #[tokio::test]
+    async fn itest_validator_removal_does_not_approve() {
+        let principal = create_principal();
+        let alice = create_principal();
+        let bob = create_principal();
+        create_test_users(&principal).await;
+        create_test_users(&alice).await;
+        create_test_users(&bob).await;
+
+        let policy: Policy = create_and_add_policy(principal.to_string()).await;
+        let mut condition = create_new_x_oo_y_condition(alice.to_string());
+        if let UpdateCondition::XOutOfY(c) = &mut condition {
+            let mut second_validator = c.validators[0].clone();
+            second_validator.principal_id = bob.to_string();
+            c.validators.push(second_validator);
+            c.quorum = 100;
+            c.quorum_type = Some(QuorumType::Percentage);
+        }
+        let upa: UpdatePolicyArgs = UpdatePolicyArgs {
+            id: policy.id().to_string(),
+            name: policy.name().clone(),
+            beneficiaries: HashSet::new(),
+            secrets: HashSet::new(),
+            key_box: KeyBox::new(),
+            conditions_logical_operator: None,
+            conditions: vec![condition],
+            conditions_expression: None,
+            cooling_off_period_days: None,
+            vetoers: None,
+            not_before: None,
+            expires_at: None,
+            release_stages: None,
+            beneficiary_secrets: None,
+        };
+        let policy = update_policy_impl(upa, principal.to_string())
+            .await
+            .unwrap();
+        let args = ConfirmXOutOfYConditionArgs {
+            policy_id: policy.id().to_string(),
+            condition_id: policy.conditions()[0].id(),
+            status: true,
+            reason: None,
+            evidence: None,
+            evidence_visibility: None,
+        };
+        confirm_x_out_of_y_condition_impl(args, alice.to_string()).unwrap();
+
+        // alice's vote alone covers the quorum once bob is gone, but nobody voted again
+        delete_user_impl(bob.to_string()).unwrap();
+        let policy = get_policy_from_policy_store(policy.id()).unwrap();
+        assert!(!policy.conditions()[0].get_condition_status());
+        assert!(!*policy.conditions_status());
+    }
+
+    #[tokio::test]
+    async fn itest_secret_ballot() {
+        let principal = create_principal();
+        let alice = create_principal();
+        let bob = create_principal();
+        create_test_users(&principal).await;
+        create_test_users(&alice).await;
+        create_test_users(&bob).await;
+
+        let policy: Policy = create_and_add_policy(principal.to_string()).await;
+        let mut condition = create_new_x_oo_y_condition(alice.to_string());
+        if let UpdateCondition::XOutOfY(c) = &mut condition {
+            let mut second = c.validators[0].clone();
+            second.principal_id = bob.to_string();
+            c.validators.push(second);
+            c.quorum = 2;
+            c.secret_ballot_days = Some(1);
+        }
+        let upa: UpdatePolicyArgs = UpdatePolicyArgs {
+            id: policy.id().to_string(),
+            name: policy.name().clone(),
+            beneficiaries: HashSet::new(),
+            secrets: HashSet::new(),
+            key_box: KeyBox::new(),
+            conditions_logical_operator: None,
+            conditions: vec![condition],
+            conditions_expression: None,
+            cooling_off_period_days: None,
+            vetoers: None,
+            not_before: None,
+            expires_at: None,
+            release_stages: None,
+            beneficiary_secrets: None,
+        };
+        let policy = update_policy_impl(upa, principal.to_string())
+            .await
+            .unwrap();
+        let condition_id = policy.conditions()[0].id();
+
+        // votes cannot be cast openly
+        let args = ConfirmXOutOfYConditionArgs {
+            policy_id: policy.id().to_string(),
+            condition_id: condition_id.clone(),
+            status: true,
+            reason: None,
+            evidence: None,
+            evidence_visibility: None,
+        };
+        let result = confirm_x_out_of_y_condition_impl(args, alice.to_string());
+        assert!(result.is_err_and(|e| matches!(e, SmartVaultErr::InvalidVoteCommitment(_))));
+
+        for validator in [&alice, &bob] {
+            let args = CommitXOutOfYVoteArgs {
+                policy_id: policy.id().to_string(),
+                condition_id: condition_id.clone(),
+                commitment: vote_commitment(&validator.to_string(), true, "salt"),
+            };
+            commit_x_out_of_y_vote_impl(args, validator.to_string()).unwrap();
+        }
+
+        // validators only see their own commitment
+        let policy_for_alice =
+            get_policy_as_validator_impl(policy.id().to_string(), alice.to_string()).unwrap();
+        assert_eq!(policy_for_alice.voting_deadlines[0].reveal_open, Some(true));
+        if let Condition::XOutOfY(c) = &policy_for_alice.conditions[0] {
+            let commitments = c.commitments.as_ref().unwrap();
+            assert_eq!(commitments.len(), 1);
+            assert!(commitments.contains_key(&alice.to_string()));
+        }
+
+        let reveal = |status: bool, salt: &str| RevealXOutOfYVoteArgs {
+            policy_id: policy.id().to_string(),
+            condition_id: condition_id.clone(),
+            status,
+            salt: salt.to_string(),
+            reason: None,
+            evidence: None,
+            evidence_visibility: None,
+        };
+        let result = reveal_x_out_of_y_vote_impl(reveal(true, "pepper"), alice.to_string());
+        assert!(result.is_err_and(|e| matches!(e, SmartVaultErr::InvalidVoteCommitment(_))));
+        reveal_x_out_of_y_vote_impl(reveal(true, "salt"), alice.to_string()).unwrap();
+        let stored = get_policy_from_policy_store(policy.id()).unwrap();
+        assert!(!stored.conditions_status());
+        reveal_x_out_of_y_vote_impl(reveal(true, "salt"), bob.to_string()).unwrap();
+        let stored = get_policy_from_policy_store(policy.id()).unwrap();
+        assert!(stored.conditions_status());
+    }
+
+    #[tokio::test]
+    async fn itest_guardian_veto() {
+        let principal = create_principal();
+        let alice = create_principal();
+        let guardian = create_principal();
+        create_test_users(&principal).await;
+        create_test_users(&alice).await;
+        create_test_users(&guardian).await;
+
+        let policy: Policy = create_and_add_policy(principal.to_string()).await;
+        let mut condition = create_new_x_oo_y_condition(alice.to_string());
+        if let UpdateCondition::XOutOfY(c) = &mut condition {
+            let mut second = c.validators[0].clone();
+            second.principal_id = guardian.to_string();
+            second.role = Some(ValidatorRole::Guardian);
+            c.validators.push(second);
+            c.quorum = 1;
+        }
+        let upa: UpdatePolicyArgs = UpdatePolicyArgs {
+            id: policy.id().to_string(),
+            name: policy.name().clone(),
+            beneficiaries: HashSet::new(),
+            secrets: HashSet::new(),
+            key_box: KeyBox::new(),
+            conditions_logical_operator: None,
+            conditions: vec![condition],
+            conditions_expression: None,
+            cooling_off_period_days: Some(7),
+            vetoers: None,
+            not_before: None,
+            expires_at: None,
+            release_stages: None,
+            beneficiary_secrets: None,
+        };
+        let policy = update_policy_impl(upa, principal.to_string())
+            .await
+            .unwrap();
+        let condition_id = policy.conditions()[0].id();
+
+        let vote = |status: bool| ConfirmXOutOfYConditionArgs {
+            policy_id: policy.id().to_string(),
+            condition_id: condition_id.clone(),
+            status,
+            reason: None,
+            evidence: None,
+            evidence_visibility: None,
+        };
+        confirm_x_out_of_y_condition_impl(vote(true), alice.to_string()).unwrap();
+        let stored = get_policy_from_policy_store(policy.id()).unwrap();
+        assert!(stored.is_release_pending());
+
+        // the guardian still sees the triggered condition and rejects it
+        let for_guardian =
+            get_policy_as_validator_impl(policy.id().to_string(), guardian.to_string()).unwrap();
+        assert_eq!(for_guardian.conditions.len(), 1);
+        confirm_x_out_of_y_condition_impl(vote(false), guardian.to_string()).unwrap();
+        let stored = get_policy_from_policy_store(policy.id()).unwrap();
+        assert!(!stored.conditions_status());
+        assert!(!stored.conditions()[0].get_condition_status());
+        assert_eq!(
+            stored.condition_resets()[0].reason,
+            ConditionResetReason::ValidatorRejection
+        );
+
+        // a rejection which is not withdrawn blocks the condition
+        confirm_x_out_of_y_condition_impl(vote(false), guardian.to_string()).unwrap();
+        confirm_x_out_of_y_condition_impl(vote(true), alice.to_string()).unwrap();
+        let stored = get_policy_from_policy_store(policy.id()).unwrap();
+        assert!(!stored.conditions_status());
+        assert_eq!(
+            stored.conditions()[0].outcome(),
+            Some(VoteOutcome::Rejected)
+        );
+
+        // the rejection only blocks its own condition, another branch can still release the policy
+        let mut upa = UpdatePolicyArgs::from(stored);
+        upa.conditions_logical_operator = Some(LogicalOperator::Or);
+        upa.conditions
+            .push(UpdateCondition::LastLogin(UpdateLastLoginTimeCondition {
+                id: None,
+                number_of_days_since_last_login: None,
+                inactivity: Some(Duration::days(30)),
+                activity_type: None,
+            }));
+        let mut stored = update_policy_impl(upa, principal.to_string())
+            .await
+            .unwrap();
+        stored.conditions[1].set_condition_status(true);
+        update_policy_in_policy_store(stored.clone()).unwrap();
+        evaluate_overall_conditions_status(stored.id()).unwrap();
+        let stored = get_policy_from_policy_store(policy.id()).unwrap();
+        assert!(stored.conditions_status());
+        assert!(!stored.conditions()[0].get_condition_status());
+    }
+
+    #[tokio::test]
+    async fn itest_owner_login_clears_votes() {
+        let principal = create_principal();
+        let alice = create_principal();
+        let bob = create_principal();
+        create_test_users(&principal).await;
+        create_test_users(&alice).await;
+        create_test_users(&bob).await;
+
+        let policy: Policy = create_and_add_policy(principal.to_string()).await;
+        let mut condition = create_new_x_oo_y_condition(alice.to_string());
+        if let UpdateCondition::XOutOfY(c) = &mut condition {
+            let mut second = c.validators[0].clone();
+            second.principal_id = bob.to_string();
+            c.validators.push(second);
+            c.quorum = 2;
+            c.clear_votes_on_activity = Some(ActivityType::ExplicitLogin);
+        }
+        let upa: UpdatePolicyArgs = UpdatePolicyArgs {
+            id: policy.id().to_string(),
+            name: policy.name().clone(),
+            beneficiaries: HashSet::new(),
+            secrets: HashSet::new(),
+            key_box: KeyBox::new(),
+            conditions_logical_operator: None,
+            conditions: vec![condition],
+            conditions_expression: None,
+            cooling_off_period_days: None,
+            vetoers: None,
+            not_before: None,
+            expires_at: None,
+            release_stages: None,
+            beneficiary_secrets: None,
+        };
+        let policy = update_policy_impl(upa, principal.to_string())
+            .await
+            .unwrap();
+        let condition_id = policy.conditions()[0].id();
+
+        let vote = || ConfirmXOutOfYConditionArgs {
+            policy_id: policy.id().to_string(),
+            condition_id: condition_id.clone(),
+            status: true,
+            reason: None,
+            evidence: None,
+            evidence_visibility: None,
+        };
+        let alice_status = || {
+            let for_alice =
+                get_policy_as_validator_impl(policy.id().to_string(), alice.to_string()).unwrap();
+            match &for_alice.conditions[0] {
+                Condition::XOutOfY(c) => (
+                    c.validators[0].status,
+                    c.vote_clearances.clone().unwrap_or_default(),
+                ),
+                _ => panic!("Expected an XOutOfY condition"),
+            }
+        };
+        confirm_x_out_of_y_condition_impl(vote(), alice.to_string()).unwrap();
+
+        // other activity of the owner does not clear the votes
+        track_activity(&principal.to_string());
+        assert_eq!(alice_status().0, Some(true));
+
+        // the login clears the vote and tells alice why
+        update_user_login_date_impl(principal.to_string()).unwrap();
+        let (status, clearances) = alice_status();
+        assert_eq!(status, None);
+        assert_eq!(
+            clearances.get(&alice.to_string()).unwrap().reason,
+            VoteClearanceReason::OwnerActivity
+        );
+
+        // voting again removes the notice
+        confirm_x_out_of_y_condition_impl(vote(), alice.to_string()).unwrap();
+        let (status, clearances) = alice_status();
+        assert_eq!(status, Some(true));
+        assert!(clearances.is_empty());
+    }
+
+    #[tokio::test]
+    async fn itest_vacation_visible_to_validators() {
+        let principal = create_principal();
+        let validator = create_principal();
+        create_test_users(&principal).await;
+        create_test_users(&validator).await;
+
+        let policy: Policy = create_and_add_policy(principal.to_string()).await;
+        let upa: UpdatePolicyArgs = UpdatePolicyArgs {
+            id: policy.id().to_string(),
+            name: policy.name().clone(),
+            beneficiaries: HashSet::new(),
+            secrets: HashSet::new(),
+            key_box: KeyBox::new(),
+            conditions_logical_operator: None,
+            conditions: vec![create_new_x_oo_y_condition(validator.to_string())],
+            conditions_expression: None,
+            cooling_off_period_days: None,
+            vetoers: None,
+            not_before: None,
+            expires_at: None,
+            release_stages: None,
+            beneficiary_secrets: None,
+        };
+        let policy = update_policy_impl(upa, principal.to_string())
+            .await
+            .unwrap();
+
+        let until = time::get_current_time() + 30 * time::NANOS_PER_DAY;
+        let owner = start_vacation_impl(until, principal.to_string()).unwrap();
+        assert_eq!(owner.vacations()[0].end, until);
+        let too_long = time::get_current_time() + 365 * time::NANOS_PER_DAY;
+        let result = start_vacation_impl(too_long, principal.to_string());
+        assert!(result.is_err_and(|e| matches!(e, SmartVaultErr::InvalidVacation(_))));
+
+        let for_validator =
+            get_policy_as_validator_impl(policy.id().to_string(), validator.to_string()).unwrap();
+        assert_eq!(for_validator.owner_vacations, owner.vacations());
+
+        let owner = end_vacation_impl(principal.to_string()).unwrap();
+        assert!(owner.vacations()[0].end < until);
+    }
+
+    #[tokio::test]
+    async fn itest_dead_mans_switch() {
+        let principal = create_principal();
+        let validator = create_principal();
+        create_test_users(&principal).await;
+        create_test_users(&validator).await;
+
+        let policy: Policy = create_and_add_policy(principal.to_string()).await;
+        let upa: UpdatePolicyArgs = UpdatePolicyArgs {
+            id: policy.id().to_string(),
+            name: policy.name().clone(),
+            beneficiaries: HashSet::new(),
+            secrets: HashSet::new(),
+            key_box: KeyBox::new(),
+            conditions_logical_operator: None,
+            conditions: vec![UpdateCondition::DeadMansSwitch(
+                UpdateDeadMansSwitchCondition { id: None },
+            )],
+            conditions_expression: None,
+            cooling_off_period_days: None,
+            vetoers: None,
+            not_before: None,
+            expires_at: None,
+            release_stages: None,
+            beneficiary_secrets: None,
+        };
+
+        // a policy can only reference an existing switch
+        let result = update_policy_impl(upa.clone(), principal.to_string()).await;
+        assert!(result.is_err_and(|e| matches!(e, SmartVaultErr::InvalidDeadMansSwitch(_))));
+
+        let args = UpdateDeadMansSwitchArgs {
+            conditions: vec![create_new_x_oo_y_condition(validator.to_string())],
+            conditions_logical_operator: None,
+        };
+        let owner = update_dead_mans_switch_impl(args, principal.to_string())
+            .await
+            .unwrap();
+        let condition_id = owner.dead_mans_switch.unwrap().conditions[0].id();
+        update_policy_impl(upa, principal.to_string())
+            .await
+            .unwrap();
+        let result = delete_dead_mans_switch_impl(principal.to_string());
+        assert!(result.is_err_and(|e| matches!(e, SmartVaultErr::DeadMansSwitchInUse(_))));
+
+        let switches = get_dead_mans_switch_list_as_validator_impl(validator.to_string()).unwrap();
+        assert_eq!(switches.len(), 1);
+        assert_eq!(switches[0].owner, principal.to_string());
+
+        // only validators of the switch can vote, the policies referencing it follow the switch
+        let confirm = |caller: &Principal| {
+            confirm_dead_mans_switch_impl(
+                ConfirmDeadMansSwitchArgs {
+                    owner: principal.to_string(),
+                    condition_id: condition_id.clone(),
+                    status: true,
+                },
+                caller.to_string(),
+            )
+        };
+        let result = confirm(&principal);
+        assert!(result.is_err_and(|e| matches!(e, SmartVaultErr::Unauthorized)));
+        confirm(&validator).unwrap();
+
+        let owner = get_current_user_impl(principal.to_string()).unwrap();
+        assert!(owner.dead_mans_switch.unwrap().triggered_at.is_some());
+        let policy = get_policy_from_policy_store(policy.id()).unwrap();
+        assert!(policy.conditions()[0].get_condition_status());
+        assert!(*policy.conditions_status());
+
+        // resetting the policy re-arms the switch, otherwise it would trigger the policy again
+        let policy =
+            reset_policy_conditions_impl(policy.id().to_string(), principal.to_string()).unwrap();
+        assert!(!policy.conditions()[0].get_condition_status());
+        let dead_mans_switch = get_current_user_impl(principal.to_string())
+            .unwrap()
+            .dead_mans_switch
+            .unwrap();
+        assert!(dead_mans_switch.triggered_at.is_none());
+        assert!(!dead_mans_switch.conditions[0].get_condition_status());
+
+        // validators who delete their account are removed from the switch
+        delete_user_impl(validator.to_string()).unwrap();
+        let dead_mans_switch = get_current_user_impl(principal.to_string())
+            .unwrap()
+            .dead_mans_switch
+            .unwrap();
+        match &dead_mans_switch.conditions[0] {
+            Condition::XOutOfY(c) => {
+                assert!(c.validators.is_empty());
+                assert!(c.quorum_unreachable_since.is_some());
+            }
+            _ => panic!("x out of y condition expected"),
+        }
+    }
+
+    #[tokio::test]
+    async fn itest_condition_templates() {
+        let principal = create_principal();
+        let other = create_principal();
+        create_test_users(&principal).await;
+        create_test_users(&other).await;
+
+        let last_login = |days: u64| {
+            UpdateCondition::LastLogin(UpdateLastLoginTimeCondition {
+                id: None,
+                number_of_days_since_last_login: None,
+                inactivity: Some(Duration::days(days)),
+                activity_type: None,
+            })
+        };
+        let template = create_condition_template_impl(
+            CreateConditionTemplateArgs {
+                name: "Quarterly".to_string(),
+                description: None,
+                conditions: vec![last_login(90)],
+                conditions_logical_operator: None,
+                conditions_expression: None,
+            },
+            principal.to_string(),
+        )
+        .await
+        .unwrap();
+
+        // the built-in templates are listed for everybody, own templates only for their owner
+        let own_templates = get_condition_template_list_impl(principal.to_string()).unwrap();
+        let other_templates = get_condition_template_list_impl(other.to_string()).unwrap();
+        assert_eq!(own_templates.len(), other_templates.len() + 1);
+        assert!(other_templates.iter().all(|t| t.is_builtin()));
+
+        let policy: Policy = create_and_add_policy(principal.to_string()).await;
+        let args = ApplyConditionTemplateArgs {
+            policy_id: policy.id().to_string(),
+            template_id: template.id.clone(),
+            linked: true,
+        };
+        let result = apply_condition_template_impl(args.clone(), other.to_string()).await;
+        assert!(result.is_err_and(|e| matches!(e, SmartVaultErr::ConditionTemplateDoesNotExist(_))));
+        let policy = apply_condition_template_impl(args, principal.to_string())
+            .await
+            .unwrap();
+        assert_eq!(policy.condition_template, Some(template.id.clone()));
+        let condition_id = policy.conditions()[0].id();
+
+        // editing the template updates the linked policy and keeps the condition
+        let update = |conditions: Vec<UpdateCondition>| UpdateConditionTemplateArgs {
+            id: template.id.clone(),
+            name: "Half-yearly".to_string(),
+            description: None,
+            conditions,
+            conditions_logical_operator: None,
+            conditions_expression: None,
+        };
+        let template_update =
+            update_condition_template_impl(update(vec![last_login(180)]), principal.to_string())
+                .await
+                .unwrap();
+        assert!(template_update.unlinked_policies.is_empty());
+        let policy = get_policy_from_policy_store(policy.id()).unwrap();
+        assert_eq!(policy.conditions()[0].id(), condition_id);
+        assert!(matches!(
+            &policy.conditions()[0],
+            Condition::LastLogin(c) if c.inactivity() == Duration::days(180)
+        ));
+
+        // renaming the policy keeps the link, editing its conditions by hand removes it
+        let mut upa = UpdatePolicyArgs::from(policy.clone());
+        upa.name = Some("Renamed".to_string());
+        let policy = update_policy_impl(upa.clone(), principal.to_string())
+            .await
+            .unwrap();
+        assert_eq!(policy.condition_template, Some(template.id.clone()));
+        upa.conditions = vec![last_login(30)];
+        let policy = update_policy_impl(upa, principal.to_string())
+            .await
+            .unwrap();
+        assert_eq!(policy.condition_template, None);
+
+        // linked policies which fail validation with the new conditions are reported and unlinked
+        let linked_policy: Policy = create_and_add_policy(principal.to_string()).await;
+        let args = ApplyConditionTemplateArgs {
+            policy_id: linked_policy.id().to_string(),
+            template_id: template.id.clone(),
+            linked: true,
+        };
+        apply_condition_template_impl(args, principal.to_string())
+            .await
+            .unwrap();
+        let dead_mans_switch =
+            UpdateCondition::DeadMansSwitch(UpdateDeadMansSwitchCondition { id: None });
+        let template_update =
+            update_condition_template_impl(update(vec![dead_mans_switch]), principal.to_string())
+                .await
+                .unwrap();
+        assert_eq!(
+            template_update.unlinked_policies,
+            vec![linked_policy.id().to_string()]
+        );
+        let linked_policy = get_policy_from_policy_store(linked_policy.id()).unwrap();
+        assert_eq!(linked_policy.condition_template, None);
+        assert!(matches!(
+            &linked_policy.conditions()[0],
+            Condition::LastLogin(c) if c.inactivity() == Duration::days(180)
+        ));
+
+        // built-in templates cannot be changed
+        let builtin = own_templates.iter().find(|t| t.is_builtin()).unwrap();
+        let result = delete_condition_template_impl(builtin.id.clone(), principal.to_string());
+        assert!(result.is_err_and(|e| matches!(e, SmartVaultErr::InvalidConditionTemplate(_))));
+        delete_condition_template_impl(template.id.clone(), principal.to_string()).unwrap();
+        assert_eq!(
+            get_condition_template_list_impl(principal.to_string())
+                .unwrap()
+                .len(),
+            other_templates.len()
+        );
+    }
+
+    #[tokio::test]
+    async fn itest_legacy_last_login_conditions() {
+        let principal = create_principal();
+        create_test_users(&principal).await;
+
+        let policy: Policy = create_and_add_policy(principal.to_string()).await;
+        let last_login = |number_of_days_since_last_login: Option<u64>| {
+            UpdateCondition::LastLogin(UpdateLastLoginTimeCondition {
+                id: None,
+                number_of_days_since_last_login,
+                inactivity: None,
+                activity_type: None,
+            })
+        };
+        let mut upa = UpdatePolicyArgs::from(policy.clone());
+        upa.conditions = vec![last_login(None)];
+        let result = update_policy_impl(upa.clone(), principal.to_string()).await;
+        assert!(result.is_err_and(|e| matches!(e, SmartVaultErr::InvalidPolicyCondition)));
+
+        // the deprecated number of days is only stored as inactivity
+        upa.conditions = vec![last_login(Some(30))];
+        let mut policy = update_policy_impl(upa, principal.to_string())
+            .await
+            .unwrap();
+        let Condition::LastLogin(condition) = &mut policy.conditions_mut()[0] else {
+            panic!("last login condition expected");
+        };
+        assert_eq!(condition.number_of_days_since_last_login, None);
+        assert_eq!(condition.inactivity, Some(Duration::days(30)));
+
+        // conditions stored before inactivity existed fall back to the number of days
+        condition.number_of_days_since_last_login = Some(90);
+        condition.inactivity = None;
+        assert_eq!(condition.inactivity(), Duration::days(90));
+    }
+
+    #[tokio::test]
+    async fn itest_reset_conditions() {
+        let principal = create_principal();
+        let validator = create_principal();
+        create_test_users(&principal).await;
+        create_test_users(&validator).await;
+
+        let policy: Policy = create_and_add_policy(principal.to_string()).await;
+        let upa: UpdatePolicyArgs = UpdatePolicyArgs {
+            id: policy.id().to_string(),
+            name: policy.name().clone(),
+            beneficiaries: HashSet::new(),
+            secrets: HashSet::new(),
+            key_box: KeyBox::new(),
+            conditions_logical_operator: None,
+            conditions: vec![create_new_x_oo_y_condition(validator.to_string())],
+            conditions_expression: None,
+            cooling_off_period_days: None,
+            vetoers: None,
+            not_before: None,
+            expires_at: None,
+            release_stages: None,
+            beneficiary_secrets: None,
+        };
+        let policy = update_policy_impl(upa.clone(), principal.to_string())
+            .await
+            .unwrap();
+        let condition_id = policy.conditions()[0].id();
+        let confirm = || {
+            let args = ConfirmXOutOfYConditionArgs {
+                policy_id: policy.id().to_string(),
+                condition_id: condition_id.clone(),
+                status: true,
+                reason: None,
+                evidence: None,
+                evidence_visibility: None,
+            };
+            confirm_x_out_of_y_condition_impl(args, validator.to_string()).unwrap();
+            get_policy_from_policy_store(policy.id()).unwrap()
+        };
+
+        // the validator voted by mistake
+        assert!(confirm().is_released());
+        let result = reset_policy_condition_impl(
+            policy.id().to_string(),
+            condition_id.clone(),
+            validator.to_string(),
+        );
+        assert!(result.is_err_and(|e| matches!(e, SmartVaultErr::CallerNotPolicyOwner(_))));
+        let result = reset_policy_condition_impl(
+            policy.id().to_string(),
+            "unknown".to_string(),
+            principal.to_string(),
+        );
+        assert!(result.is_err_and(|e| matches!(e, SmartVaultErr::PolicyConditionDoesNotExist(_))));
+        let reset_policy = reset_policy_condition_impl(
+            policy.id().to_string(),
+            condition_id.clone(),
+            principal.to_string(),
+        )
+        .unwrap();
+        assert!(!reset_policy.conditions_status);
+        assert!(!reset_policy.is_released());
+        assert!(!reset_policy.conditions()[0].get_condition_status());
+        assert_eq!(reset_policy.condition_resets().len(), 1);
+        assert_eq!(
+            reset_policy.condition_resets()[0].reason,
+            ConditionResetReason::OwnerReset
+        );
+
+        // unchanged conditions keep their status on update
+        assert!(confirm().conditions_status);
+        let mut upa = upa;
+        upa.conditions = confirm()
+            .conditions()
+            .iter()
+            .map(|c| c.into_update_condition())
+            .collect();
+        let updated_policy = update_policy_impl(upa.clone(), principal.to_string())
+            .await
+            .unwrap();
+        assert!(updated_policy.conditions_status);
+        assert_eq!(updated_policy.condition_resets().len(), 1);
+
+        // an edited triggered condition is set back to false
+        if let UpdateCondition::XOutOfY(xooy) = &mut upa.conditions[0] {
+            xooy.question = "Is the owner really gone?".to_string();
+        }
+        let updated_policy = update_policy_impl(upa, principal.to_string())
+            .await
+            .unwrap();
+        assert!(!updated_policy.conditions_status);
+        assert!(!updated_policy.conditions()[0].get_condition_status());
+        let reset = updated_policy.condition_resets().last().unwrap();
+        assert_eq!(reset.condition_id, Some(condition_id.clone()));
+        assert_eq!(reset.reason, ConditionResetReason::ConditionEdited);
+
+        // reset of the whole policy
+        assert!(confirm().conditions_status);
+        let reset_policy =
+            reset_policy_conditions_impl(policy.id().to_string(), principal.to_string()).unwrap();
+        assert!(!reset_policy.conditions_status);
+        assert_eq!(
+            reset_policy.condition_resets().last().unwrap().condition_id,
+            None
+        );
+    }
+
+    #[test]
+    fn utest_decode_policy_stored_before_upgrade() {
+        // policies as they have been stored before the policy features have been added
+        #[derive(CandidType, Serialize)]
+        struct StoredValidator {
+            principal_id: String,
+            status: Option<bool>,
+        }
+        #[derive(CandidType, Serialize)]
+        struct StoredLastLoginTimeCondition {
+            id: String,
+            number_of_days_since_last_login: u64,
+            condition_status: bool,
+        }
+        #[derive(CandidType, Serialize)]
+        struct StoredXOutOfYCondition {
+            id: String,
+            validators: Vec<StoredValidator>,
+            question: String,
+            quorum: u64,
+            condition_status: bool,
+        }
+        #[derive(CandidType, Serialize)]
+        struct StoredFixedDateTimeCondition {
+            id: String,
+            datetime: u64,
+            condition_status: bool,
+        }
+        #[derive(CandidType, Serialize)]
+        enum StoredCondition {
+            LastLogin(StoredLastLoginTimeCondition),
+            XOutOfY(StoredXOutOfYCondition),
+            FixedDateTime(StoredFixedDateTimeCondition),
+        }
+        #[derive(CandidType, Serialize)]
+        struct StoredPolicy {
+            id: String,
+            owner: String,
+            name: Option<String>,
+            date_created: u64,
+            date_modified: u64,
+            beneficiaries: HashSet<String>,
+            secrets: HashSet<String>,
+            key_box: KeyBox,
+            conditions_status: bool,
+            conditions_logical_operator: Option<LogicalOperator>,
+            conditions: Vec<StoredCondition>,
+        }
+
+        let stored = StoredPolicy {
+            id: "policy".to_string(),
+            owner: "owner".to_string(),
+            name: None,
+            date_created: 0,
+            date_modified: 0,
+            beneficiaries: HashSet::from(["beneficiary".to_string()]),
+            secrets: HashSet::from(["secret".to_string()]),
+            key_box: KeyBox::new(),
+            conditions_status: true,
+            conditions_logical_operator: Some(LogicalOperator::Or),
+            conditions: vec![
+                StoredCondition::LastLogin(StoredLastLoginTimeCondition {
+                    id: "1".to_string(),
+                    number_of_days_since_last_login: 100,
+                    condition_status: false,
+                }),
+                StoredCondition::XOutOfY(StoredXOutOfYCondition {
+                    id: "2".to_string(),
+                    validators: vec![StoredValidator {
+                        principal_id: "validator".to_string(),
+                        status: Some(true),
+                    }],
+                    question: "Is the owner gone?".to_string(),
+                    quorum: 1,
+                    condition_status: true,
+                }),
+                StoredCondition::FixedDateTime(StoredFixedDateTimeCondition {
+                    id: "3".to_string(),
+                    datetime: 0,
+                    condition_status: false,
+                }),
+            ],
+        };
+
+        let policy = Policy::from_bytes(Cow::Owned(Encode!(&stored).unwrap()));
+        assert_eq!(policy.owner(), "owner");
+        assert_eq!(policy.conditions().len(), 3);
+        // released as soon as the conditions were met
+        assert!(policy.is_released());
+        assert!(policy.vetoers().is_empty());
+        assert!(policy.condition_resets().is_empty());
+        assert!(policy.is_secret_assigned(&"beneficiary".to_string(), &"secret".to_string()));
+        assert!(policy
+            .check_challenge_token(&"beneficiary".to_string(), None, 0)
+            .is_ok());
+        assert_eq!(policy.conditions()[1].outcome(), Some(VoteOutcome::Pending));
+        let Condition::LastLogin(condition) = &policy.conditions()[0] else {
+            panic!("last login condition expected");
+        };
+        assert_eq!(condition.number_of_days_since_last_login, Some(100));
+        assert_eq!(condition.inactivity, None);
+        assert_eq!(condition.inactivity(), Duration::days(100));
+
+        // the decoded policy is stored in the current format
+        let policy = Policy::from_bytes(policy.to_bytes());
+        assert!(policy.is_released());
+
+        // the migration after the upgrade stores the number of days as inactivity
+        POLICY_STORE
+            .with(|ps| ps.borrow_mut().add_policy(policy))
+            .unwrap();
+        migrate_conditions();
+        let policy = get_policy_from_policy_store(&"policy".to_string()).unwrap();
+        let Condition::LastLogin(condition) = &policy.conditions()[0] else {
+            panic!("last login condition expected");
+        };
+        assert_eq!(condition.number_of_days_since_last_login, None);
+        assert_eq!(condition.inactivity, Some(Duration::days(100)));
+        assert!(policy.is_released());
+    }
+
     fn create_principal() -> Principal {
         // create random u8
         let mut rng = rand::thread_rng();
@@ -188,7 +2174,9 @@ mod tests {
         let last_login_time_condition: UpdateCondition =
             UpdateCondition::LastLogin(UpdateLastLoginTimeCondition {
                 id: None,
-                number_of_days_since_last_login: 0,
+                number_of_days_since_last_login: None,
+                inactivity: Some(Duration::days(0)),
+                activity_type: None,
             });
         last_login_time_condition
     }
@@ -200,9 +2188,11 @@ mod tests {
                 validators: vec![Validator {
                     principal_id: validator_id,
                     status: Some(false),
+                    ..Default::default()
                 }],
                 quorum: 1,
                 question: "When will you be happy?".to_string(),
+                ..Default::default()
             });
         x_out_of_y_condition
     }
@@ -238,6 +2228,13 @@ mod tests {
                 last_login_time_condition.clone(),
                 x_out_of_y_condition.clone(),
             ],
+            conditions_expression: None,
+            cooling_off_period_days: None,
+            vetoers: None,
+            not_before: None,
+            expires_at: None,
+            release_stages: None,
+            beneficiary_secrets: None,
         };
 
         // add policy
@@ -297,6 +2294,13 @@ mod tests {
             key_box: added_policy.key_box.clone(),
             conditions_logical_operator: added_policy.conditions_logical_operator().clone(),
             conditions: update_conditions,
+            conditions_expression: added_policy.update_conditions_expression(),
+            cooling_off_period_days: added_policy.cooling_off_period_days,
+            vetoers: Some(added_policy.vetoers()),
+            not_before: added_policy.not_before,
+            expires_at: added_policy.expires_at,
+            release_stages: None,
+            beneficiary_secrets: None,
         };
 
         // perform the update
@@ -332,6 +2336,13 @@ mod tests {
                 updated_last_login_time_condition.clone(),
                 updated_x_out_of_y_condition.clone(),
             ],
+            conditions_expression: None,
+            cooling_off_period_days: None,
+            vetoers: None,
+            not_before: None,
+            expires_at: None,
+            release_stages: None,
+            beneficiary_secrets: None,
         };
 
         // add policy
@@ -369,6 +2380,13 @@ mod tests {
             key_box: added_policy.key_box.clone(),
             conditions_logical_operator: None,
             conditions: vec![updated_last_login_time_condition.clone()],
+            conditions_expression: None,
+            cooling_off_period_days: None,
+            vetoers: None,
+            not_before: None,
+            expires_at: None,
+            release_stages: None,
+            beneficiary_secrets: None,
         };
 
         // add policy
@@ -6,10 +6,16 @@ use std::borrow::Cow;
 use std::collections::{BTreeMap, HashSet};
 use std::hash::{Hash, Hasher};
 
-use crate::policies::conditions::{Condition, UpdateCondition, Validator};
+use crate::common::error::SmartVaultErr;
+use crate::policies::beneficiary_challenges::{BeneficiaryChallenge, BeneficiaryChallengeStatus};
+use crate::policies::condition_templates::ConditionTemplateID;
+use crate::policies::conditions::{
+    Condition, ConditionExpression, ConditionID, UpdateCondition, UpdateConditionExpression,
+    Validator, VoteOutcome,
+};
 use crate::secrets::secret::SecretID;
 use crate::secrets::secret::SecretListEntry;
-use crate::users::user::{KeyBox, PrincipalID};
+use crate::users::user::{KeyBox, PrincipalID, Vacation};
 use crate::utils::{caller::get_caller, time};
 
 pub type PolicyID = String;
@@ -30,7 +36,62 @@ pub struct Policy {
     pub key_box: KeyBox,
     pub conditions_status: bool,
     conditions_logical_operator: Option<LogicalOperator>,
+    /// Nested combination of the conditions, replaces the conditions_logical_operator if set
+    conditions_expression: Option<ConditionExpression>,
     pub conditions: Vec<Condition>,
+    /// Waiting period between the conditions being met and the release to the beneficiaries.
+    /// During this period the release can be vetoed by the owner or the vetoers.
+    pub cooling_off_period_days: Option<u64>,
+    /// Principals besides the owner who are allowed to veto a pending release
+    vetoers: Option<HashSet<PrincipalID>>,
+    /// Point in time at which the policy is released, set as soon as the conditions are met
+    pub release_date: Option<u64>,
+    /// Whether the policy has been released to the beneficiaries, see `released()`
+    pub released: Option<bool>,
+    /// The policy is not released before this point in time, even if the conditions are met
+    pub not_before: Option<u64>,
+    /// The policy becomes void at this point in time and cannot be accessed anymore
+    pub expires_at: Option<u64>,
+    /// History of conditions which have been set back to false, see `condition_resets()`
+    pub condition_resets: Option<Vec<ConditionReset>>,
+    /// Stages in which the secrets are released. Secrets without a stage are released with the policy.
+    pub release_stages: Option<Vec<ReleaseStage>>,
+    /// Secrets assigned to individual beneficiaries. Beneficiaries without an entry get all secrets.
+    pub beneficiary_secrets: Option<BTreeMap<PrincipalID, HashSet<SecretID>>>,
+    /// Template the conditions are linked to, edits of the template are applied to the policy
+    pub condition_template: Option<ConditionTemplateID>,
+    /// Challenges beneficiaries have to answer before their secrets are revealed
+    pub beneficiary_challenges: Option<BTreeMap<PrincipalID, BeneficiaryChallenge>>,
+}
+
+/// Group of secrets which is released some time after the policy itself
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone, PartialEq)]
+pub struct ReleaseStage {
+    pub name: Option<String>,
+    /// Number of days after the release of the policy at which the stage is unlocked
+    pub delay_days: Option<u64>,
+    /// The stage is not unlocked before this point in time
+    pub not_before: Option<u64>,
+    pub secrets: HashSet<SecretID>,
+}
+
+/// Entry in the reset history of a policy
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone)]
+pub struct ConditionReset {
+    /// None if all conditions of the policy have been reset
+    pub condition_id: Option<ConditionID>,
+    pub reason: ConditionResetReason,
+    pub date: u64,
+}
+
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone, PartialEq)]
+pub enum ConditionResetReason {
+    /// Reset by the owner through the reset API
+    OwnerReset,
+    /// A triggered condition has been edited through update_policy
+    ConditionEdited,
+    /// A guardian or the reject quorum rejected a triggered condition
+    ValidatorRejection,
 }
 
 impl Hash for Policy {
@@ -78,7 +139,17 @@ pub struct UpdatePolicyArgs {
     pub secrets: HashSet<SecretID>,
     pub key_box: KeyBox,
     pub conditions_logical_operator: Option<LogicalOperator>,
+    pub conditions_expression: Option<UpdateConditionExpression>,
     pub conditions: Vec<UpdateCondition>,
+    pub cooling_off_period_days: Option<u64>,
+    /// None keeps the current vetoers
+    pub vetoers: Option<HashSet<PrincipalID>>,
+    pub not_before: Option<u64>,
+    pub expires_at: Option<u64>,
+    /// None keeps the current release stages
+    pub release_stages: Option<Vec<ReleaseStage>>,
+    /// None keeps the current assignments
+    pub beneficiary_secrets: Option<BTreeMap<PrincipalID, HashSet<SecretID>>>,
 }
 
 #[derive(Debug, CandidType, Deserialize, Serialize, Clone, PartialEq)]
@@ -87,6 +158,7 @@ pub struct PolicyListEntry {
     pub name: Option<String>,
     pub owner: PrincipalID,
     pub conditions_status: bool,
+    pub released: bool,
 }
 
 #[derive(Debug, CandidType, Deserialize, Serialize, Clone)]
@@ -94,15 +166,33 @@ pub struct PolicyForValidator {
     pub id: PolicyID,
     pub owner: PrincipalID,
     pub conditions: Vec<Condition>,
+    pub voting_deadlines: Vec<VotingDeadline>,
+    /// Vacations of the owner during which inactivity-based conditions are paused
+    pub owner_vacations: Vec<Vacation>,
+}
+
+/// Remaining time in nanoseconds until the voting window of a condition closes
+/// and until the validator's own vote expires
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone)]
+pub struct VotingDeadline {
+    pub condition_id: ConditionID,
+    pub voting_window_remaining: Option<u64>,
+    pub vote_expiry_remaining: Option<u64>,
+    /// Remaining time of the commit phase of a running secret ballot
+    pub commit_phase_remaining: Option<u64>,
+    /// Whether committed votes can be revealed, none if the condition has no secret ballot
+    pub reveal_open: Option<bool>,
 }
 
 impl From<Policy> for PolicyListEntry {
     fn from(t: Policy) -> Self {
+        let released = t.released();
         PolicyListEntry {
             id: t.id().into(),
             name: t.name,
             owner: t.owner,
             conditions_status: t.conditions_status,
+            released,
         }
     }
 }
@@ -121,7 +211,19 @@ impl Policy {
             key_box: BTreeMap::new(),
             conditions_status: false,
             conditions_logical_operator: None,
+            conditions_expression: None,
             conditions: Vec::new(),
+            cooling_off_period_days: None,
+            vetoers: None,
+            release_date: None,
+            released: Some(false),
+            not_before: None,
+            expires_at: None,
+            condition_resets: None,
+            release_stages: None,
+            beneficiary_secrets: None,
+            condition_template: None,
+            beneficiary_challenges: None,
         }
     }
 
@@ -138,6 +240,7 @@ impl Policy {
         new_policy.key_box = BTreeMap::new();
         new_policy.conditions = Vec::new();
         new_policy.conditions_logical_operator = None;
+        new_policy.conditions_expression = None;
         new_policy.conditions_status = false;
         new_policy
     }
@@ -158,6 +261,15 @@ impl Policy {
         new_policy.key_box = upa.key_box;
         new_policy.conditions = new_conditions;
         new_policy.conditions_logical_operator = upa.conditions_logical_operator;
+        new_policy.conditions_expression = upa
+            .conditions_expression
+            .map(|e| e.bind(&new_policy.conditions));
+        new_policy.cooling_off_period_days = upa.cooling_off_period_days;
+        new_policy.vetoers = upa.vetoers;
+        new_policy.not_before = upa.not_before;
+        new_policy.expires_at = upa.expires_at;
+        new_policy.release_stages = upa.release_stages;
+        new_policy.beneficiary_secrets = upa.beneficiary_secrets;
         new_policy.conditions_status = condition_status;
         new_policy.date_created = date_created;
         new_policy
@@ -167,6 +279,20 @@ impl Policy {
         &self.id
     }
 
+    /// The conditions as they are passed to update_policy
+    pub fn update_conditions(&self) -> Vec<UpdateCondition> {
+        self.conditions
+            .iter()
+            .map(|c| c.into_update_condition())
+            .collect()
+    }
+
+    pub fn update_conditions_expression(&self) -> Option<UpdateConditionExpression> {
+        self.conditions_expression
+            .as_ref()
+            .map(|e| e.to_update_expression(&self.conditions))
+    }
+
     pub fn date_created(&self) -> &u64 {
         &self.date_created
     }
@@ -207,6 +333,10 @@ impl Policy {
         &self.conditions_logical_operator
     }
     
+    pub fn conditions_expression(&self) -> &Option<ConditionExpression> {
+        &self.conditions_expression
+    }
+
     /// Returns whether the value was newly inserted. That is:
     /// - If beneficiaries did not previously contain this beneficiary, true is returned.
     /// - If beneficiaries already contained this beneficiary, false is returned, and the set is not modified.
@@ -224,13 +354,279 @@ impl Policy {
     }
 
     pub fn remove_secret(&mut self, secret: &SecretID) -> bool {
+        for stage in self.release_stages.iter_mut().flatten() {
+            stage.secrets.remove(secret);
+        }
+        for secrets in self
+            .beneficiary_secrets
+            .iter_mut()
+            .flat_map(|b| b.values_mut())
+        {
+            secrets.remove(secret);
+        }
         self.secrets.remove(secret)
     }
 
+    /// Sets the overall conditions status. Once the conditions are met, the policy is released
+    /// right away or, if a cooling-off period or a not before date is defined, as soon as these are over.
     pub fn set_conditions_status(&mut self, status: bool) {
+        if status && !self.conditions_status {
+            let now: u64 = time::get_current_time();
+            let cooling_off_period: u64 = self
+                .cooling_off_period_days
+                .unwrap_or(0)
+                .saturating_mul(time::NANOS_PER_DAY);
+            let release_date = now
+                .saturating_add(cooling_off_period)
+                .max(self.not_before.unwrap_or(0));
+            self.release_date = Some(release_date);
+            self.released = Some(release_date <= now && !self.is_expired(now));
+        }
+        if !status {
+            self.release_date = None;
+            self.released = Some(false);
+        }
         self.conditions_status = status;
     }
 
+    pub fn vetoers(&self) -> HashSet<PrincipalID> {
+        self.vetoers.clone().unwrap_or_default()
+    }
+
+    /// Whether the cooling-off period is over. Policies stored before the cooling-off period
+    /// existed have been released as soon as their conditions were met.
+    pub fn released(&self) -> bool {
+        self.released.unwrap_or(self.conditions_status)
+    }
+
+    /// Whether the beneficiaries are allowed to access the policy
+    pub fn is_released(&self) -> bool {
+        self.conditions_status && self.released()
+    }
+
+    /// Whether the policy has become void
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.expires_at.is_some_and(|expiry| now >= expiry)
+    }
+
+    /// Checks that the beneficiaries are allowed to access the policy right now
+    pub fn check_beneficiary_access(&self) -> Result<(), SmartVaultErr> {
+        let now: u64 = time::get_current_time();
+        if self.is_expired(now) {
+            return Err(SmartVaultErr::PolicyExpired(self.id.to_string()));
+        }
+        if !self.is_released() || self.not_before.is_some_and(|not_before| now < not_before) {
+            return Err(SmartVaultErr::InvalidPolicyCondition);
+        }
+        Ok(())
+    }
+
+    /// Point in time at which a release stage is unlocked, known once the release date is set
+    pub fn stage_unlock_date(&self, stage: &ReleaseStage) -> Option<u64> {
+        let release_date = self.release_date?;
+        let delay: u64 = stage
+            .delay_days
+            .unwrap_or(0)
+            .saturating_mul(time::NANOS_PER_DAY);
+        Some(
+            release_date
+                .saturating_add(delay)
+                .max(stage.not_before.unwrap_or(0)),
+        )
+    }
+
+    /// Whether a secret of the policy has been released to the beneficiaries at `now`
+    pub fn is_secret_unlocked(&self, secret_id: &SecretID, now: u64) -> bool {
+        if !self.is_released() || !self.secrets.contains(secret_id) {
+            return false;
+        }
+        match self
+            .release_stages
+            .iter()
+            .flatten()
+            .find(|stage| stage.secrets.contains(secret_id))
+        {
+            Some(stage) => self.stage_unlock_date(stage).is_some_and(|d| now >= d),
+            None => true,
+        }
+    }
+
+    /// Removes all secrets and release stages which have not been released yet
+    pub fn retain_unlocked_secrets(&mut self, now: u64) {
+        let locked_secrets: Vec<SecretID> = self
+            .secrets
+            .iter()
+            .filter(|s| !self.is_secret_unlocked(s, now))
+            .cloned()
+            .collect();
+        for secret_id in locked_secrets {
+            self.remove_secret(&secret_id);
+            self.key_box.remove(&secret_id);
+        }
+        let unlocked_stages: Vec<ReleaseStage> = self
+            .release_stages
+            .iter()
+            .flatten()
+            .filter(|stage| self.stage_unlock_date(stage).is_some_and(|d| now >= d))
+            .cloned()
+            .collect();
+        self.release_stages = Some(unlocked_stages);
+    }
+
+    /// Whether a secret of the policy is assigned to the beneficiary
+    pub fn is_secret_assigned(&self, beneficiary: &PrincipalID, secret_id: &SecretID) -> bool {
+        match self
+            .beneficiary_secrets
+            .as_ref()
+            .and_then(|beneficiary_secrets| beneficiary_secrets.get(beneficiary))
+        {
+            Some(secrets) => secrets.contains(secret_id),
+            None => self.secrets.contains(secret_id),
+        }
+    }
+
+    /// Removes all secrets, assignments and challenges which do not belong to the beneficiary
+    pub fn retain_secrets_of_beneficiary(&mut self, beneficiary: &PrincipalID) {
+        let other_secrets: Vec<SecretID> = self
+            .secrets
+            .iter()
+            .filter(|s| !self.is_secret_assigned(beneficiary, s))
+            .cloned()
+            .collect();
+        for secret_id in other_secrets {
+            self.remove_secret(&secret_id);
+            self.key_box.remove(&secret_id);
+        }
+        if let Some(beneficiary_secrets) = self.beneficiary_secrets.as_mut() {
+            beneficiary_secrets.retain(|b, _| b == beneficiary);
+        }
+        if let Some(beneficiary_challenges) = self.beneficiary_challenges.as_mut() {
+            beneficiary_challenges.retain(|b, _| b == beneficiary);
+        }
+    }
+
+    /// Checks that the beneficiary is allowed to access a secret of the policy right now
+    pub fn check_secret_access(
+        &self,
+        beneficiary: &PrincipalID,
+        secret_id: &SecretID,
+    ) -> Result<(), SmartVaultErr> {
+        self.check_beneficiary_access()?;
+        if !self.is_secret_assigned(beneficiary, secret_id) {
+            return Err(SmartVaultErr::SecretNotAssigned(secret_id.to_string()));
+        }
+        if !self.is_secret_unlocked(secret_id, time::get_current_time()) {
+            return Err(SmartVaultErr::SecretNotReleased(secret_id.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Checks that the beneficiary answered its challenge, if any, and the token is still valid
+    pub fn check_challenge_token(
+        &self,
+        beneficiary: &PrincipalID,
+        token: Option<&str>,
+        now: u64,
+    ) -> Result<(), SmartVaultErr> {
+        match self
+            .beneficiary_challenges
+            .as_ref()
+            .and_then(|beneficiary_challenges| beneficiary_challenges.get(beneficiary))
+        {
+            Some(challenge) => challenge.check_token(token, now),
+            None => Ok(()),
+        }
+    }
+
+    /// Whether the conditions are met but the cooling-off period is not yet over
+    pub fn is_release_pending(&self) -> bool {
+        self.conditions_status && !self.released()
+    }
+
+    /// Releases the policy if the cooling-off period is over.
+    /// Returns whether the policy has been released by this call.
+    pub fn finalize_release(&mut self, now: u64) -> bool {
+        if self.is_release_pending()
+            && self.release_date.is_some_and(|d| now >= d)
+            && !self.is_expired(now)
+        {
+            self.released = Some(true);
+            return true;
+        }
+        false
+    }
+
+    /// Cancels a pending release and re-arms all conditions
+    pub fn veto_release(&mut self) {
+        let now: u64 = time::get_current_time();
+        for condition in self.conditions.iter_mut() {
+            condition.reset(now);
+        }
+        self.set_conditions_status(false);
+    }
+
+    /// Re-arms a single condition. The overall status is withdrawn if the conditions are no longer met.
+    pub fn reset_condition(
+        &mut self,
+        condition_id: &ConditionID,
+        reason: ConditionResetReason,
+    ) -> Result<(), SmartVaultErr> {
+        let now: u64 = time::get_current_time();
+        let condition = self
+            .conditions
+            .iter_mut()
+            .find(|c| &c.id() == condition_id)
+            .ok_or_else(|| SmartVaultErr::PolicyConditionDoesNotExist(condition_id.to_string()))?;
+        condition.reset(now);
+        self.add_condition_reset(ConditionReset {
+            condition_id: Some(condition_id.to_string()),
+            reason,
+            date: now,
+        });
+
+        if self.conditions_status && !self.overall_conditions_met() {
+            self.set_conditions_status(false);
+        }
+        Ok(())
+    }
+
+    /// Re-arms a triggered XOutOfY condition which has been rejected by its validators,
+    /// as long as the policy has not been released
+    pub fn reset_rejected_condition(
+        &mut self,
+        condition_id: &ConditionID,
+    ) -> Result<(), SmartVaultErr> {
+        let rejected = self.conditions.iter().any(|c| {
+            &c.id() == condition_id
+                && c.get_condition_status()
+                && c.outcome() == Some(VoteOutcome::Rejected)
+        });
+        if rejected && !self.is_released() {
+            self.reset_condition(condition_id, ConditionResetReason::ValidatorRejection)?;
+        }
+        Ok(())
+    }
+
+    pub fn condition_resets(&self) -> &[ConditionReset] {
+        self.condition_resets.as_deref().unwrap_or_default()
+    }
+
+    fn add_condition_reset(&mut self, reset: ConditionReset) {
+        self.condition_resets
+            .get_or_insert_with(Vec::new)
+            .push(reset);
+    }
+
+    /// Re-arms all conditions and withdraws the overall status
+    pub fn reset_conditions(&mut self, reason: ConditionResetReason) {
+        self.veto_release();
+        self.add_condition_reset(ConditionReset {
+            condition_id: None,
+            reason,
+            date: time::get_current_time(),
+        });
+    }
+
     // TODO: make proper CRUD functions
     pub fn key_box_mut(&mut self) -> &mut KeyBox {
         &mut self.key_box
@@ -257,8 +653,17 @@ impl Policy {
     }
 
     pub fn validate_overall_conditions_status(&mut self) {
+        if self.overall_conditions_met() {
+            self.set_conditions_status(true);
+        }
+    }
+
+    /// Combines the status of all conditions according to the condition expression or logical operator
+    pub fn overall_conditions_met(&self) -> bool {
         let mut set_to_true:bool = false;
-        if self.conditions_logical_operator.is_none() || self.conditions_logical_operator == Some(LogicalOperator::And) {
+        if let Some(expression) = &self.conditions_expression {
+            set_to_true = expression.evaluate(&self.conditions);
+        } else if self.conditions_logical_operator.is_none() || self.conditions_logical_operator == Some(LogicalOperator::And) {
             // All conditions must be true
             set_to_true = true;
             for condition in &self.conditions {
@@ -278,9 +683,7 @@ impl Policy {
             }
         }
         
-        if set_to_true {
-            self.conditions_status = true;
-        }
+        set_to_true
     }
 }
 
@@ -296,7 +699,19 @@ pub struct PolicyWithSecretListEntries {
     key_box: KeyBox,
     conditions_status: bool,
     conditions_logical_operator: Option<LogicalOperator>,
+    conditions_expression: Option<ConditionExpression>,
     pub conditions: Vec<Condition>,
+    cooling_off_period_days: Option<u64>,
+    vetoers: HashSet<PrincipalID>,
+    release_date: Option<u64>,
+    released: bool,
+    not_before: Option<u64>,
+    expires_at: Option<u64>,
+    condition_resets: Vec<ConditionReset>,
+    release_stages: Vec<ReleaseStage>,
+    beneficiary_secrets: BTreeMap<PrincipalID, HashSet<SecretID>>,
+    condition_template: Option<ConditionTemplateID>,
+    beneficiary_challenges: BTreeMap<PrincipalID, BeneficiaryChallengeStatus>,
 }
 
 impl PolicyWithSecretListEntries {
@@ -314,16 +729,54 @@ impl PolicyWithSecretListEntries {
             conditions: Vec::new(),
             conditions_status: false,
             conditions_logical_operator: None,
+            conditions_expression: None,
+            cooling_off_period_days: None,
+            vetoers: HashSet::new(),
+            release_date: None,
+            released: false,
+            not_before: None,
+            expires_at: None,
+            condition_resets: Vec::new(),
+            release_stages: Vec::new(),
+            beneficiary_secrets: BTreeMap::new(),
+            condition_template: None,
+            beneficiary_challenges: BTreeMap::new(),
         }
     }
 
     pub fn secrets(&mut self) -> &mut HashSet<SecretListEntry> {
         &mut self.secrets
     }
+
+    pub fn release_stages(&self) -> &Vec<ReleaseStage> {
+        &self.release_stages
+    }
+}
+
+impl From<Policy> for UpdatePolicyArgs {
+    fn from(p: Policy) -> Self {
+        UpdatePolicyArgs {
+            conditions: p.update_conditions(),
+            conditions_expression: p.update_conditions_expression(),
+            id: p.id,
+            name: p.name,
+            beneficiaries: p.beneficiaries,
+            secrets: p.secrets,
+            key_box: p.key_box,
+            conditions_logical_operator: p.conditions_logical_operator,
+            cooling_off_period_days: p.cooling_off_period_days,
+            vetoers: p.vetoers,
+            not_before: p.not_before,
+            expires_at: p.expires_at,
+            release_stages: p.release_stages,
+            beneficiary_secrets: p.beneficiary_secrets,
+        }
+    }
 }
 
 impl From<Policy> for PolicyWithSecretListEntries {
     fn from(p: Policy) -> Self {
+        let released = p.released();
         let mut new_policy = PolicyWithSecretListEntries::new(p.id);
         new_policy.name = p.name;
         new_policy.owner = p.owner;
@@ -331,7 +784,24 @@ impl From<Policy> for PolicyWithSecretListEntries {
         new_policy.key_box = p.key_box;
         new_policy.conditions = p.conditions;
         new_policy.conditions_logical_operator = p.conditions_logical_operator;
+        new_policy.conditions_expression = p.conditions_expression;
         new_policy.conditions_status = p.conditions_status;
+        new_policy.cooling_off_period_days = p.cooling_off_period_days;
+        new_policy.vetoers = p.vetoers.unwrap_or_default();
+        new_policy.release_date = p.release_date;
+        new_policy.released = released;
+        new_policy.not_before = p.not_before;
+        new_policy.expires_at = p.expires_at;
+        new_policy.condition_resets = p.condition_resets.unwrap_or_default();
+        new_policy.release_stages = p.release_stages.unwrap_or_default();
+        new_policy.beneficiary_secrets = p.beneficiary_secrets.unwrap_or_default();
+        new_policy.condition_template = p.condition_template;
+        new_policy.beneficiary_challenges = p
+            .beneficiary_challenges
+            .iter()
+            .flatten()
+            .map(|(beneficiary, challenge)| (beneficiary.to_string(), challenge.status()))
+            .collect();
         new_policy.date_created = p.date_created;
         new_policy.date_modified = p.date_modified;
         new_policy
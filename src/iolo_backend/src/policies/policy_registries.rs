@@ -104,8 +104,10 @@ impl PolicyRegistries {
         for validator in validators {
             match self.validator_to_policies.get(&validator.principal_id) {
                 Some(mut sphs) => {
-                    // entry for validator already exists
+                    // entry for validator already exists, re-insert the extended set
                     sphs.0.insert(policy_id.clone());
+                    self.validator_to_policies
+                        .insert(validator.principal_id.to_string(), sphs);
                 }
                 None => {
                     // no entry for validator yet
@@ -139,6 +141,11 @@ impl PolicyRegistries {
         }
     }
 
+    /// Removes all policies of a validator, e.g. when the validator deletes their account
+    pub fn remove_validator(&mut self, validator: &PrincipalID) {
+        self.validator_to_policies.remove(validator);
+    }
+
     pub fn get_policy_ids_as_beneficiary(
         &self,
         beneficiary: &PrincipalID,
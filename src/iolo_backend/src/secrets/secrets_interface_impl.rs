@@ -5,6 +5,7 @@ use crate::policies::policy::{Policy, PolicyID};
 use crate::secrets::secret::{SecretID, UpdateSecretArgs};
 
 use crate::users::user::PrincipalID;
+use crate::utils::time;
 use crate::{
     common::{error::SmartVaultErr, uuid::UUID},
     smart_vaults::smart_vault::{SECRET_STORE, USER_STORE},
@@ -140,6 +141,7 @@ pub fn get_encrypted_symmetric_key_impl(
 pub fn get_secret_as_beneficiary_impl(
     secret_id: SecretID,
     policy_id: PolicyID,
+    challenge_token: Option<String>,
     caller: PrincipalID,
 ) -> Result<Secret, SmartVaultErr> {
     // fetch policy from policy store and check if caller is beneficiary
@@ -154,16 +156,19 @@ pub fn get_secret_as_beneficiary_impl(
         return Err(SmartVaultErr::PolicyDoesNotExist(policy_id));
     }
 
-    // Check that beneficiary is allowed to read policy
-    if *policy.conditions_status() {
-        let secret = SECRET_STORE.with(|ss| {
-            let secret_store = ss.borrow();
-            secret_store.get(&secret_id)
-        })?;
-        return Ok(secret);
-    }
-
-    Err(SmartVaultErr::InvalidPolicyCondition)
+    // Check that beneficiary is allowed to read the secret
+    policy.check_secret_access(&caller, &secret_id)?;
+    // and has answered its challenge
+    policy.check_challenge_token(
+        &caller,
+        challenge_token.as_deref(),
+        time::get_current_time(),
+    )?;
+    let secret = SECRET_STORE.with(|ss| {
+        let secret_store = ss.borrow();
+        secret_store.get(&secret_id)
+    })?;
+    Ok(secret)
 }
 
 pub fn get_encrypted_symmetric_key_as_beneficiary_impl(
@@ -183,12 +188,13 @@ pub fn get_encrypted_symmetric_key_as_beneficiary_impl(
         return Err(SmartVaultErr::PolicyDoesNotExist(policy_id));
     }
 
-    // Check that beneficiary is allowed to read policy
-    if *policy.conditions_status() {
-        return Ok(policy.key_box().get(&secret_id).unwrap().clone());
-    }
-
-    Err(SmartVaultErr::InvalidPolicyCondition)
+    // Check that beneficiary is allowed to read the secret
+    policy.check_secret_access(&caller, &secret_id)?;
+    policy
+        .key_box()
+        .get(&secret_id)
+        .cloned()
+        .ok_or(SmartVaultErr::KeyBoxEntryDoesNotExistForSecret(secret_id))
 }
 
 /**
@@ -5,6 +5,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::common::error::SmartVaultErr;
 use crate::policies::policies_interface_impl::get_policy_from_policy_store;
+use crate::utils::time;
 
 use super::vetkd_types::{
     CanisterId, VetKDCurve, VetKDEncryptedKeyReply, VetKDEncryptedKeyRequest, VetKDKeyId,
@@ -17,6 +18,8 @@ const VETKD_SYSTEM_API_CANISTER_ID: &str = "cr2gw-4iaaa-aaaal-qcrda-cai";
 pub struct PolicyKeyDerviationArgs {
     pub encryption_public_key: Vec<u8>,
     pub policy_id: String,
+    /// Token granted to a beneficiary for answering the challenge of the policy
+    pub challenge_token: Option<String>,
 }
 
 /// Computes a fresh vetkd symmetric key to encrypt/decrypt the secrets in a user vault.
@@ -58,14 +61,24 @@ async fn generate_vetkd_encrypted_symmetric_key_for_policy(
 
     // Checks if one of the following conditions are met:
     // 1. Caller is the owner or
-    // 2. Caller is a beneficiary and conditions are met
+    // 2. Caller is a beneficiary and the policy has been released and is not expired
     let key_can_be_generated = policy.owner() == &caller.to_string()
-        || (policy.beneficiaries().contains(&caller.to_string()) && *policy.conditions_status());
+        || (policy.beneficiaries().contains(&caller.to_string())
+            && policy.check_beneficiary_access().is_ok());
 
     if !key_can_be_generated {
         return Err(SmartVaultErr::KeyGenerationNotAllowed);
     }
 
+    // Beneficiaries have to answer their challenge first
+    if policy.owner() != &caller.to_string() {
+        policy.check_challenge_token(
+            &caller.to_string(),
+            args.challenge_token.as_deref(),
+            time::get_current_time(),
+        )?;
+    }
+
     let derivation_id = args.policy_id.as_bytes().to_vec();
 
     let request = VetKDEncryptedKeyRequest {
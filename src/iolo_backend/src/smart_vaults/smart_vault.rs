@@ -3,16 +3,36 @@ use std::cell::RefCell;
 use ic_cdk::{post_upgrade, pre_upgrade};
 
 use crate::common::error::SmartVaultErr;
-use crate::policies::conditions::ConfirmXOutOfYConditionArgs;
+use crate::policies::conditions::{
+    CommitXOutOfYVoteArgs, ConditionID, ConfirmXOutOfYConditionArgs, FileClaimArgs, QuorumHealth,
+    RevealXOutOfYVoteArgs,
+};
+use crate::policies::condition_template_store::ConditionTemplateStore;
+use crate::policies::conditions_manager::migrate_conditions;
+use crate::policies::condition_templates::{
+    ApplyConditionTemplateArgs, ConditionTemplate, ConditionTemplateID, ConditionTemplateUpdate,
+    CreateConditionTemplateArgs, UpdateConditionTemplateArgs,
+};
+use crate::policies::forecast::{ForecastPolicyArgs, PolicyForecast};
+use crate::policies::oracle_store::OracleStore;
+use crate::policies::oracles::{add_oracle_impl, get_oracle_list_impl, remove_oracle_impl};
 use crate::policies::policies_interface_impl::{
-    create_policy_impl, confirm_x_out_of_y_condition_impl, get_policy_as_beneficiary_impl,
-    get_policy_as_owner_impl, get_policy_as_validator_impl, get_policy_list_as_beneficiary_impl,
-    get_policy_list_as_owner_impl, get_policy_list_as_validator_impl, delete_policy_impl,
-    update_policy_impl,
+    apply_condition_template_impl, check_in_impl, create_condition_template_impl,
+    create_policy_impl, commit_x_out_of_y_vote_impl, delete_condition_template_impl,
+    get_condition_template_list_impl, update_condition_template_impl,
+    confirm_x_out_of_y_condition_impl, file_claim_impl, forecast_policy_impl,
+    get_quorum_health_impl, reveal_x_out_of_y_vote_impl,
+    get_policy_as_beneficiary_impl, get_policy_as_owner_impl, get_policy_as_validator_impl,
+    get_policy_list_as_beneficiary_impl, get_policy_list_as_owner_impl,
+    get_policy_list_as_validator_impl, delete_policy_impl, reset_policy_condition_impl,
+    reset_policy_conditions_impl, update_policy_impl, veto_policy_release_impl,
+    set_beneficiary_challenge_impl, remove_beneficiary_challenge_impl,
+    answer_beneficiary_challenge_impl, get_policy_response_for_owner,
 };
-use crate::policies::policy::{
-    CreatePolicyArgs, Policy, PolicyForValidator, PolicyID, PolicyListEntry,
+use crate::policies::beneficiary_challenges::{
+    AnswerBeneficiaryChallengeArgs, ChallengeToken, SetBeneficiaryChallengeArgs,
 };
+use crate::policies::policy::{CreatePolicyArgs, PolicyForValidator, PolicyID, PolicyListEntry};
 use crate::policies::policy::{PolicyWithSecretListEntries, UpdatePolicyArgs};
 use crate::policies::policy_registries::PolicyRegistries;
 use crate::policies::policy_store::PolicyStore;
@@ -24,13 +44,21 @@ use crate::secrets::secrets_interface_impl::{
     get_secret_list_impl, delete_secret_impl, update_secret_impl,
 };
 
+use crate::users::activity_tracker::track_activity_on_success;
 use crate::users::contact::{CreateContactArgs, Contact};
-use crate::users::user::{AddOrUpdateUserArgs, PrincipalID, User};
+use crate::users::dead_mans_switch::{
+    ConfirmDeadMansSwitchArgs, DeadMansSwitchForValidator, UpdateDeadMansSwitchArgs,
+};
+use crate::users::user::{
+    AddOrUpdateUserArgs, DeadlineExtension, ExtendDeadlineArgs, PrincipalID, User,
+};
 use crate::users::user_store::UserStore;
 use crate::users::users_interface_impl::{
     crate_contact_impl, create_user_impl, delete_user_impl, get_contact_list_impl,
-    get_current_user_impl, delete_contact_impl, update_contact_impl, update_user_impl,
-    update_user_login_date_impl,
+    get_current_user_impl, delete_contact_impl, end_vacation_impl, extend_inactivity_deadline_impl,
+    start_vacation_impl, update_dead_mans_switch_impl, delete_dead_mans_switch_impl,
+    confirm_dead_mans_switch_impl, get_dead_mans_switch_list_as_validator_impl,
+    update_contact_impl, update_user_impl, update_user_login_date_impl,
 };
 use crate::utils::caller::get_caller;
 
@@ -46,6 +74,12 @@ thread_local! {
 
     /// Policy Registry for beneficiaries and validators
     pub static POLICY_REGISTRIES: RefCell<PolicyRegistries> = RefCell::new(PolicyRegistries::new());
+
+    /// User-defined condition templates
+    pub static CONDITION_TEMPLATE_STORE: RefCell<ConditionTemplateStore> = RefCell::new(ConditionTemplateStore::new());
+
+    /// Oracle canisters allowed by the controllers
+    pub static ORACLE_STORE: RefCell<OracleStore> = RefCell::new(OracleStore::new());
 }
 
 /// Creates a new user
@@ -62,7 +96,7 @@ pub fn get_current_user() -> Result<User, SmartVaultErr> {
 
 #[ic_cdk_macros::update]
 pub fn update_user(args: AddOrUpdateUserArgs) -> Result<User, SmartVaultErr> {
-    update_user_impl(args, get_caller_id())
+    track_activity_on_success(&get_caller_id(), update_user_impl(args, get_caller_id()))
 }
 
 #[ic_cdk_macros::update]
@@ -70,6 +104,53 @@ pub fn update_user_login_date() -> Result<User, SmartVaultErr> {
     update_user_login_date_impl(get_caller_id())
 }
 
+/// Pauses the inactivity-based conditions of the caller's policies until the given date
+#[ic_cdk_macros::update]
+pub fn start_vacation(until: u64) -> Result<User, SmartVaultErr> {
+    track_activity_on_success(
+        &get_caller_id(),
+        start_vacation_impl(until, get_caller_id()),
+    )
+}
+
+#[ic_cdk_macros::update]
+pub fn end_vacation() -> Result<User, SmartVaultErr> {
+    track_activity_on_success(&get_caller_id(), end_vacation_impl(get_caller_id()))
+}
+
+/// Sets the account-level dead man's switch which policies can reference as condition
+#[ic_cdk_macros::update]
+pub async fn update_dead_mans_switch(
+    args: UpdateDeadMansSwitchArgs,
+) -> Result<User, SmartVaultErr> {
+    track_activity_on_success(
+        &get_caller_id(),
+        update_dead_mans_switch_impl(args, get_caller_id()).await,
+    )
+}
+
+#[ic_cdk_macros::update]
+pub fn delete_dead_mans_switch() -> Result<User, SmartVaultErr> {
+    track_activity_on_success(
+        &get_caller_id(),
+        delete_dead_mans_switch_impl(get_caller_id()),
+    )
+}
+
+#[ic_cdk_macros::update]
+pub fn confirm_dead_mans_switch(args: ConfirmDeadMansSwitchArgs) -> Result<(), SmartVaultErr> {
+    track_activity_on_success(
+        &get_caller_id(),
+        confirm_dead_mans_switch_impl(args, get_caller_id()),
+    )
+}
+
+#[ic_cdk_macros::query]
+pub fn get_dead_mans_switch_list_as_validator(
+) -> Result<Vec<DeadMansSwitchForValidator>, SmartVaultErr> {
+    get_dead_mans_switch_list_as_validator_impl(get_caller_id())
+}
+
 #[ic_cdk_macros::update]
 pub fn delete_user() -> Result<(), SmartVaultErr> {
     delete_user_impl(get_caller_id())
@@ -77,7 +158,10 @@ pub fn delete_user() -> Result<(), SmartVaultErr> {
 
 #[ic_cdk_macros::update]
 pub async fn create_secret(args: CreateSecretArgs) -> Result<Secret, SmartVaultErr> {
-    create_secret_impl(args, get_caller_id()).await
+    track_activity_on_success(
+        &get_caller_id(),
+        create_secret_impl(args, get_caller_id()).await,
+    )
 }
 
 #[ic_cdk_macros::query]
@@ -87,12 +171,15 @@ pub fn get_secret(sid: SecretID) -> Result<Secret, SmartVaultErr> {
 
 #[ic_cdk_macros::update]
 pub fn update_secret(usa: UpdateSecretArgs) -> Result<Secret, SmartVaultErr> {
-    update_secret_impl(usa, get_caller_id())
+    track_activity_on_success(&get_caller_id(), update_secret_impl(usa, get_caller_id()))
 }
 
 #[ic_cdk_macros::update]
 pub fn delete_secret(secret_id: String) -> Result<(), SmartVaultErr> {
-    delete_secret_impl(secret_id, get_caller_id())
+    track_activity_on_success(
+        &get_caller_id(),
+        delete_secret_impl(secret_id, get_caller_id()),
+    )
 }
 
 #[ic_cdk_macros::query]
@@ -109,8 +196,9 @@ pub fn get_encrypted_symmetric_key(sid: SecretID) -> Result<Vec<u8>, SmartVaultE
 pub fn get_secret_as_beneficiary(
     sid: SecretID,
     policy_id: PolicyID,
+    challenge_token: Option<String>,
 ) -> Result<Secret, SmartVaultErr> {
-    get_secret_as_beneficiary_impl(sid, policy_id, get_caller_id())
+    get_secret_as_beneficiary_impl(sid, policy_id, challenge_token, get_caller_id())
 }
 
 #[ic_cdk_macros::query]
@@ -126,8 +214,15 @@ pub fn get_encrypted_symmetric_key_as_beneficiary(
  */
 
 #[ic_cdk_macros::update]
-pub async fn create_policy(args: CreatePolicyArgs) -> Result<Policy, SmartVaultErr> {
-    create_policy_impl(args, get_caller_id()).await
+pub async fn create_policy(
+    args: CreatePolicyArgs,
+) -> Result<PolicyWithSecretListEntries, SmartVaultErr> {
+    track_activity_on_success(
+        &get_caller_id(),
+        create_policy_impl(args, get_caller_id())
+            .await
+            .and_then(|policy| get_policy_response_for_owner(policy, &get_caller_id())),
+    )
 }
 
 #[ic_cdk_macros::query]
@@ -165,21 +260,196 @@ pub fn get_policy_list_as_validator() -> Result<Vec<PolicyForValidator>, SmartVa
     get_policy_list_as_validator_impl(get_caller_id())
 }
 
+/// Projects when a policy would be released, optionally with a hypothetical last login, time and votes
+#[ic_cdk_macros::query]
+pub fn forecast_policy(args: ForecastPolicyArgs) -> Result<PolicyForecast, SmartVaultErr> {
+    forecast_policy_impl(args, get_caller_id())
+}
+
+/// Shows whether the quorums of a policy can still be reached by validators whose account exists
+#[ic_cdk_macros::query]
+pub fn get_quorum_health(policy_id: PolicyID) -> Result<Vec<QuorumHealth>, SmartVaultErr> {
+    get_quorum_health_impl(policy_id, get_caller_id())
+}
+
 #[ic_cdk_macros::update]
-pub async fn update_policy(upa: UpdatePolicyArgs) -> Result<Policy, SmartVaultErr> {
-    update_policy_impl(upa, get_caller_id()).await
+pub async fn update_policy(
+    upa: UpdatePolicyArgs,
+) -> Result<PolicyWithSecretListEntries, SmartVaultErr> {
+    track_activity_on_success(
+        &get_caller_id(),
+        update_policy_impl(upa, get_caller_id())
+            .await
+            .and_then(|policy| get_policy_response_for_owner(policy, &get_caller_id())),
+    )
 }
 
 #[ic_cdk_macros::update]
 pub fn delete_policy(policy_id: String) -> Result<(), SmartVaultErr> {
-    delete_policy_impl(policy_id, get_caller_id())
+    track_activity_on_success(
+        &get_caller_id(),
+        delete_policy_impl(policy_id, get_caller_id()),
+    )
+}
+
+#[ic_cdk_macros::update]
+pub async fn create_condition_template(
+    args: CreateConditionTemplateArgs,
+) -> Result<ConditionTemplate, SmartVaultErr> {
+    track_activity_on_success(
+        &get_caller_id(),
+        create_condition_template_impl(args, get_caller_id()).await,
+    )
+}
+
+/// Lists the built-in condition templates and the templates of the caller
+#[ic_cdk_macros::query]
+pub fn get_condition_template_list() -> Result<Vec<ConditionTemplate>, SmartVaultErr> {
+    get_condition_template_list_impl(get_caller_id())
+}
+
+/// Updates a condition template and all policies linked to it
+#[ic_cdk_macros::update]
+pub async fn update_condition_template(
+    args: UpdateConditionTemplateArgs,
+) -> Result<ConditionTemplateUpdate, SmartVaultErr> {
+    track_activity_on_success(
+        &get_caller_id(),
+        update_condition_template_impl(args, get_caller_id()).await,
+    )
+}
+
+#[ic_cdk_macros::update]
+pub fn delete_condition_template(template_id: ConditionTemplateID) -> Result<(), SmartVaultErr> {
+    track_activity_on_success(
+        &get_caller_id(),
+        delete_condition_template_impl(template_id, get_caller_id()),
+    )
+}
+
+/// Replaces the conditions of a policy with the conditions of a template
+#[ic_cdk_macros::update]
+pub async fn apply_condition_template(
+    args: ApplyConditionTemplateArgs,
+) -> Result<PolicyWithSecretListEntries, SmartVaultErr> {
+    track_activity_on_success(
+        &get_caller_id(),
+        apply_condition_template_impl(args, get_caller_id())
+            .await
+            .and_then(|policy| get_policy_response_for_owner(policy, &get_caller_id())),
+    )
 }
 
 #[ic_cdk_macros::update]
 pub fn confirm_x_out_of_y_condition(
     args: ConfirmXOutOfYConditionArgs,
 ) -> Result<(), SmartVaultErr> {
-    confirm_x_out_of_y_condition_impl(args, get_caller_id())
+    track_activity_on_success(
+        &get_caller_id(),
+        confirm_x_out_of_y_condition_impl(args, get_caller_id()),
+    )
+}
+
+/// Commits to a vote of a secret ballot without disclosing it
+#[ic_cdk_macros::update]
+pub fn commit_x_out_of_y_vote(args: CommitXOutOfYVoteArgs) -> Result<(), SmartVaultErr> {
+    track_activity_on_success(
+        &get_caller_id(),
+        commit_x_out_of_y_vote_impl(args, get_caller_id()),
+    )
+}
+
+/// Reveals a committed vote of a secret ballot once the reveal phase has started
+#[ic_cdk_macros::update]
+pub fn reveal_x_out_of_y_vote(args: RevealXOutOfYVoteArgs) -> Result<(), SmartVaultErr> {
+    track_activity_on_success(
+        &get_caller_id(),
+        reveal_x_out_of_y_vote_impl(args, get_caller_id()),
+    )
+}
+
+/// Cancels a pending policy release during the cooling-off period
+#[ic_cdk_macros::update]
+pub fn veto_policy_release(
+    policy_id: PolicyID,
+) -> Result<PolicyWithSecretListEntries, SmartVaultErr> {
+    track_activity_on_success(
+        &get_caller_id(),
+        veto_policy_release_impl(policy_id, get_caller_id()).map(PolicyWithSecretListEntries::from),
+    )
+}
+
+/// Sets the challenge a beneficiary has to answer before its secrets are revealed
+#[ic_cdk_macros::update]
+pub async fn set_beneficiary_challenge(
+    args: SetBeneficiaryChallengeArgs,
+) -> Result<PolicyWithSecretListEntries, SmartVaultErr> {
+    track_activity_on_success(
+        &get_caller_id(),
+        set_beneficiary_challenge_impl(args, get_caller_id())
+            .await
+            .and_then(|policy| get_policy_response_for_owner(policy, &get_caller_id())),
+    )
+}
+
+#[ic_cdk_macros::update]
+pub fn remove_beneficiary_challenge(
+    policy_id: PolicyID,
+    beneficiary: PrincipalID,
+) -> Result<PolicyWithSecretListEntries, SmartVaultErr> {
+    track_activity_on_success(
+        &get_caller_id(),
+        remove_beneficiary_challenge_impl(policy_id, beneficiary, get_caller_id())
+            .and_then(|policy| get_policy_response_for_owner(policy, &get_caller_id())),
+    )
+}
+
+/// A beneficiary answers its challenge and gets a time-limited token to access the secrets
+#[ic_cdk_macros::update]
+pub async fn answer_beneficiary_challenge(
+    args: AnswerBeneficiaryChallengeArgs,
+) -> Result<ChallengeToken, SmartVaultErr> {
+    track_activity_on_success(
+        &get_caller_id(),
+        answer_beneficiary_challenge_impl(args, get_caller_id()).await,
+    )
+}
+
+/// A beneficiary claims that the owner has passed away
+#[ic_cdk_macros::update]
+pub fn file_claim(args: FileClaimArgs) -> Result<(), SmartVaultErr> {
+    track_activity_on_success(&get_caller_id(), file_claim_impl(args, get_caller_id()))
+}
+
+/// Sets a triggered condition of a policy back to false
+#[ic_cdk_macros::update]
+pub fn reset_policy_condition(
+    policy_id: PolicyID,
+    condition_id: ConditionID,
+) -> Result<PolicyWithSecretListEntries, SmartVaultErr> {
+    track_activity_on_success(
+        &get_caller_id(),
+        reset_policy_condition_impl(policy_id, condition_id, get_caller_id())
+            .and_then(|policy| get_policy_response_for_owner(policy, &get_caller_id())),
+    )
+}
+
+/// Sets all conditions of a policy back to false
+#[ic_cdk_macros::update]
+pub fn reset_policy_conditions(
+    policy_id: PolicyID,
+) -> Result<PolicyWithSecretListEntries, SmartVaultErr> {
+    track_activity_on_success(
+        &get_caller_id(),
+        reset_policy_conditions_impl(policy_id, get_caller_id())
+            .and_then(|policy| get_policy_response_for_owner(policy, &get_caller_id())),
+    )
+}
+
+/// Proof of life of the owner, resets the deadlines of all check-in conditions
+#[ic_cdk_macros::update]
+pub fn check_in() -> Result<(), SmartVaultErr> {
+    track_activity_on_success(&get_caller_id(), check_in_impl(get_caller_id()))
 }
 
 /**
@@ -187,7 +457,7 @@ pub fn confirm_x_out_of_y_condition(
  */
 #[ic_cdk_macros::update]
 pub fn create_contact(args: CreateContactArgs) -> Result<Contact, SmartVaultErr> {
-    crate_contact_impl(args, get_caller_id())
+    track_activity_on_success(&get_caller_id(), crate_contact_impl(args, get_caller_id()))
 }
 
 #[ic_cdk_macros::query]
@@ -197,12 +467,53 @@ pub fn get_contact_list() -> Result<Vec<Contact>, SmartVaultErr> {
 
 #[ic_cdk_macros::update]
 pub fn update_contact(c: Contact) -> Result<Contact, SmartVaultErr> {
-    update_contact_impl(c, get_caller_id())
+    track_activity_on_success(&get_caller_id(), update_contact_impl(c, get_caller_id()))
+}
+
+/// Postpones the inactivity deadlines of an owner who made the caller a deadline extender
+#[ic_cdk_macros::update]
+pub fn extend_inactivity_deadline(
+    args: ExtendDeadlineArgs,
+) -> Result<DeadlineExtension, SmartVaultErr> {
+    track_activity_on_success(
+        &get_caller_id(),
+        extend_inactivity_deadline_impl(args, get_caller_id()),
+    )
 }
 
 #[ic_cdk_macros::update]
 pub fn delete_contact(contact: PrincipalID) -> Result<(), SmartVaultErr> {
-    delete_contact_impl(contact, get_caller_id())
+    track_activity_on_success(
+        &get_caller_id(),
+        delete_contact_impl(contact, get_caller_id()),
+    )
+}
+
+/// Allows oracle conditions to call the canister (controllers only)
+#[ic_cdk_macros::update]
+pub fn add_oracle(canister_id: PrincipalID) -> Result<(), SmartVaultErr> {
+    check_caller_is_controller()?;
+    add_oracle_impl(canister_id)
+}
+
+/// Stops calling the canister from oracle conditions (controllers only)
+#[ic_cdk_macros::update]
+pub fn remove_oracle(canister_id: PrincipalID) -> Result<(), SmartVaultErr> {
+    check_caller_is_controller()?;
+    remove_oracle_impl(canister_id)
+}
+
+/// Gets the canisters which can be used in oracle conditions
+#[ic_cdk_macros::query]
+pub fn get_oracle_list() -> Vec<PrincipalID> {
+    get_oracle_list_impl()
+}
+
+fn check_caller_is_controller() -> Result<(), SmartVaultErr> {
+    if !ic_cdk::api::is_controller(&get_caller()) {
+        return Err(SmartVaultErr::Unauthorized);
+    }
+    Ok(())
 }
 
 pub fn get_caller_id() -> PrincipalID {
@@ -213,7 +524,9 @@ pub fn get_caller_id() -> PrincipalID {
 fn pre_upgrade() {}
 
 #[post_upgrade]
-fn post_upgrade() {}
+fn post_upgrade() {
+    migrate_conditions();
+}
 
 #[cfg(test)]
 mod tests {}
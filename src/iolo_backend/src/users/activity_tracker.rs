@@ -0,0 +1,78 @@
+use std::cell::RefCell;
+
+use crate::{
+    common::error::SmartVaultErr,
+    policies::{
+        conditions::ActivityType,
+        conditions_manager::{clear_votes_on_owner_activity, rearm_dead_mans_switch},
+    },
+    smart_vaults::smart_vault::USER_STORE,
+    users::{user::PrincipalID, user_store::UserStore},
+};
+
+/// Records that the caller has been active. Called by the update endpoints so that
+/// LastLogin conditions can count any authenticated activity and not only explicit logins.
+/// Callers without a user account are ignored.
+pub fn track_activity(caller: &PrincipalID) {
+    let tracked = USER_STORE.with(|ur: &RefCell<UserStore>| {
+        let mut user_store = ur.borrow_mut();
+        user_store.update_user_activity_date(caller).is_ok()
+    });
+    if tracked {
+        rearm_dead_mans_switch(caller).ok();
+        clear_votes_on_owner_activity(caller, ActivityType::AnyActivity).ok();
+    }
+}
+
+/// Records the activity of the caller once the call has succeeded and passes its result on.
+/// Failed calls, e.g. of unauthorized callers, are no sign of life.
+pub fn track_activity_on_success<T>(
+    caller: &PrincipalID,
+    result: Result<T, SmartVaultErr>,
+) -> Result<T, SmartVaultErr> {
+    if result.is_ok() {
+        track_activity(caller);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use candid::Principal;
+
+    use crate::common::error::SmartVaultErr;
+    use crate::users::{
+        activity_tracker::{track_activity, track_activity_on_success},
+        user::AddOrUpdateUserArgs,
+        users_interface_impl::{create_user_impl, get_current_user_impl},
+    };
+
+    #[tokio::test]
+    async fn utest_track_activity() {
+        let principal = Principal::from_slice(&[7; 29]).to_string();
+        let aua = AddOrUpdateUserArgs {
+            name: Some("Alice".to_string()),
+            email: None,
+            user_type: None,
+        };
+        let user = create_user_impl(aua, principal.clone()).await.unwrap();
+        let last_activity = user.date_last_activity().unwrap();
+
+        track_activity(&principal);
+        let user = get_current_user_impl(principal.clone()).unwrap();
+        assert!(user.date_last_activity().unwrap() > last_activity);
+        assert_eq!(user.date_last_login(), &Some(last_activity));
+
+        // failed calls are not recorded
+        let result: Result<(), SmartVaultErr> = Err(SmartVaultErr::Unauthorized);
+        assert!(track_activity_on_success(&principal, result).is_err());
+        let failed = get_current_user_impl(principal.clone()).unwrap();
+        assert_eq!(failed.date_last_activity(), user.date_last_activity());
+        assert!(track_activity_on_success(&principal, Ok(())).is_ok());
+        let succeeded = get_current_user_impl(principal.clone()).unwrap();
+        assert!(succeeded.date_last_activity() > user.date_last_activity());
+
+        // unknown callers are ignored
+        track_activity(&Principal::anonymous().to_string());
+    }
+}
@@ -10,6 +10,15 @@ pub struct Contact {
     pub name: Option<String>,
     pub email: Option<String>,
     pub user_type: Option<UserType>,
+    pub role: Option<ContactRole>,
+}
+
+/// Role of a contact with respect to the user, no role grants no rights
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone, Copy, PartialEq)]
+pub enum ContactRole {
+    /// Can postpone the user's inactivity deadlines, e.g. while the user is in hospital,
+    /// without getting any access to secrets
+    DeadlineExtender,
 }
 
 #[derive(Debug, CandidType, Deserialize, Serialize, Clone)]
@@ -18,6 +27,7 @@ pub struct CreateContactArgs {
     pub name: Option<String>,
     pub email: Option<String>,
     pub user_type: Option<UserType>,
+    pub role: Option<ContactRole>,
 }
 
 impl From<CreateContactArgs> for Contact {
@@ -27,6 +37,7 @@ impl From<CreateContactArgs> for Contact {
             name: aca.name,
             email: aca.email,
             user_type: aca.user_type,
+            role: aca.role,
         }
     }
 }
@@ -64,6 +75,7 @@ mod tests {
             name: None,
             email: None,
             user_type: None,
+            role: None,
         };
         hs.insert(contact.clone());
 
@@ -0,0 +1,315 @@
+//! The dead man's switch of a user combines inactivity, check-in and validator conditions on account level.
+//! Policies reference it with a dead man's switch condition instead of copying these conditions,
+//! so that the switch is configured once and evaluated once per user by the timer.
+
+use candid::{CandidType, Deserialize};
+use serde::Serialize;
+
+use crate::common::error::SmartVaultErr;
+use crate::policies::conditions::{Condition, ConditionID, ConditionUpdate, UpdateCondition};
+use crate::policies::policy::LogicalOperator;
+
+use super::user::{PrincipalID, User};
+
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone)]
+pub struct DeadMansSwitch {
+    pub conditions: Vec<Condition>,
+    pub conditions_logical_operator: Option<LogicalOperator>,
+    /// Time at which the conditions of the switch have been met
+    pub triggered_at: Option<u64>,
+    pub date_modified: u64,
+}
+
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone)]
+pub struct UpdateDeadMansSwitchArgs {
+    pub conditions: Vec<UpdateCondition>,
+    pub conditions_logical_operator: Option<LogicalOperator>,
+}
+
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone)]
+pub struct ConfirmDeadMansSwitchArgs {
+    pub owner: PrincipalID,
+    pub condition_id: ConditionID,
+    pub status: bool,
+}
+
+/// Dead man's switch of an owner as seen by one of its validators
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone)]
+pub struct DeadMansSwitchForValidator {
+    pub owner: PrincipalID,
+    pub conditions: Vec<Condition>,
+}
+
+impl UpdateDeadMansSwitchArgs {
+    /// Only inactivity, check-in and validator conditions can be part of a switch
+    pub fn validate(&self) -> Result<(), SmartVaultErr> {
+        if self.conditions.is_empty() {
+            return Err(SmartVaultErr::InvalidDeadMansSwitch(
+                "A switch needs at least one condition".to_string(),
+            ));
+        }
+        if self.conditions.len() < 2 && self.conditions_logical_operator.is_some() {
+            return Err(SmartVaultErr::LogicalOperatorWithLessThanTwoConditions);
+        }
+        for condition in self.conditions.iter() {
+            match condition {
+                UpdateCondition::LastLogin(c) => c.validate()?,
+                UpdateCondition::CheckIn(c) => {
+                    if !c.schedule.is_valid() {
+                        return Err(SmartVaultErr::InvalidCheckInSchedule(format!(
+                            "{:?}",
+                            c.schedule
+                        )));
+                    }
+                }
+                UpdateCondition::XOutOfY(c) => {
+                    if c.secret_ballot_days.is_some() || !c.backup_validators.is_empty() {
+                        return Err(SmartVaultErr::InvalidDeadMansSwitch(
+                            "Secret ballots and backup validators are not supported".to_string(),
+                        ));
+                    }
+                    c.validate()?;
+                }
+                _ => {
+                    return Err(SmartVaultErr::InvalidDeadMansSwitch(
+                        "Only inactivity, check-in and validator conditions are supported"
+                            .to_string(),
+                    ))
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl DeadMansSwitch {
+    /// Creates the switch from the update args. Conditions of the existing switch keep their id
+    /// but are re-armed, since the owner editing the switch is alive.
+    pub async fn from_update(
+        args: UpdateDeadMansSwitchArgs,
+        existing: Option<DeadMansSwitch>,
+        now: u64,
+    ) -> Result<Self, SmartVaultErr> {
+        let existing_conditions = existing.map(|s| s.conditions).unwrap_or_default();
+        let mut conditions: Vec<Condition> = vec![];
+        for update in args.conditions {
+            let condition = match update.id() {
+                Some(id) => {
+                    let mut existing = existing_conditions
+                        .iter()
+                        .find(|c| c.id() == id)
+                        .cloned()
+                        .ok_or_else(|| {
+                        SmartVaultErr::PolicyConditionDoesNotExist(id.clone())
+                    })?;
+                    let mut updated = existing.update_condition(update);
+                    updated.reset(now);
+                    updated
+                }
+                None => Condition::from_update_condition(update).await,
+            };
+            conditions.push(condition);
+        }
+
+        Ok(Self {
+            conditions,
+            conditions_logical_operator: args.conditions_logical_operator,
+            triggered_at: None,
+            date_modified: now,
+        })
+    }
+
+    /// Whether the conditions of the switch are met at `at`, a triggered switch stays triggered
+    pub fn met_at(&self, user: &User, at: u64) -> bool {
+        if self.triggered_at.is_some() {
+            return true;
+        }
+        let mut statuses = self
+            .conditions
+            .iter()
+            .map(|c| c.get_condition_status() || c.evaluate_at(Some(user), at));
+        match self.conditions_logical_operator {
+            Some(LogicalOperator::Or) => statuses.any(|s| s),
+            _ => !self.conditions.is_empty() && statuses.all(|s| s),
+        }
+    }
+
+    /// Evaluates the conditions once for all policies of the user.
+    /// Returns true if the switch has been changed.
+    pub fn evaluate(&mut self, user: &User, now: u64) -> bool {
+        if self.triggered_at.is_some() {
+            return false;
+        }
+        let mut changed = false;
+        for condition in self.conditions.iter_mut() {
+            if let Condition::XOutOfY(cond) = condition {
+                changed |= cond.expire_votes(now);
+            }
+            if !condition.get_condition_status() && condition.evaluate_at(Some(user), now) {
+                condition.set_condition_status(true);
+                changed = true;
+            }
+        }
+        if self.met_at(user, now) {
+            self.triggered_at = Some(now);
+            changed = true;
+        }
+        changed
+    }
+
+    /// Re-arms the switch and all of its conditions, e.g. because the owner has shown to be alive
+    /// or a release based on the switch has been vetoed
+    pub fn reset(&mut self, now: u64) {
+        self.triggered_at = None;
+        for condition in self.conditions.iter_mut() {
+            condition.reset(now);
+        }
+    }
+
+    /// Removes a validator who deleted their account from the validator conditions of the switch.
+    /// Switches have no backup validators. Returns true if the switch has been changed.
+    pub fn remove_validator(
+        &mut self,
+        validator: &PrincipalID,
+        is_reachable: impl Fn(&PrincipalID) -> bool,
+        now: u64,
+    ) -> bool {
+        let mut removed = false;
+        for condition in self.conditions.iter_mut() {
+            if let Condition::XOutOfY(xooy) = condition {
+                if xooy.validators.iter().any(|v| &v.principal_id == validator) {
+                    xooy.substitute_validator(validator, &is_reachable);
+                    xooy.update_quorum_health(&is_reachable, now);
+                    removed = true;
+                }
+            }
+        }
+        removed
+    }
+
+    /// Registers a check-in of the owner. Returns true if a check-in condition has been moved.
+    pub fn check_in(&mut self, now: u64) -> bool {
+        let mut checked_in = false;
+        for condition in self.conditions.iter_mut() {
+            checked_in |= condition.check_in(now);
+        }
+        checked_in
+    }
+
+    /// Validator conditions of the switch the validator votes on, without the votes of other validators
+    pub fn for_validator(
+        &self,
+        owner: &PrincipalID,
+        validator: &PrincipalID,
+    ) -> Option<DeadMansSwitchForValidator> {
+        let conditions: Vec<Condition> = self
+            .conditions
+            .iter()
+            .filter_map(|c| match c {
+                Condition::XOutOfY(xooy)
+                    if xooy.validators.iter().any(|v| &v.principal_id == validator) =>
+                {
+                    let mut xooy = xooy.clone();
+                    xooy.validators.retain(|v| &v.principal_id == validator);
+                    if let Some(vote_evidence) = xooy.vote_evidence.as_mut() {
+                        vote_evidence.retain(|principal_id, _| principal_id == validator);
+                    }
+                    if let Some(vote_clearances) = xooy.vote_clearances.as_mut() {
+                        vote_clearances.retain(|principal_id, _| principal_id == validator);
+                    }
+                    Some(Condition::XOutOfY(xooy))
+                }
+                _ => None,
+            })
+            .collect();
+
+        if conditions.is_empty() {
+            return None;
+        }
+        Some(DeadMansSwitchForValidator {
+            owner: owner.to_string(),
+            conditions,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::policies::conditions::{
+        CheckInCondition, CheckInSchedule, Condition, Duration, LastLoginTimeCondition,
+    };
+    use crate::policies::policy::LogicalOperator;
+    use crate::users::user::{AddOrUpdateUserArgs, User};
+    use crate::utils::time::NANOS_PER_DAY;
+
+    use super::DeadMansSwitch;
+
+    #[test]
+    fn utest_dead_mans_switch() {
+        let t0: u64 = 1_700_000_000 * 1_000_000_000;
+        let mut user = User::new(
+            "owner".to_string(),
+            AddOrUpdateUserArgs {
+                name: None,
+                email: None,
+                user_type: None,
+            },
+        );
+        user.date_last_login = Some(t0);
+        user.date_last_activity = Some(t0);
+
+        let mut dead_mans_switch = DeadMansSwitch {
+            conditions: vec![
+                Condition::LastLogin(LastLoginTimeCondition {
+                    id: "last_login".to_string(),
+                    number_of_days_since_last_login: None,
+                    inactivity: Some(Duration::days(10)),
+                    activity_type: None,
+                    condition_status: false,
+                }),
+                Condition::CheckIn(CheckInCondition {
+                    id: "check_in".to_string(),
+                    schedule: CheckInSchedule::EveryNumberOfDays(30),
+                    grace_period_days: 0,
+                    last_check_in: t0,
+                    next_deadline: t0 + 30 * NANOS_PER_DAY,
+                    condition_status: false,
+                }),
+            ],
+            conditions_logical_operator: Some(LogicalOperator::Or),
+            triggered_at: None,
+            date_modified: t0,
+        };
+        assert!(!dead_mans_switch.met_at(&user, t0 + 1));
+        assert!(dead_mans_switch.met_at(&user, t0 + 11 * NANOS_PER_DAY));
+
+        // with And, the owner checking in keeps the switch armed
+        dead_mans_switch.conditions_logical_operator = Some(LogicalOperator::And);
+        assert!(!dead_mans_switch.met_at(&user, t0 + 11 * NANOS_PER_DAY));
+        assert!(dead_mans_switch.check_in(t0 + 20 * NANOS_PER_DAY));
+        assert!(!dead_mans_switch.met_at(&user, t0 + 31 * NANOS_PER_DAY));
+
+        // once triggered, the switch stays triggered
+        let now = t0 + 51 * NANOS_PER_DAY;
+        assert!(dead_mans_switch.evaluate(&user, now));
+        assert_eq!(dead_mans_switch.triggered_at, Some(now));
+        assert!(dead_mans_switch
+            .conditions
+            .iter()
+            .all(|c| c.get_condition_status()));
+        assert!(!dead_mans_switch.evaluate(&user, now + 1));
+        assert!(!dead_mans_switch.check_in(now + 1));
+        assert!(dead_mans_switch.met_at(&user, t0 + 1));
+
+        // until it is re-armed
+        user.date_last_login = Some(now + 1);
+        dead_mans_switch.reset(now + 1);
+        assert_eq!(dead_mans_switch.triggered_at, None);
+        assert!(dead_mans_switch
+            .conditions
+            .iter()
+            .all(|c| !c.get_condition_status()));
+        assert!(!dead_mans_switch.met_at(&user, now + 2));
+        assert!(!dead_mans_switch.evaluate(&user, now + 2));
+    }
+}
@@ -1,4 +1,6 @@
+pub mod activity_tracker;
 pub mod contact;
+pub mod dead_mans_switch;
 pub mod user;
 pub mod user_store;
 pub mod users_interface_impl;
@@ -10,7 +10,8 @@ use crate::{
     utils::time,
 };
 
-use super::contact::Contact;
+use super::contact::{Contact, ContactRole};
+use super::dead_mans_switch::DeadMansSwitch;
 
 /// Keybox stores the encrypted symmetric key (SK) needed to decrypt the secret's fields (e.g. password)
 /// For every secret, a vector of u8 is stored. The vector has the following format:
@@ -20,6 +21,33 @@ use super::contact::Contact;
 pub type KeyBox = BTreeMap<SecretID, Vec<u8>>;
 pub type PrincipalID = String;
 
+/// Maximum length of a vacation (in days)
+pub const MAX_VACATION_DAYS: u64 = 180;
+
+/// Maximum number of days deadline extenders can add to the inactivity deadlines since the user's last login
+pub const MAX_DEADLINE_EXTENSION_DAYS: u64 = 90;
+
+/// Postponement of the user's inactivity deadlines by a deadline extender, kept as audit trail
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone, PartialEq)]
+pub struct DeadlineExtension {
+    pub extended_by: PrincipalID,
+    pub days: u64,
+    pub date: u64,
+}
+
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone)]
+pub struct ExtendDeadlineArgs {
+    pub owner: PrincipalID,
+    pub days: u64,
+}
+
+/// Period in which the inactivity-based conditions of the user's policies are paused
+#[derive(Debug, CandidType, Deserialize, Serialize, Clone, PartialEq)]
+pub struct Vacation {
+    pub start: u64,
+    pub end: u64,
+}
+
 #[derive(Debug, CandidType, Deserialize, Serialize, Clone)]
 pub struct User {
     pub id: PrincipalID,
@@ -29,10 +57,18 @@ pub struct User {
     pub date_created: u64,
     pub date_modified: u64,
     pub date_last_login: Option<u64>,
+    /// Last authenticated update call of the user, including logins
+    pub date_last_activity: Option<u64>,
     pub contacts: HashSet<Contact>,
     pub secrets: HashSet<SecretID>,
     pub policies: HashSet<PolicyID>,
     pub key_box: KeyBox,
+    /// Vacations of the user, visible to the validators of the user's policies
+    pub vacations: Option<Vec<Vacation>>,
+    /// Postponements of the inactivity deadlines by deadline extenders
+    pub deadline_extensions: Option<Vec<DeadlineExtension>>,
+    /// Account-level conditions which policies can reference instead of defining their own
+    pub dead_mans_switch: Option<DeadMansSwitch>,
 }
 
 impl Storable for User {
@@ -79,10 +115,14 @@ impl From<AddOrUpdateUserArgs> for User {
             date_created: now,
             date_modified: now,
             date_last_login: None,
+            date_last_activity: None,
             contacts: HashSet::new(),
             secrets: HashSet::new(),
             policies: HashSet::new(),
             key_box: KeyBox::new(),
+            vacations: None,
+            deadline_extensions: None,
+            dead_mans_switch: None,
         }
     }
 }
@@ -105,10 +145,14 @@ impl User {
             date_created: now,
             date_modified: now,
             date_last_login: Some(now),
+            date_last_activity: Some(now),
             contacts: HashSet::new(),
             secrets: HashSet::new(),
             policies: HashSet::new(),
             key_box: KeyBox::new(),
+            vacations: None,
+            deadline_extensions: None,
+            dead_mans_switch: None,
         }
     }
 
@@ -128,6 +172,10 @@ impl User {
         &self.date_last_login
     }
 
+    pub fn date_last_activity(&self) -> &Option<u64> {
+        &self.date_last_activity
+    }
+
     pub fn add_secret(&mut self, secret_id: SecretID, encrypted_symmetric_key: Vec<u8>) {
         self.secrets.insert(secret_id.clone());
         self.key_box.insert(secret_id, encrypted_symmetric_key);
@@ -174,9 +222,123 @@ impl User {
         }
     }
 
+    pub fn vacations(&self) -> &[Vacation] {
+        self.vacations.as_deref().unwrap_or_default()
+    }
+
+    /// Time between `from` and `to` the user spent on vacation
+    pub fn paused_time(&self, from: u64, to: u64) -> u64 {
+        self.vacations()
+            .iter()
+            .map(|v| v.end.min(to).saturating_sub(v.start.max(from)))
+            .sum()
+    }
+
+    pub fn active_vacation(&self, now: u64) -> Option<&Vacation> {
+        self.vacations()
+            .iter()
+            .find(|v| v.start <= now && now < v.end)
+    }
+
+    pub fn is_deadline_extender(&self, principal: &PrincipalID) -> bool {
+        self.contacts
+            .iter()
+            .any(|c| &c.id == principal && c.role == Some(ContactRole::DeadlineExtender))
+    }
+
+    /// Time by which the inactivity deadlines are postponed by the extensions granted since `since`
+    pub fn deadline_extension(&self, since: u64) -> u64 {
+        self.extended_days(since)
+            .saturating_mul(time::NANOS_PER_DAY)
+    }
+
+    fn extended_days(&self, since: u64) -> u64 {
+        self.deadline_extensions
+            .iter()
+            .flatten()
+            .filter(|e| e.date >= since)
+            .map(|e| e.days)
+            .sum()
+    }
+
+    /// Postpones the inactivity deadlines on behalf of a deadline extender.
+    /// Extensions lapse with the next login of the user.
+    pub fn extend_deadline(
+        &mut self,
+        extender: &PrincipalID,
+        days: u64,
+        now: u64,
+    ) -> Result<DeadlineExtension, SmartVaultErr> {
+        if !self.is_deadline_extender(extender) {
+            return Err(SmartVaultErr::CallerNotDeadlineExtender(
+                self.id.to_string(),
+            ));
+        }
+        let extended_days = self.extended_days(self.date_last_login.unwrap_or(0));
+        if days == 0 || extended_days.saturating_add(days) > MAX_DEADLINE_EXTENSION_DAYS {
+            return Err(SmartVaultErr::InvalidDeadlineExtension(format!(
+                "Between 1 and {} days can be added since the last login",
+                MAX_DEADLINE_EXTENSION_DAYS - extended_days
+            )));
+        }
+
+        let extension = DeadlineExtension {
+            extended_by: extender.to_string(),
+            days,
+            date: now,
+        };
+        self.deadline_extensions
+            .get_or_insert_with(Vec::new)
+            .push(extension.clone());
+        self.date_modified = now;
+        Ok(extension)
+    }
+
+    /// Starts a vacation lasting until `until`. A running vacation is extended instead,
+    /// but never beyond the maximum length counted from its start.
+    pub fn start_vacation(&mut self, until: u64, now: u64) -> Result<Vacation, SmartVaultErr> {
+        if until <= now {
+            return Err(SmartVaultErr::InvalidVacation(
+                "A vacation has to end in the future".to_string(),
+            ));
+        }
+        let start = self.active_vacation(now).map_or(now, |v| v.start);
+        if until - start > MAX_VACATION_DAYS.saturating_mul(time::NANOS_PER_DAY) {
+            return Err(SmartVaultErr::InvalidVacation(format!(
+                "A vacation must not last longer than {} days",
+                MAX_VACATION_DAYS
+            )));
+        }
+
+        let vacation = Vacation { start, end: until };
+        let vacations = self.vacations.get_or_insert_with(Vec::new);
+        vacations.retain(|v| v.start != start);
+        vacations.push(vacation.clone());
+        self.date_modified = now;
+        Ok(vacation)
+    }
+
+    /// Ends the running vacation early
+    pub fn end_vacation(&mut self, now: u64) -> Result<(), SmartVaultErr> {
+        let Some(vacation) = self
+            .vacations
+            .iter_mut()
+            .flatten()
+            .find(|v| v.start <= now && now < v.end)
+        else {
+            return Err(SmartVaultErr::InvalidVacation(
+                "There is no running vacation".to_string(),
+            ));
+        };
+        vacation.end = now;
+        self.date_modified = now;
+        Ok(())
+    }
+
     pub fn update_login_date(&mut self) {
         let now = time::get_current_time();
         self.date_last_login = Some(now);
+        self.date_last_activity = Some(now);
         self.date_modified = now;
     }
 }
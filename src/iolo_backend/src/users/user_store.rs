@@ -14,7 +14,8 @@ use crate::{
 };
 
 use super::contact::Contact;
-use super::user::PrincipalID;
+use super::dead_mans_switch::DeadMansSwitch;
+use super::user::{DeadlineExtension, PrincipalID};
 
 #[derive(Serialize, Deserialize)]
 pub struct UserStore {
@@ -97,6 +98,7 @@ impl UserStore {
         if let Some(mut existing_user) = self.users.remove(caller) {
             let now = time::get_current_time();
             existing_user.date_last_login = Some(now);
+            existing_user.date_last_activity = Some(now);
             existing_user.date_modified = now;
             self.users.insert(caller.to_string(), existing_user.clone());
             Ok(existing_user)
@@ -105,6 +107,60 @@ impl UserStore {
         }
     }
 
+    pub fn start_vacation(
+        &mut self,
+        caller: &PrincipalID,
+        until: u64,
+    ) -> Result<User, SmartVaultErr> {
+        let mut user = self.get_user(caller)?;
+        user.start_vacation(until, time::get_current_time())?;
+        self.users.insert(caller.to_string(), user.clone());
+        Ok(user)
+    }
+
+    pub fn end_vacation(&mut self, caller: &PrincipalID) -> Result<User, SmartVaultErr> {
+        let mut user = self.get_user(caller)?;
+        user.end_vacation(time::get_current_time())?;
+        self.users.insert(caller.to_string(), user.clone());
+        Ok(user)
+    }
+
+    pub fn extend_deadline(
+        &mut self,
+        owner: &PrincipalID,
+        extender: &PrincipalID,
+        days: u64,
+    ) -> Result<DeadlineExtension, SmartVaultErr> {
+        let mut user = self.get_user(owner)?;
+        let extension = user.extend_deadline(extender, days, time::get_current_time())?;
+        self.users.insert(owner.to_string(), user);
+        Ok(extension)
+    }
+
+    pub fn update_dead_mans_switch(
+        &mut self,
+        caller: &PrincipalID,
+        dead_mans_switch: Option<DeadMansSwitch>,
+    ) -> Result<User, SmartVaultErr> {
+        let mut user = self.get_user(caller)?;
+        user.dead_mans_switch = dead_mans_switch;
+        self.users.insert(caller.to_string(), user.clone());
+        Ok(user)
+    }
+
+    pub fn update_user_activity_date(
+        &mut self,
+        caller: &PrincipalID,
+    ) -> Result<User, SmartVaultErr> {
+        if let Some(mut existing_user) = self.users.remove(caller) {
+            existing_user.date_last_activity = Some(time::get_current_time());
+            self.users.insert(caller.to_string(), existing_user.clone());
+            Ok(existing_user)
+        } else {
+            Err(SmartVaultErr::UserDoesNotExist(caller.to_string()))
+        }
+    }
+
     pub fn add_secret_to_user(
         &mut self,
         caller: &PrincipalID,
@@ -274,12 +330,15 @@ impl UserStore {
 
 #[cfg(test)]
 mod tests {
-    use candid::Principal;
+    use candid::{CandidType, Encode, Principal};
+    use ic_stable_structures::Storable;
+    use serde::Serialize;
+    use std::borrow::Cow;
 
     use crate::users::contact::Contact;
     use crate::{
         common::error::SmartVaultErr,
-        users::user::{AddOrUpdateUserArgs, User},
+        users::user::{AddOrUpdateUserArgs, KeyBox, User, UserType},
         users::user_store::UserStore,
     };
 
@@ -322,8 +381,68 @@ mod tests {
             name: Some("Testuser".to_string()),
             email: Some("test@me.com".to_string()),
             user_type: None,
+            role: None,
         };
         assert!(user_store.create_contact(&new_user.id, contact).is_ok());
         assert_eq!(user_store.get_contact_list(&new_user.id).unwrap().len(), 1);
     }
+
+    #[test]
+    fn utest_decode_user_stored_before_upgrade() {
+        // users as they have been stored before vacations and deadline extensions have been added
+        #[derive(CandidType, Serialize)]
+        struct StoredContact {
+            id: String,
+            name: Option<String>,
+            email: Option<String>,
+            user_type: Option<UserType>,
+        }
+        #[derive(CandidType, Serialize)]
+        struct StoredUser {
+            id: String,
+            name: Option<String>,
+            email: Option<String>,
+            user_type: Option<UserType>,
+            date_created: u64,
+            date_modified: u64,
+            date_last_login: Option<u64>,
+            contacts: Vec<StoredContact>,
+            secrets: Vec<String>,
+            policies: Vec<String>,
+            key_box: KeyBox,
+        }
+
+        let stored = StoredUser {
+            id: "user".to_string(),
+            name: None,
+            email: None,
+            user_type: None,
+            date_created: 0,
+            date_modified: 0,
+            date_last_login: Some(1),
+            contacts: vec![StoredContact {
+                id: "contact".to_string(),
+                name: None,
+                email: None,
+                user_type: None,
+            }],
+            secrets: vec![],
+            policies: vec!["policy".to_string()],
+            key_box: KeyBox::new(),
+        };
+
+        let mut user = User::from_bytes(Cow::Owned(Encode!(&stored).unwrap()));
+        assert_eq!(user.id(), "user");
+        assert_eq!(user.policies(), vec!["policy".to_string()]);
+        assert!(user.vacations().is_empty());
+        assert_eq!(user.paused_time(0, 10), 0);
+        assert_eq!(user.deadline_extension(0), 0);
+        assert!(!user.is_deadline_extender(&"contact".to_string()));
+        assert!(user.dead_mans_switch.is_none());
+
+        // the decoded user is stored in the current format
+        user.start_vacation(10, 2).unwrap();
+        let user = User::from_bytes(user.to_bytes());
+        assert_eq!(user.paused_time(0, 10), 8);
+    }
 }
@@ -1,10 +1,25 @@
 use std::cell::RefCell;
 
-use crate::{common::error::SmartVaultErr, smart_vaults::smart_vault::USER_STORE};
+use crate::{
+    common::error::SmartVaultErr,
+    policies::{
+        conditions::{ActivityType, Condition, UpdateCondition},
+        conditions_manager::{
+            clear_votes_on_owner_activity, evaluate_dead_mans_switch, rearm_dead_mans_switch,
+        },
+        policies_interface_impl::{get_policy_from_policy_store, remove_validator_from_policies},
+    },
+    smart_vaults::smart_vault::USER_STORE,
+    utils::time,
+};
 
 use super::{
     contact::{Contact, CreateContactArgs},
-    user::{AddOrUpdateUserArgs, PrincipalID, User},
+    dead_mans_switch::{
+        ConfirmDeadMansSwitchArgs, DeadMansSwitch, DeadMansSwitchForValidator,
+        UpdateDeadMansSwitchArgs,
+    },
+    user::{AddOrUpdateUserArgs, DeadlineExtension, ExtendDeadlineArgs, PrincipalID, User},
     user_store::UserStore,
 };
 
@@ -49,13 +64,151 @@ pub fn update_user_impl(
 
 pub fn update_user_login_date_impl(principal: PrincipalID) -> Result<User, SmartVaultErr> {
     // get current user
-    USER_STORE.with(|ur: &RefCell<UserStore>| -> Result<User, SmartVaultErr> {
+    let user = USER_STORE.with(|ur: &RefCell<UserStore>| -> Result<User, SmartVaultErr> {
         let mut user_store = ur.borrow_mut();
         user_store.update_user_login_date(&principal.to_string())
+    })?;
+
+    // the owner is alive, the dead man's switch is re-armed and pending votes of validators are void.
+    // The login has been recorded already, so a failure does not fail the call.
+    rearm_dead_mans_switch(&principal).ok();
+    clear_votes_on_owner_activity(&principal, ActivityType::ExplicitLogin).ok();
+    Ok(user)
+}
+
+/// Pauses the inactivity-based conditions of the user's policies until the given date
+pub fn start_vacation_impl(until: u64, principal: PrincipalID) -> Result<User, SmartVaultErr> {
+    USER_STORE.with(|ur: &RefCell<UserStore>| -> Result<User, SmartVaultErr> {
+        let mut user_store = ur.borrow_mut();
+        user_store.start_vacation(&principal, until)
+    })
+}
+
+pub fn end_vacation_impl(principal: PrincipalID) -> Result<User, SmartVaultErr> {
+    USER_STORE.with(|ur: &RefCell<UserStore>| -> Result<User, SmartVaultErr> {
+        let mut user_store = ur.borrow_mut();
+        user_store.end_vacation(&principal)
+    })
+}
+
+/// A deadline extender of the owner postpones the owner's inactivity deadlines
+pub fn extend_inactivity_deadline_impl(
+    args: ExtendDeadlineArgs,
+    caller: PrincipalID,
+) -> Result<DeadlineExtension, SmartVaultErr> {
+    USER_STORE.with(
+        |ur: &RefCell<UserStore>| -> Result<DeadlineExtension, SmartVaultErr> {
+            let mut user_store = ur.borrow_mut();
+            user_store.extend_deadline(&args.owner, &caller, args.days)
+        },
+    )
+}
+
+/// Sets the dead man's switch of the user, an existing switch is re-armed
+pub async fn update_dead_mans_switch_impl(
+    args: UpdateDeadMansSwitchArgs,
+    principal: PrincipalID,
+) -> Result<User, SmartVaultErr> {
+    args.validate()?;
+    // Check that the validators do exist
+    for condition in args.conditions.iter() {
+        if let UpdateCondition::XOutOfY(c) = condition {
+            for v in c.validators.iter() {
+                get_user_from_user_store(&v.principal_id)?;
+            }
+        }
+    }
+
+    let user = get_user_from_user_store(&principal)?;
+    let dead_mans_switch =
+        DeadMansSwitch::from_update(args, user.dead_mans_switch, time::get_current_time()).await?;
+    USER_STORE.with(|ur: &RefCell<UserStore>| -> Result<User, SmartVaultErr> {
+        let mut user_store = ur.borrow_mut();
+        user_store.update_dead_mans_switch(&principal, Some(dead_mans_switch))
+    })
+}
+
+/// Removes the dead man's switch of the user, as long as no policy references it
+pub fn delete_dead_mans_switch_impl(principal: PrincipalID) -> Result<User, SmartVaultErr> {
+    let user = get_user_from_user_store(&principal)?;
+    for policy_id in user.policies() {
+        let policy = get_policy_from_policy_store(&policy_id)?;
+        if policy
+            .conditions()
+            .iter()
+            .any(|c| matches!(c, Condition::DeadMansSwitch(_)))
+        {
+            return Err(SmartVaultErr::DeadMansSwitchInUse(policy_id));
+        }
+    }
+
+    USER_STORE.with(|ur: &RefCell<UserStore>| -> Result<User, SmartVaultErr> {
+        let mut user_store = ur.borrow_mut();
+        user_store.update_dead_mans_switch(&principal, None)
     })
 }
 
+/// A validator voting on a validator condition of the owner's dead man's switch
+pub fn confirm_dead_mans_switch_impl(
+    args: ConfirmDeadMansSwitchArgs,
+    caller: PrincipalID,
+) -> Result<(), SmartVaultErr> {
+    let owner = get_user_from_user_store(&args.owner)?;
+    let mut dead_mans_switch = owner
+        .dead_mans_switch
+        .clone()
+        .ok_or_else(|| SmartVaultErr::InvalidDeadMansSwitch(args.owner.to_string()))?;
+    let now = time::get_current_time();
+
+    match dead_mans_switch
+        .conditions
+        .iter_mut()
+        .find(|c| c.id() == args.condition_id)
+    {
+        Some(Condition::XOutOfY(xooy)) => {
+            xooy.expire_votes(now);
+            if !xooy.register_vote(&caller, args.status, now) {
+                return Err(SmartVaultErr::Unauthorized);
+            }
+        }
+        _ => {
+            return Err(SmartVaultErr::PolicyConditionDoesNotExist(
+                args.condition_id,
+            ))
+        }
+    }
+
+    dead_mans_switch.evaluate(&owner, now);
+    USER_STORE.with(|ur: &RefCell<UserStore>| -> Result<User, SmartVaultErr> {
+        let mut user_store = ur.borrow_mut();
+        user_store.update_dead_mans_switch(&args.owner, Some(dead_mans_switch))
+    })?;
+
+    // the policies referencing the switch follow right away
+    evaluate_dead_mans_switch(&owner.id)
+}
+
+/// Dead man's switches of all owners the caller is a validator of
+pub fn get_dead_mans_switch_list_as_validator_impl(
+    caller: PrincipalID,
+) -> Result<Vec<DeadMansSwitchForValidator>, SmartVaultErr> {
+    let users: Vec<User> = USER_STORE.with(|ur: &RefCell<UserStore>| ur.borrow().users());
+    Ok(users
+        .iter()
+        .filter_map(|u| {
+            u.dead_mans_switch
+                .as_ref()
+                .and_then(|s| s.for_validator(u.id(), &caller))
+        })
+        .collect())
+}
+
 pub fn delete_user_impl(principal: PrincipalID) -> Result<(), SmartVaultErr> {
+    get_user_from_user_store(&principal)?;
+
+    // backup validators take over the votes of the user, before the user is deleted
+    remove_validator_from_policies(&principal)?;
+
     // delete the user
     USER_STORE.with(|ur: &RefCell<UserStore>| -> Result<User, SmartVaultErr> {
         let mut user_store = ur.borrow_mut();
@@ -135,13 +288,13 @@ mod tests {
         common::error::SmartVaultErr,
         smart_vaults::smart_vault::USER_STORE,
         users::{
-            contact::CreateContactArgs,
-            user::AddOrUpdateUserArgs,
+            contact::{ContactRole, CreateContactArgs},
+            user::{AddOrUpdateUserArgs, ExtendDeadlineArgs, MAX_DEADLINE_EXTENSION_DAYS},
             user_store::UserStore,
             users_interface_impl::{
                 crate_contact_impl, create_user_impl, delete_contact_impl, delete_user_impl,
-                get_contact_list_impl, get_current_user_impl, update_contact_impl,
-                update_user_impl, update_user_login_date_impl,
+                extend_inactivity_deadline_impl, get_contact_list_impl, get_current_user_impl,
+                update_contact_impl, update_user_impl, update_user_login_date_impl,
             },
         },
     };
@@ -174,6 +327,7 @@ mod tests {
             name: Some("my contact".to_string()),
             email: None,
             user_type: None,
+            role: None,
         };
         let add_contact_result = crate_contact_impl(aca, principal.to_string());
         assert!(add_contact_result.is_ok());
@@ -234,6 +388,62 @@ mod tests {
         });
     }
 
+    #[tokio::test]
+    async fn itest_deadline_extender() {
+        let principal = create_principal().to_string();
+        let extender = create_principal().to_string();
+        let aua = AddOrUpdateUserArgs {
+            name: None,
+            email: None,
+            user_type: None,
+        };
+        create_user_impl(aua, principal.clone()).await.unwrap();
+        let aca = CreateContactArgs {
+            id: extender.clone(),
+            name: Some("sister".to_string()),
+            email: None,
+            user_type: None,
+            role: None,
+        };
+        let mut contact = crate_contact_impl(aca, principal.clone()).unwrap();
+        let extend = |days: u64| {
+            extend_inactivity_deadline_impl(
+                ExtendDeadlineArgs {
+                    owner: principal.clone(),
+                    days,
+                },
+                extender.clone(),
+            )
+        };
+
+        // a contact without the role cannot extend the deadline
+        let result = extend(30);
+        assert!(result.is_err_and(|e| matches!(e, SmartVaultErr::CallerNotDeadlineExtender(_))));
+
+        contact.role = Some(ContactRole::DeadlineExtender);
+        update_contact_impl(contact, principal.clone()).unwrap();
+        let extension = extend(30).unwrap();
+        assert_eq!(extension.extended_by, extender);
+        let result = extend(MAX_DEADLINE_EXTENSION_DAYS);
+        assert!(result.is_err_and(|e| matches!(e, SmartVaultErr::InvalidDeadlineExtension(_))));
+
+        // the audit trail is kept and the extensions lapse with the next login
+        let owner = get_current_user_impl(principal.clone()).unwrap();
+        assert_eq!(owner.deadline_extensions, Some(vec![extension.clone()]));
+        assert_eq!(
+            owner.deadline_extension(owner.date_last_login().unwrap()),
+            30 * crate::utils::time::NANOS_PER_DAY
+        );
+        std::thread::sleep(std::time::Duration::from_millis(1));
+        update_user_login_date_impl(principal.clone()).unwrap();
+        let owner = get_current_user_impl(principal.clone()).unwrap();
+        assert_eq!(
+            owner.deadline_extension(owner.date_last_login().unwrap()),
+            0
+        );
+        extend(MAX_DEADLINE_EXTENSION_DAYS).unwrap();
+    }
+
     pub fn create_principal() -> Principal {
         // create random u8
         let mut rng = rand::thread_rng();
@@ -13,6 +13,69 @@ cfg_if::cfg_if! {
     }
 }
 
+pub const NANOS_PER_SECOND: u64 = 1_000_000_000;
+pub const NANOS_PER_MINUTE: u64 = 60 * NANOS_PER_SECOND;
+pub const NANOS_PER_HOUR: u64 = 60 * NANOS_PER_MINUTE;
+pub const NANOS_PER_DAY: u64 = 86_400 * NANOS_PER_SECOND;
+
+/// Returns the number of days since 1970-01-01 for a date of the proleptic Gregorian calendar.
+/// See http://howardhinnant.github.io/date_algorithms.html for the algorithm.
+pub fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = (if y >= 0 { y } else { y - 399 }) / 400;
+    let yoe = y - era * 400; // [0, 399]
+    let m = month as i64;
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + day as i64 - 1; // [0, 365]
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy; // [0, 146096]
+    era * 146097 + doe - 719468
+}
+
+/// Inverse of `days_from_civil`: returns (year, month, day) for a number of days since 1970-01-01.
+pub fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = (if z >= 0 { z } else { z - 146096 }) / 146097;
+    let doe = z - era * 146097; // [0, 146096]
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365; // [0, 399]
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100); // [0, 365]
+    let mp = (5 * doy + 2) / 153; // [0, 11]
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = (if mp < 10 { mp + 3 } else { mp - 9 }) as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+pub fn is_leap_year(year: i64) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+pub fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        _ if is_leap_year(year) => 29,
+        _ => 28,
+    }
+}
+
+/// Adds calendar months to a point in time (in nanoseconds, UTC), keeping the time of day.
+/// Days beyond the length of the target month are clamped to its last day, e.g. Jan 31 + 1 month = Feb 28/29.
+pub fn add_months(time: u64, months: u64) -> u64 {
+    let (year, month, day) = civil_from_days((time / NANOS_PER_DAY) as i64);
+    let Some(total_months) = i64::try_from(months)
+        .ok()
+        .and_then(|months| (year * 12 + month as i64 - 1).checked_add(months))
+    else {
+        return u64::MAX;
+    };
+    let (year, month) = (total_months / 12, (total_months % 12) as u32 + 1);
+    let day = day.min(days_in_month(year, month));
+    u64::try_from(days_from_civil(year, month, day))
+        .ok()
+        .and_then(|days| days.checked_mul(NANOS_PER_DAY))
+        .and_then(|date| date.checked_add(time % NANOS_PER_DAY))
+        .unwrap_or(u64::MAX)
+}
+
 #[cfg(test)]
 mod tests {
     use std::thread;
@@ -33,4 +96,39 @@ mod tests {
         );
         assert!(time_1 > 1674990000000000000, "time_1 {} is either not in Nanoseconds (19 digits) or less than 1674990000000000000 (2023-01-29, 12:00", time_1);
     }
+
+    #[test]
+    fn utest_civil_dates() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(2023, 1, 29), 19386);
+        assert_eq!(
+            days_from_civil(2024, 3, 1) - days_from_civil(2024, 2, 28),
+            2
+        );
+        assert_eq!(civil_from_days(19386), (2023, 1, 29));
+        assert_eq!(
+            civil_from_days(days_from_civil(2040, 12, 31)),
+            (2040, 12, 31)
+        );
+        assert_eq!(days_in_month(2024, 2), 29);
+        assert_eq!(days_in_month(2100, 2), 28);
+        assert_eq!(days_in_month(2000, 2), 29);
+    }
+
+    #[test]
+    fn utest_add_months() {
+        let date = |year: i64, month: u32, day: u32| {
+            days_from_civil(year, month, day) as u64 * NANOS_PER_DAY
+        };
+        let noon = 12 * NANOS_PER_HOUR;
+        assert_eq!(
+            add_months(date(2024, 1, 31) + noon, 1),
+            date(2024, 2, 29) + noon
+        );
+        assert_eq!(add_months(date(2023, 1, 31), 1), date(2023, 2, 28));
+        assert_eq!(add_months(date(2024, 11, 15), 3), date(2025, 2, 15));
+        assert_eq!(add_months(date(2024, 2, 29), 12), date(2025, 2, 28));
+        assert_eq!(add_months(date(2024, 2, 29), 0), date(2024, 2, 29));
+        assert_eq!(add_months(date(2024, 1, 1), u64::MAX), u64::MAX);
+    }
 }
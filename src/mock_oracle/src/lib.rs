@@ -0,0 +1,46 @@
+//! This canister is a local stand-in for an oracle used by oracle conditions of the iolo backend.
+//! It implements the interface defined in iolo_backend/oracle.did and replies whatever has been
+//! configured with set_reply. It has to be added with add_oracle of the iolo backend first.
+
+use candid::{CandidType, Deserialize};
+use ic_cdk::{query, update};
+use std::cell::RefCell;
+
+#[derive(CandidType, Deserialize, Clone)]
+pub struct OracleRequest {
+    pub owner: String,
+    pub policy_id: String,
+    pub condition_id: String,
+}
+
+#[derive(CandidType, Deserialize, Clone)]
+pub enum OracleReply {
+    Status(bool),
+    LastActivity(u64),
+}
+
+thread_local! {
+    static REPLY: RefCell<Option<OracleReply>> = RefCell::new(None);
+    static REQUESTS: RefCell<Vec<OracleRequest>> = RefCell::new(Vec::new());
+}
+
+/// Sets the reply of proof_of_life. Without a reply, calls to proof_of_life fail.
+#[update]
+fn set_reply(reply: Option<OracleReply>) {
+    REPLY.with(|r| *r.borrow_mut() = reply);
+}
+
+#[update]
+fn proof_of_life(request: OracleRequest) -> OracleReply {
+    REQUESTS.with(|r| r.borrow_mut().push(request));
+    match REPLY.with(|r| r.borrow().clone()) {
+        Some(reply) => reply,
+        None => ic_cdk::trap("no reply configured"),
+    }
+}
+
+/// Returns all requests received so far
+#[query]
+fn get_requests() -> Vec<OracleRequest> {
+    REQUESTS.with(|r| r.borrow().clone())
+}